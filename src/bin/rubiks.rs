@@ -0,0 +1,431 @@
+// A command line tool to scramble, solve and check cubes without starting the game. It uses the same model and
+// solver as the game. The exit code is 0 on success, 1 if the cube could not be solved or is not solved, and 2 for
+// invalid input.
+
+use std::process::ExitCode;
+
+use serde_json::{json, Value};
+
+use rubiks_cube::model::{
+    algorithm_from_notation, algorithm_to_notation, create_random_scramble_sequence,
+    create_seeded_scramble_sequence, scramble_length,
+    solver::{self, SolveStrategy},
+    CubeSize, CubeState, MAX_CUBE_SIZE,
+};
+
+const USAGE: &str = "usage: rubiks <command> [options]
+
+commands:
+  scramble  --size <size> [--seed <seed>] [--length <moves>]
+  solve     --facelets <facelets> [--strategy kociemba|gods-algorithm]
+  apply     --alg <algorithm> [--from <facelets>] [--size <size>]
+  verify    --scramble <algorithm> --solution <algorithm> [--size <size>]
+
+options:
+  --json    print the result as JSON
+
+Facelets list the stickers of the faces in URFDLB order, for example UUUUUUUUURRRRRRRRR... for a solved cube.";
+
+const DEFAULT_CUBE_SIZE: usize = 3;
+/// Longer scrambles do not mix up a cube any better, and would only use up memory
+const MAX_SCRAMBLE_LENGTH: usize = 10_000;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let is_json = args.iter().any(|arg| arg == "--json");
+
+    match run(&args) {
+        Ok(report) => {
+            if is_json {
+                println!("{}", report.to_json());
+            } else {
+                println!("{}", report.plain);
+            }
+
+            if report.is_success {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::from(1)
+            }
+        }
+        Err(err) => {
+            if is_json {
+                println!("{}", json!({ "error": err }));
+            } else {
+                eprintln!("error: {err}\n\n{USAGE}");
+            }
+
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// The result of a command
+struct Report {
+    plain: String,
+    json: Value,
+    /// False if the command ran, but the cube could not be solved or is not solved
+    is_success: bool,
+}
+
+impl Report {
+    fn to_json(&self) -> String {
+        self.json.to_string()
+    }
+}
+
+/// The options of a command, in the format `--name value`
+struct Options {
+    values: Vec<(String, String)>,
+}
+
+impl Options {
+    fn parse(args: &[String], allowed: &[&str]) -> Result<Self, String> {
+        let mut values = vec![];
+        let mut args = args.iter().filter(|arg| *arg != "--json");
+
+        while let Some(arg) = args.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                return Err(format!("unexpected argument \"{arg}\""));
+            };
+            if !allowed.contains(&name) {
+                return Err(format!("unknown option --{name}"));
+            }
+
+            let Some(value) = args.next() else {
+                return Err(format!("missing value for --{name}"));
+            };
+            values.push((name.to_string(), value.clone()));
+        }
+
+        Ok(Self { values })
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .rev()
+            .find(|(option_name, _)| option_name == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn required(&self, name: &str) -> Result<&str, String> {
+        self.get(name).ok_or_else(|| format!("missing --{name}"))
+    }
+
+    fn parse_value<T: std::str::FromStr>(&self, name: &str) -> Result<Option<T>, String> {
+        self.get(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| format!("invalid value \"{value}\" for --{name}"))
+            })
+            .transpose()
+    }
+
+    /// Like `parse_value`, for numbers that can be at most `max`
+    fn parse_bounded(&self, name: &str, max: usize) -> Result<Option<usize>, String> {
+        let value = self.parse_value(name)?;
+        if value.is_some_and(|value| value > max) {
+            return Err(format!(
+                "invalid value \"{}\" for --{name}, it can be at most {max}",
+                self.get(name).unwrap_or_default()
+            ));
+        }
+
+        Ok(value)
+    }
+
+    fn cube_size(&self) -> Result<usize, String> {
+        let cube_size = self
+            .parse_bounded("size", MAX_CUBE_SIZE)?
+            .unwrap_or(DEFAULT_CUBE_SIZE);
+        if cube_size == 0 {
+            return Err("the cube size must be at least 1".to_string());
+        }
+
+        Ok(cube_size)
+    }
+}
+
+fn run(args: &[String]) -> Result<Report, String> {
+    let Some(command) = args.first() else {
+        return Err("missing command".to_string());
+    };
+    let args = &args[1..];
+
+    match command.as_str() {
+        "scramble" => scramble(&Options::parse(args, &["size", "seed", "length"])?),
+        "solve" => solve(&Options::parse(args, &["facelets", "strategy"])?),
+        "apply" => apply(&Options::parse(args, &["alg", "from", "size"])?),
+        "verify" => verify(&Options::parse(args, &["scramble", "solution", "size"])?),
+        command => Err(format!("unknown command \"{command}\"")),
+    }
+}
+
+fn scramble(options: &Options) -> Result<Report, String> {
+    let cube_size = options.cube_size()?;
+    let notation_cube_size = CubeSize(cube_size as i32);
+    let length = options
+        .parse_bounded("length", MAX_SCRAMBLE_LENGTH)?
+        .unwrap_or_else(|| scramble_length(&notation_cube_size));
+    let seed: Option<u64> = options.parse_value("seed")?;
+
+    let scramble = match seed {
+        Some(seed) => create_seeded_scramble_sequence(&notation_cube_size, length, seed),
+        None => create_random_scramble_sequence(&notation_cube_size, length),
+    };
+    let notation = algorithm_to_notation(&scramble, &notation_cube_size);
+
+    Ok(Report {
+        plain: notation.clone(),
+        json: json!({
+            "size": cube_size,
+            "seed": seed,
+            "scramble": notation,
+        }),
+        is_success: true,
+    })
+}
+
+fn solve(options: &Options) -> Result<Report, String> {
+    let cube_state = parse_facelets(options.required("facelets")?)?;
+    let strategy = match options.get("strategy").unwrap_or("kociemba") {
+        "kociemba" => SolveStrategy::Kociemba,
+        "gods-algorithm" => SolveStrategy::GodsAlgorithm,
+        strategy => return Err(format!("unknown strategy \"{strategy}\"")),
+    };
+
+    let solution = solver::get_solve_sequence(strategy, &cube_state);
+
+    // the solver returns an empty solution if it could not find one
+    let mut solved_state = cube_state.clone();
+    for rotation_event in &solution {
+        solved_state.handle_rotate_event(rotation_event);
+    }
+    let is_solved = solved_state.is_solved();
+
+    let notation = algorithm_to_notation(&solution, &CubeSize(cube_state.size() as i32));
+
+    Ok(Report {
+        plain: if is_solved {
+            notation.clone()
+        } else {
+            "no solution found".to_string()
+        },
+        json: json!({
+            "solved": is_solved,
+            "solution": notation,
+            "moves": solution.len(),
+        }),
+        is_success: is_solved,
+    })
+}
+
+fn apply(options: &Options) -> Result<Report, String> {
+    let mut cube_state = match options.get("from") {
+        Some(facelets) => parse_facelets(facelets)?,
+        None => CubeState::new(options.cube_size()?),
+    };
+    if options.get("from").is_some() && options.get("size").is_some() {
+        let cube_size = options.cube_size()?;
+        if cube_size != cube_state.size() {
+            return Err(format!(
+                "--size {cube_size} does not match the {0}x{0} cube of --from",
+                cube_state.size()
+            ));
+        }
+    }
+
+    apply_algorithm(&mut cube_state, options.required("alg")?, "--alg")?;
+
+    let facelets = cube_state.to_facelets();
+
+    Ok(Report {
+        plain: facelets.clone(),
+        json: json!({
+            "facelets": facelets,
+            "solved": cube_state.is_solved(),
+        }),
+        is_success: true,
+    })
+}
+
+fn verify(options: &Options) -> Result<Report, String> {
+    let mut cube_state = CubeState::new(options.cube_size()?);
+
+    apply_algorithm(&mut cube_state, options.required("scramble")?, "--scramble")?;
+    apply_algorithm(&mut cube_state, options.required("solution")?, "--solution")?;
+
+    let is_solved = cube_state.is_solved();
+
+    Ok(Report {
+        plain: if is_solved { "solved" } else { "not solved" }.to_string(),
+        json: json!({
+            "solved": is_solved,
+            "facelets": cube_state.to_facelets(),
+        }),
+        is_success: is_solved,
+    })
+}
+
+fn apply_algorithm(
+    cube_state: &mut CubeState,
+    algorithm: &str,
+    option: &str,
+) -> Result<(), String> {
+    let cube_size = CubeSize(cube_state.size() as i32);
+    let rotation_events = algorithm_from_notation(algorithm, &cube_size)
+        .map_err(|err| format!("invalid {option}: {err}"))?;
+
+    for rotation_event in &rotation_events {
+        cube_state.handle_rotate_event(rotation_event);
+    }
+
+    Ok(())
+}
+
+/// Parses facelets of any cube size, which follows from the number of facelets. This checks that every face has the
+/// right number of stickers, but not whether the cube can actually be solved.
+fn parse_facelets(facelets: &str) -> Result<CubeState, String> {
+    let facelets = facelets.trim();
+    let cube_state =
+        CubeState::parse_facelets(facelets).map_err(|err| format!("invalid facelets: {err}"))?;
+    let cube_size = cube_state.size();
+
+    for face in ['U', 'R', 'F', 'D', 'L', 'B'] {
+        if facelets.chars().filter(|facelet| *facelet == face).count() != cube_size * cube_size {
+            return Err(format!(
+                "invalid facelets: facelets must contain each face {} times",
+                cube_size * cube_size
+            ));
+        }
+    }
+
+    Ok(cube_state)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::{parse_facelets, run, Report};
+
+    fn run_command(args: &str) -> Result<Report, String> {
+        let args: Vec<String> = args.split(' ').map(str::to_string).collect();
+        run(&args)
+    }
+
+    fn run_with_values(command: &str, options: &[(&str, &str)]) -> Result<Report, String> {
+        let mut args = vec![command.to_string()];
+        for (name, value) in options {
+            args.push(format!("--{name}"));
+            args.push(value.to_string());
+        }
+
+        run(&args)
+    }
+
+    #[test]
+    fn test_seeded_scramble() {
+        let report = run_command("scramble --size 4 --seed 42").unwrap();
+        assert_eq!(30, report.plain.split(' ').count());
+        assert_eq!(
+            report.plain,
+            run_command("scramble --size 4 --seed 42").unwrap().plain
+        );
+        assert_eq!(
+            json!({ "size": 4, "seed": 42, "scramble": report.plain }),
+            serde_json::from_str::<Value>(&report.to_json()).unwrap()
+        );
+
+        assert_eq!(
+            5,
+            run_command("scramble --length 5")
+                .unwrap()
+                .plain
+                .split(' ')
+                .count()
+        );
+    }
+
+    #[test]
+    fn test_apply_and_verify() {
+        let scrambled = run_with_values("apply", &[("alg", "R U R' U'")]).unwrap();
+        assert_eq!(json!(false), scrambled.json["solved"]);
+
+        let solved = run_with_values(
+            "apply",
+            &[("alg", "U R U' R'"), ("from", scrambled.plain.as_str())],
+        )
+        .unwrap();
+        assert_eq!(
+            "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB",
+            solved.plain
+        );
+
+        let verified = run_with_values(
+            "verify",
+            &[("scramble", "R U2 F'"), ("solution", "F U2 R'")],
+        )
+        .unwrap();
+        assert!(verified.is_success);
+        assert_eq!("solved", verified.plain);
+
+        let not_verified =
+            run_with_values("verify", &[("scramble", "R U2 F'"), ("solution", "F U2 R")]).unwrap();
+        assert!(!not_verified.is_success);
+    }
+
+    #[test]
+    fn test_solve_solved_cube() {
+        let report = run_with_values("solve", &[("facelets", &"U".repeat(4 * 6))]);
+        assert_eq!(
+            Err("invalid facelets: facelets must contain each face 4 times".to_string()),
+            report.map(|_| ())
+        );
+
+        let solved = run_with_values("solve", &[("facelets", "UUUURRRRFFFFDDDDLLLLBBBB")]).unwrap();
+        assert!(solved.is_success);
+        assert_eq!("", solved.plain);
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert_eq!(Err("missing command".to_string()), run(&[]).map(|_| ()));
+        assert_eq!(
+            Err("unknown command \"shuffle\"".to_string()),
+            run_command("shuffle").map(|_| ())
+        );
+        assert_eq!(
+            Err("unknown option --seed".to_string()),
+            run_command("solve --seed 1").map(|_| ())
+        );
+        assert_eq!(
+            Err("missing value for --size".to_string()),
+            run_command("scramble --size").map(|_| ())
+        );
+        assert_eq!(
+            Err("invalid value \"four\" for --size".to_string()),
+            run_command("scramble --size four").map(|_| ())
+        );
+        assert_eq!(
+            Err("invalid --alg: unexpected characters in \"Rq\"".to_string()),
+            run_with_values("apply", &[("alg", "R Rq")]).map(|_| ())
+        );
+        assert_eq!(
+            Err("invalid value \"99999999999\" for --length, it can be at most 10000".to_string()),
+            run_command("scramble --length 99999999999").map(|_| ())
+        );
+        assert_eq!(
+            Err("invalid value \"1000000\" for --size, it can be at most 100".to_string()),
+            run_with_values("apply", &[("alg", "R"), ("size", "1000000")]).map(|_| ())
+        );
+        assert!(run_with_values(
+            "verify",
+            &[("scramble", "R"), ("solution", "R'"), ("size", "101")]
+        )
+        .is_err());
+        assert!(parse_facelets("UUUUU").is_err());
+    }
+}
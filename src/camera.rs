@@ -1,20 +1,405 @@
-use bevy::prelude::*;
+use std::f32::consts::{PI, TAU};
+
+use bevy::{
+    input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel},
+    picking::pointer::PointerInteraction,
+    prelude::*,
+    window::{PrimaryWindow, WindowResized},
+};
+
+use crate::{cube::Cube, schedules::CubeScheduleSet};
 
 pub struct CameraPlugin;
 
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_camera);
+        app.add_systems(Startup, spawn_camera)
+            .add_systems(
+                Update,
+                handle_orbit_camera_input.in_set(CubeScheduleSet::HandleUserInput),
+            )
+            .add_systems(
+                Update,
+                (frame_cube, update_orbit_camera_transform)
+                    .chain()
+                    .in_set(CubeScheduleSet::UpdateAnimations),
+            );
+    }
+}
+
+/// The radians to orbit per pixel that the mouse moves
+const ORBIT_SENSITIVITY: f32 = 0.005;
+/// Keep the camera from flipping over when orbiting over the top or bottom of the cube
+const MAX_PITCH: f32 = PI / 2.0 - 0.01;
+/// How far the user can zoom in, relative to the distance at which the cube is framed
+const MIN_ZOOM: f32 = 0.25;
+/// How far the user can zoom out, relative to the distance at which the cube is framed
+const MAX_ZOOM: f32 = 4.0;
+/// The distance to the cube, relative to the distance at which the cube would exactly fit on the screen. This
+/// leaves room for the interface.
+const FRAMING_MARGIN: f32 = 2.55;
+/// The fraction of the distance to zoom per scrolled line
+const ZOOM_PER_SCROLL_LINE: f32 = 0.1;
+/// How fast the camera moves to its target, higher is faster
+const TRANSITION_SPEED: f32 = 8.0;
+/// The maximum number of seconds between two clicks for them to count as a double click
+const DOUBLE_CLICK_SECONDS: f32 = 0.3;
+
+#[derive(Clone, Copy, Debug)]
+pub enum CameraView {
+    /// The view the camera starts with
+    Default,
+    Front,
+    Back,
+    Isometric,
+}
+
+impl CameraView {
+    /// Returns the yaw and pitch of the view, in radians
+    fn angles(&self) -> (f32, f32) {
+        match self {
+            CameraView::Default => (0.409, 0.405),
+            CameraView::Front => (0.0, 0.0),
+            CameraView::Back => (PI, 0.35),
+            // looking straight at a corner
+            CameraView::Isometric => (PI / 4.0, (1.0 / 3.0_f32.sqrt()).asin()),
+        }
+    }
+}
+
+/// Orbits around the center of the cube. The camera moves smoothly from its current position to its target position.
+#[derive(Component)]
+pub struct OrbitCamera {
+    yaw: f32,
+    pitch: f32,
+    distance: f32,
+    target_yaw: f32,
+    target_pitch: f32,
+    target_distance: f32,
+    /// True while the user is dragging to orbit
+    is_orbiting: bool,
+    /// Seconds since startup of the last click on the background
+    last_click_time: Option<f32>,
+    /// The distance at which the cube fits nicely on the screen
+    framed_distance: f32,
+    is_framed: bool,
+}
+
+/// The distance to use until the cube has been framed
+const DEFAULT_DISTANCE: f32 = 17.8;
+
+impl OrbitCamera {
+    fn new(view: CameraView, distance: f32) -> Self {
+        let (yaw, pitch) = view.angles();
+
+        Self {
+            yaw,
+            pitch,
+            distance,
+            target_yaw: yaw,
+            target_pitch: pitch,
+            target_distance: distance,
+            is_orbiting: false,
+            last_click_time: None,
+            framed_distance: distance,
+            is_framed: false,
+        }
+    }
+
+    /// Set the distance at which the cube fits nicely on the screen. Zooming by the user is kept relative to it.
+    fn frame(&mut self, framed_distance: f32) {
+        if self.is_framed {
+            self.target_distance *= framed_distance / self.framed_distance;
+        } else {
+            // the first time the camera should already be in place when the cube shows up
+            self.distance = framed_distance;
+            self.target_distance = framed_distance;
+            self.is_framed = true;
+        }
+
+        self.framed_distance = framed_distance;
+    }
+
+    /// Smoothly move to the given view, keeping the current distance
+    pub fn set_view(&mut self, view: CameraView) {
+        let (yaw, pitch) = view.angles();
+
+        // take the shortest way around
+        self.target_yaw = self.yaw + (yaw - self.yaw + PI).rem_euclid(TAU) - PI;
+        self.target_pitch = pitch;
+    }
+
+    fn reset(&mut self) {
+        self.set_view(CameraView::Default);
+        self.target_distance = self.framed_distance;
+    }
+
+    fn orbit(&mut self, delta: Vec2) {
+        self.yaw -= delta.x * ORBIT_SENSITIVITY;
+        self.pitch = (self.pitch + delta.y * ORBIT_SENSITIVITY).clamp(-MAX_PITCH, MAX_PITCH);
+
+        // orbiting is instant
+        self.target_yaw = self.yaw;
+        self.target_pitch = self.pitch;
+    }
+
+    fn zoom(&mut self, scrolled_lines: f32) {
+        self.target_distance =
+            (self.target_distance * (1.0 - scrolled_lines * ZOOM_PER_SCROLL_LINE)).clamp(
+                self.framed_distance * MIN_ZOOM,
+                self.framed_distance * MAX_ZOOM,
+            );
+    }
+
+    /// Move towards the target position. Returns false if the camera already was at its target position.
+    fn transition(&mut self, delta_secs: f32) -> bool {
+        let is_at_target = self.yaw == self.target_yaw
+            && self.pitch == self.target_pitch
+            && self.distance == self.target_distance;
+        if is_at_target {
+            return false;
+        }
+
+        let progress = 1.0 - (-TRANSITION_SPEED * delta_secs).exp();
+        let step = |current: f32, target: f32| {
+            let next = current + (target - current) * progress;
+            // snap to the target once the difference is not noticeable anymore
+            if (target - next).abs() < 0.0001 {
+                target
+            } else {
+                next
+            }
+        };
+
+        self.yaw = step(self.yaw, self.target_yaw);
+        self.pitch = step(self.pitch, self.target_pitch);
+        self.distance = step(self.distance, self.target_distance);
+
+        true
+    }
+
+    fn transform(&self) -> Transform {
+        let position = Vec3::new(
+            self.yaw.sin() * self.pitch.cos(),
+            self.pitch.sin(),
+            self.yaw.cos() * self.pitch.cos(),
+        ) * self.distance;
+
+        Transform::from_translation(position).looking_at(Vec3::ZERO, Vec3::Y)
     }
 }
 
 fn spawn_camera(mut commands: Commands) {
+    let orbit_camera = OrbitCamera::new(CameraView::Default, DEFAULT_DISTANCE);
+
     commands.spawn((
         Camera3d::default(),
         Camera {
             hdr: true,
             ..default()
         },
-        Transform::from_xyz(6.5, 7.0, 15.0).looking_at(Vec3::ZERO, Vec3::Y),
+        orbit_camera.transform(),
+        orbit_camera,
     ));
 }
+
+/// Dragging on the background orbits the camera, so that it does not conflict with dragging a face of the cube
+/// to turn it. Scrolling zooms and double clicking the background resets the camera.
+fn handle_orbit_camera_input(
+    mut orbit_camera_query: Query<&mut OrbitCamera>,
+    pointers: Query<&PointerInteraction>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    mut mouse_motion_events: EventReader<MouseMotion>,
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    time: Res<Time>,
+) {
+    let Ok(mut orbit_camera) = orbit_camera_query.get_single_mut() else {
+        error!("expected exactly 1 OrbitCamera component");
+        return;
+    };
+
+    if mouse_input.just_pressed(MouseButton::Left) {
+        let is_over_background = pointers
+            .iter()
+            .all(|interaction| interaction.get_nearest_hit().is_none());
+
+        if is_over_background {
+            orbit_camera.is_orbiting = true;
+
+            let now = time.elapsed_secs();
+            match orbit_camera.last_click_time {
+                Some(last_click_time) if now - last_click_time <= DOUBLE_CLICK_SECONDS => {
+                    orbit_camera.reset();
+                    orbit_camera.last_click_time = None;
+                }
+                _ => orbit_camera.last_click_time = Some(now),
+            }
+        }
+    }
+
+    if !mouse_input.pressed(MouseButton::Left) {
+        orbit_camera.is_orbiting = false;
+    }
+
+    let mouse_motion: Vec2 = mouse_motion_events.read().map(|event| event.delta).sum();
+    if orbit_camera.is_orbiting && mouse_motion != Vec2::ZERO {
+        orbit_camera.orbit(mouse_motion);
+    }
+
+    for event in mouse_wheel_events.read() {
+        let scrolled_lines = match event.unit {
+            MouseScrollUnit::Line => event.y,
+            // most devices scroll about 100 pixels per line
+            MouseScrollUnit::Pixel => event.y / 100.0,
+        };
+
+        orbit_camera.zoom(scrolled_lines);
+    }
+}
+
+/// The distance at which a sphere with the given radius exactly fits on the screen
+fn fit_distance(radius: f32, vertical_fov: f32, aspect_ratio: f32) -> f32 {
+    let horizontal_fov = 2.0 * ((vertical_fov / 2.0).tan() * aspect_ratio).atan();
+    let fov = vertical_fov.min(horizontal_fov);
+
+    radius / (fov / 2.0).sin()
+}
+
+/// Move the camera so that the cube takes up the same amount of space on the screen, no matter the cube size or the
+/// size of the window.
+fn frame_cube(
+    mut orbit_camera_query: Query<(&mut OrbitCamera, &Projection)>,
+    cube_query: Query<(Ref<Cube>, &Transform)>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut window_resized_events: EventReader<WindowResized>,
+) {
+    let is_window_resized = window_resized_events.read().count() > 0;
+
+    let Ok((cube, cube_transform)) = cube_query.get_single() else {
+        // the cube might be respawning
+        return;
+    };
+
+    if !cube.is_added() && !is_window_resized {
+        return;
+    }
+
+    let Ok((mut orbit_camera, projection)) = orbit_camera_query.get_single_mut() else {
+        error!("expected exactly 1 OrbitCamera component");
+        return;
+    };
+
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+
+    if window.height() == 0.0 {
+        // the window is minimized
+        return;
+    }
+
+    let vertical_fov = match projection {
+        Projection::Perspective(perspective_projection) => perspective_projection.fov,
+        Projection::Orthographic(_) => PerspectiveProjection::default().fov,
+    };
+
+    let cube_size = cube.size().0 as f32;
+    let cube_width =
+        cube_size * cube.piece_size() + (cube_size - 1.0) * cube.space_between_pieces();
+    // the cube can be looked at from any angle, so we fit the sphere around it
+    let radius = cube_width * 3.0_f32.sqrt() / 2.0 * cube_transform.scale.max_element();
+
+    orbit_camera.frame(
+        fit_distance(radius, vertical_fov, window.width() / window.height()) * FRAMING_MARGIN,
+    );
+}
+
+fn update_orbit_camera_transform(
+    mut orbit_camera_query: Query<(&mut OrbitCamera, &mut Transform)>,
+    time: Res<Time>,
+) {
+    let Ok((mut orbit_camera, mut transform)) = orbit_camera_query.get_single_mut() else {
+        error!("expected exactly 1 OrbitCamera component");
+        return;
+    };
+
+    // orbiting does not move towards a target, so we always update the transform
+    if orbit_camera.transition(time.delta_secs()) || orbit_camera.is_orbiting {
+        *transform = orbit_camera.transform();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use bevy::math::Vec3;
+
+    use super::{
+        fit_distance, CameraView, OrbitCamera, DEFAULT_DISTANCE, FRAMING_MARGIN, MAX_ZOOM,
+    };
+
+    #[test]
+    fn test_default_view_matches_original_camera_position() {
+        let orbit_camera = OrbitCamera::new(CameraView::Default, DEFAULT_DISTANCE);
+
+        let position = orbit_camera.transform().translation;
+        assert!(position.distance(Vec3::new(6.5, 7.0, 15.0)) < 0.1);
+    }
+
+    #[test]
+    fn test_set_view_takes_the_shortest_way_around() {
+        let mut orbit_camera = OrbitCamera::new(CameraView::Front, DEFAULT_DISTANCE);
+        orbit_camera.yaw = 2.0 * PI - 0.1;
+
+        orbit_camera.set_view(CameraView::Front);
+        assert!((orbit_camera.target_yaw - 2.0 * PI).abs() < 0.0001);
+
+        // the transition ends at the target
+        for _ in 0..1000 {
+            orbit_camera.transition(0.1);
+        }
+        assert!(!orbit_camera.transition(0.1));
+        assert_eq!(orbit_camera.yaw, orbit_camera.target_yaw);
+    }
+
+    #[test]
+    fn test_zoom_is_clamped() {
+        let mut orbit_camera = OrbitCamera::new(CameraView::Default, DEFAULT_DISTANCE);
+
+        orbit_camera.zoom(-1000.0);
+        assert_eq!(DEFAULT_DISTANCE * MAX_ZOOM, orbit_camera.target_distance);
+    }
+
+    #[test]
+    fn test_fit_distance() {
+        // a 3x3 in the default window keeps the distance the camera used to have
+        let radius = 3.08 * 3.0_f32.sqrt() / 2.0;
+        let distance = fit_distance(radius, PI / 4.0, 1280.0 / 720.0) * FRAMING_MARGIN;
+        assert!((distance - DEFAULT_DISTANCE).abs() < 0.1);
+
+        // bigger cubes need more distance
+        assert!(fit_distance(radius * 2.0, PI / 4.0, 1280.0 / 720.0) > distance / FRAMING_MARGIN);
+
+        // narrow windows are limited by their width
+        assert!(
+            fit_distance(radius, PI / 4.0, 0.5) > fit_distance(radius, PI / 4.0, 1280.0 / 720.0)
+        );
+    }
+
+    #[test]
+    fn test_frame_keeps_zoom() {
+        let mut orbit_camera = OrbitCamera::new(CameraView::Default, DEFAULT_DISTANCE);
+
+        // the first framing is instant
+        orbit_camera.frame(10.0);
+        assert_eq!(10.0, orbit_camera.distance);
+
+        orbit_camera.zoom(-5.0);
+        assert_eq!(15.0, orbit_camera.target_distance);
+
+        orbit_camera.frame(20.0);
+        assert_eq!(30.0, orbit_camera.target_distance);
+        assert_eq!(10.0, orbit_camera.distance);
+    }
+}
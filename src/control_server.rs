@@ -0,0 +1,600 @@
+// A local server to drive the cube from scripts and other tools. It is off by default and gets started with the
+// `--control-port <port>` command line argument. The server only accepts connections from this machine.
+//
+// Every line a client sends is a JSON request, and every request gets a JSON response on a single line:
+//
+//     {"id": 1, "command": "apply", "moves": "R U R' U'"}
+//     {"id": 1, "ok": true, "moves": "R U R' U'"}
+//
+// Commands: `apply` (moves, optionally with `"animate": true`), `set_state` (facelets of a cube of any size, shown
+// without moves), `scramble` (optional seed), `solve`, `state` and `subscribe`. After subscribing, the client also receives a `{"event": "move", ...}` line for
+// every move the cube makes, no matter where the move came from.
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Mutex,
+    },
+    thread,
+};
+
+use bevy::prelude::*;
+use rubiks_cube::model::MAX_CUBE_SIZE;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    cube::{
+        algorithm_from_notation, algorithm_to_notation, create_random_scramble_sequence,
+        create_seeded_scramble_sequence, scramble_length,
+        solver::{self, SolveStrategy},
+        AnimationProfileResource, CubeRotationEvent, CubeSize, CubeState, SequenceResource,
+        SetCubeStateEvent,
+    },
+    schedules::CubeScheduleSet,
+    utils::args,
+};
+
+const CONTROL_PORT_ARGUMENT: &str = "--control-port";
+
+/// Starts the control server if `port` is set
+pub struct ControlServerPlugin {
+    pub port: Option<u16>,
+}
+
+impl Plugin for ControlServerPlugin {
+    fn build(&self, app: &mut App) {
+        let Some(port) = self.port else {
+            return;
+        };
+
+        match ControlServerResource::start(port) {
+            Ok(control_server) => {
+                info!(
+                    "control server listening on {}",
+                    control_server.local_addr()
+                );
+                app.insert_resource(control_server);
+            }
+            Err(err) => {
+                error!("failed to start the control server: {err}");
+                return;
+            }
+        }
+
+        // Requests are handled before the sequence handler runs, so that moves are applied within the same frame.
+        app.add_systems(
+            Update,
+            handle_control_requests.in_set(CubeScheduleSet::Timers),
+        )
+        .add_systems(
+            Update,
+            publish_rotation_events.in_set(CubeScheduleSet::UpdateAnimations),
+        );
+    }
+}
+
+/// Returns the port given with `--control-port`, if any
+pub fn port_from_args(args: impl Iterator<Item = String>) -> Option<u16> {
+    let value = args::value_of(args, CONTROL_PORT_ARGUMENT)?;
+
+    match value.parse() {
+        Ok(port) => Some(port),
+        Err(_) => {
+            warn!("invalid {CONTROL_PORT_ARGUMENT} \"{value}\", the control server is not started");
+            None
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ControlRequest {
+    /// Gets sent back with the response, so that clients can match responses to requests
+    #[serde(default)]
+    id: Value,
+    #[serde(flatten)]
+    command: ControlCommand,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+enum ControlCommand {
+    Apply {
+        moves: String,
+        #[serde(default)]
+        animate: bool,
+    },
+    SetState {
+        facelets: String,
+    },
+    Scramble {
+        seed: Option<u64>,
+    },
+    Solve,
+    State,
+    Subscribe,
+}
+
+/// A line sent by a client, together with the channel to send the response to
+struct ReceivedLine {
+    line: String,
+    client: Sender<String>,
+}
+
+#[derive(Resource)]
+pub struct ControlServerResource {
+    local_addr: SocketAddr,
+    received_lines: Mutex<Receiver<ReceivedLine>>,
+    /// Clients that receive an event for every move
+    subscribers: Vec<Sender<String>>,
+}
+
+impl ControlServerResource {
+    /// Starts listening on localhost. With port 0, a free port gets picked.
+    fn start(port: u16) -> Result<Self, String> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+            .map_err(|err| format!("failed to bind to port {port}: {err}"))?;
+        let local_addr = listener
+            .local_addr()
+            .map_err(|err| format!("failed to get the address of the control server: {err}"))?;
+
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let sender = sender.clone();
+                        thread::spawn(move || handle_client(stream, sender));
+                    }
+                    Err(err) => warn!("control server: failed to accept a connection: {err}"),
+                }
+            }
+        });
+
+        Ok(Self {
+            local_addr,
+            received_lines: Mutex::new(receiver),
+            subscribers: vec![],
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+/// Reads the lines of a client on this thread and writes the responses and events on another one
+fn handle_client(stream: TcpStream, received_lines: Sender<ReceivedLine>) {
+    let mut writer = match stream.try_clone() {
+        Ok(writer) => writer,
+        Err(err) => {
+            warn!("control server: failed to set up a connection: {err}");
+            return;
+        }
+    };
+
+    let (client, client_receiver) = mpsc::channel::<String>();
+    thread::spawn(move || {
+        for line in client_receiver {
+            if writeln!(writer, "{line}").is_err() {
+                return;
+            }
+        }
+    });
+
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            return;
+        };
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let received_line = ReceivedLine {
+            line,
+            client: client.clone(),
+        };
+        if received_lines.send(received_line).is_err() {
+            return;
+        }
+    }
+}
+
+fn handle_control_requests(
+    mut control_server: ResMut<ControlServerResource>,
+    mut sequence_resource: ResMut<SequenceResource>,
+    animation_profile: Res<AnimationProfileResource>,
+    cube_state_query: Query<&CubeState>,
+    mut set_cube_state_event_writer: EventWriter<SetCubeStateEvent>,
+) {
+    let received_lines: Vec<ReceivedLine> = match control_server.received_lines.lock() {
+        Ok(receiver) => receiver.try_iter().collect(),
+        Err(err) => {
+            error!("control server: {err}");
+            return;
+        }
+    };
+
+    for ReceivedLine { line, client } in received_lines {
+        let request = match serde_json::from_str::<ControlRequest>(&line) {
+            Ok(request) => request,
+            Err(err) => {
+                respond(&client, Value::Null, Err(format!("invalid request: {err}")));
+                continue;
+            }
+        };
+
+        let Ok(cube_state) = cube_state_query.get_single() else {
+            // the cube might be respawning
+            respond(&client, request.id, Err("there is no cube".to_string()));
+            continue;
+        };
+
+        let result = match request.command {
+            ControlCommand::State => Ok(state_fields(cube_state)),
+            ControlCommand::Subscribe => {
+                control_server.subscribers.push(client.clone());
+                Ok(Map::new())
+            }
+            ControlCommand::SetState { facelets } => CubeState::parse_facelets(facelets.trim())
+                .and_then(|cube_state| match cube_state.size() {
+                    cube_size if cube_size > MAX_CUBE_SIZE => Err(format!(
+                        "the cube size can be at most {MAX_CUBE_SIZE}, got {cube_size}"
+                    )),
+                    _ => Ok(cube_state),
+                })
+                .map(|cube_state| {
+                    set_cube_state_event_writer.send(SetCubeStateEvent { cube_state });
+                    Map::new()
+                })
+                .map_err(|err| format!("invalid facelets: {err}")),
+            command => handle_move_command(
+                command,
+                cube_state,
+                &mut sequence_resource,
+                &animation_profile,
+            ),
+        };
+
+        respond(&client, request.id, result);
+    }
+}
+
+/// Handles the commands that turn the cube. The moves get played by the sequence resource, so they can not be
+/// started while another sequence is running.
+fn handle_move_command(
+    command: ControlCommand,
+    cube_state: &CubeState,
+    sequence_resource: &mut SequenceResource,
+    animation_profile: &AnimationProfileResource,
+) -> Result<Map<String, Value>, String> {
+    if !sequence_resource.is_done() {
+        return Err("the cube is busy with another sequence".to_string());
+    }
+
+    let cube_size = CubeSize(cube_state.size() as i32);
+    let mut animate = false;
+
+    let moves = match command {
+        ControlCommand::Apply {
+            moves,
+            animate: animate_moves,
+        } => {
+            animate = animate_moves;
+            algorithm_from_notation(&moves, &cube_size)?
+        }
+        ControlCommand::Scramble { seed } => {
+            let length = scramble_length(&cube_size);
+            match seed {
+                Some(seed) => create_seeded_scramble_sequence(&cube_size, length, seed),
+                None => create_random_scramble_sequence(&cube_size, length),
+            }
+        }
+        ControlCommand::Solve => {
+            let solution = solver::get_solve_sequence(SolveStrategy::Kociemba, cube_state);
+            if solution.is_empty() && !cube_state.is_solved() {
+                return Err("no solution found".to_string());
+            }
+            solution
+        }
+        ControlCommand::SetState { .. } | ControlCommand::State | ControlCommand::Subscribe => {
+            unreachable!()
+        }
+    };
+
+    let mut fields = Map::new();
+    fields.insert(
+        "moves".to_string(),
+        algorithm_to_notation(&moves, &cube_size).into(),
+    );
+
+    if animate {
+        sequence_resource.set_animated(moves, &animation_profile.0.solve, Some(1.0));
+    } else {
+        sequence_resource.set(moves);
+    }
+
+    Ok(fields)
+}
+
+fn state_fields(cube_state: &CubeState) -> Map<String, Value> {
+    let mut fields = Map::new();
+    fields.insert("cube_size".to_string(), cube_state.size().into());
+    fields.insert("facelets".to_string(), cube_state.to_facelets().into());
+    fields.insert("is_solved".to_string(), cube_state.is_solved().into());
+    fields
+}
+
+fn respond(client: &Sender<String>, id: Value, result: Result<Map<String, Value>, String>) {
+    let mut response = Map::new();
+    if !id.is_null() {
+        response.insert("id".to_string(), id);
+    }
+
+    match result {
+        Ok(fields) => {
+            response.insert("ok".to_string(), true.into());
+            response.extend(fields);
+        }
+        Err(err) => {
+            response.insert("ok".to_string(), false.into());
+            response.insert("error".to_string(), err.into());
+        }
+    }
+
+    // the client might have disconnected already
+    let _ = client.send(Value::Object(response).to_string());
+}
+
+fn publish_rotation_events(
+    mut control_server: ResMut<ControlServerResource>,
+    mut rotation_event_reader: EventReader<CubeRotationEvent>,
+    cube_state_query: Query<&CubeState>,
+) {
+    if control_server.subscribers.is_empty() {
+        rotation_event_reader.clear();
+        return;
+    }
+
+    let Ok(cube_state) = cube_state_query.get_single() else {
+        rotation_event_reader.clear();
+        return;
+    };
+
+    let cube_size = CubeSize(cube_state.size() as i32);
+
+    for rotation_event in rotation_event_reader.read() {
+        let event = json!({
+            "event": "move",
+            "move": rotation_event.to_notation(&cube_size),
+        })
+        .to_string();
+
+        control_server
+            .subscribers
+            .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpStream,
+        sync::mpsc::{self, Receiver},
+        thread,
+        time::Duration,
+    };
+
+    use bevy::prelude::*;
+    use serde_json::{json, Value};
+
+    use crate::cube::{
+        algorithm_from_notation, algorithm_to_notation, create_seeded_scramble_sequence,
+        scramble_length, test_utils::headless_app, CubeSize, CubeState,
+    };
+
+    use super::{port_from_args, ControlServerPlugin, ControlServerResource, MAX_CUBE_SIZE};
+
+    fn test_app() -> App {
+        let mut app = headless_app(3);
+        app.add_plugins(ControlServerPlugin { port: Some(0) });
+        app
+    }
+
+    struct TestClient {
+        stream: TcpStream,
+        lines: Receiver<String>,
+    }
+
+    impl TestClient {
+        fn connect(app: &App) -> Self {
+            let local_addr = app.world().resource::<ControlServerResource>().local_addr();
+            assert!(local_addr.ip().is_loopback());
+
+            let stream = TcpStream::connect(local_addr).unwrap();
+            let reader = BufReader::new(stream.try_clone().unwrap());
+            let (sender, lines) = mpsc::channel();
+            thread::spawn(move || {
+                for line in reader.lines().map_while(Result::ok) {
+                    if sender.send(line).is_err() {
+                        return;
+                    }
+                }
+            });
+
+            Self { stream, lines }
+        }
+
+        /// Runs the app until the next line from the server arrives
+        fn next_line(&self, app: &mut App) -> Value {
+            for _ in 0..500 {
+                app.update();
+
+                if let Ok(line) = self.lines.recv_timeout(Duration::from_millis(5)) {
+                    return serde_json::from_str(&line).unwrap();
+                }
+            }
+
+            panic!("no response from the control server");
+        }
+
+        fn request(&mut self, app: &mut App, request: Value) -> Value {
+            writeln!(self.stream, "{request}").unwrap();
+            self.next_line(app)
+        }
+    }
+
+    fn cube_state(app: &mut App) -> CubeState {
+        app.world_mut()
+            .query::<&CubeState>()
+            .single(app.world())
+            .clone()
+    }
+
+    #[test]
+    fn test_apply_moves_and_query_state() {
+        let mut app = test_app();
+        let mut client = TestClient::connect(&app);
+
+        let response = client.request(
+            &mut app,
+            json!({"id": 1, "command": "apply", "moves": "R U R' U'"}),
+        );
+        assert_eq!(json!({"id": 1, "ok": true, "moves": "R U R' U'"}), response);
+
+        let mut expected_state = CubeState::new(3);
+        for rotation_event in algorithm_from_notation("R U R' U'", &CubeSize(3)).unwrap() {
+            expected_state.handle_rotate_event(&rotation_event);
+        }
+        assert!(expected_state == cube_state(&mut app));
+
+        let response = client.request(&mut app, json!({"command": "state"}));
+        assert_eq!(
+            json!({
+                "ok": true,
+                "cube_size": 3,
+                "facelets": expected_state.to_facelets(),
+                "is_solved": false,
+            }),
+            response
+        );
+    }
+
+    #[test]
+    fn test_scramble_with_seed_and_set_state() {
+        let mut app = test_app();
+        let mut client = TestClient::connect(&app);
+
+        let response = client.request(&mut app, json!({"command": "scramble", "seed": 7}));
+        let scramble =
+            create_seeded_scramble_sequence(&CubeSize(3), scramble_length(&CubeSize(3)), 7);
+        assert_eq!(
+            json!({"ok": true, "moves": algorithm_to_notation(&scramble, &CubeSize(3))}),
+            response
+        );
+        assert!(!cube_state(&mut app).is_solved());
+
+        let response = client.request(
+            &mut app,
+            json!({"command": "set_state", "facelets": CubeState::new(3).to_facelets()}),
+        );
+        assert_eq!(json!({"ok": true}), response);
+        app.update();
+        assert!(cube_state(&mut app).is_solved());
+
+        // the cube gets spawned again for another size
+        let mut wanted_state = CubeState::new(2);
+        for rotation_event in algorithm_from_notation("R U", &CubeSize(2)).unwrap() {
+            wanted_state.handle_rotate_event(&rotation_event);
+        }
+        let response = client.request(
+            &mut app,
+            json!({"command": "set_state", "facelets": wanted_state.to_facelets()}),
+        );
+        assert_eq!(json!({"ok": true}), response);
+        for _ in 0..3 {
+            app.update();
+        }
+        assert!(wanted_state == cube_state(&mut app));
+    }
+
+    #[test]
+    fn test_subscribe_to_moves() {
+        let mut app = test_app();
+        let mut subscriber = TestClient::connect(&app);
+        let mut client = TestClient::connect(&app);
+
+        let response = subscriber.request(&mut app, json!({"command": "subscribe"}));
+        assert_eq!(json!({"ok": true}), response);
+
+        client.request(&mut app, json!({"command": "apply", "moves": "R2 x"}));
+
+        assert_eq!(
+            json!({"event": "move", "move": "R2"}),
+            subscriber.next_line(&mut app)
+        );
+        assert_eq!(
+            json!({"event": "move", "move": "x"}),
+            subscriber.next_line(&mut app)
+        );
+    }
+
+    #[test]
+    fn test_invalid_requests() {
+        let mut app = test_app();
+        let mut client = TestClient::connect(&app);
+
+        let response = client.request(&mut app, json!({"command": "explode"}));
+        assert_eq!(Some(false), response["ok"].as_bool());
+
+        let response = client.request(
+            &mut app,
+            json!({"id": "a", "command": "apply", "moves": "Q"}),
+        );
+        assert_eq!(Some("a"), response["id"].as_str());
+        assert_eq!(Some(false), response["ok"].as_bool());
+
+        let response = client.request(
+            &mut app,
+            json!({"command": "set_state", "facelets": "UUUURRRR"}),
+        );
+        assert_eq!(
+            json!({"ok": false, "error": "invalid facelets: 8 facelets do not make up a cube"}),
+            response
+        );
+
+        let too_big = CubeState::new(MAX_CUBE_SIZE + 1).to_facelets();
+        let response = client.request(
+            &mut app,
+            json!({"command": "set_state", "facelets": too_big}),
+        );
+        assert_eq!(
+            json!({"ok": false, "error": "invalid facelets: the cube size can be at most 100, got 101"}),
+            response
+        );
+
+        writeln!(client.stream, "not json").unwrap();
+        assert_eq!(Some(false), client.next_line(&mut app)["ok"].as_bool());
+
+        assert!(cube_state(&mut app).is_solved());
+    }
+
+    #[test]
+    fn test_port_from_args() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(None, port_from_args(args(&["rubiks-cube"]).into_iter()));
+        assert_eq!(
+            Some(7070),
+            port_from_args(args(&["rubiks-cube", "--control-port", "7070"]).into_iter())
+        );
+        assert_eq!(
+            None,
+            port_from_args(args(&["rubiks-cube", "--control-port", "x"]).into_iter())
+        );
+    }
+}
@@ -0,0 +1,319 @@
+use bevy::prelude::*;
+
+use crate::{schedules::CubeScheduleSet, utils::storage};
+
+use super::{CubeRotationAnimation as RotationAnimation, CubeRotationEvent, EaseFunction};
+
+const ANIMATION_PROFILE_SETTING: &str = "animation_profile";
+
+pub struct AnimationProfilePlugin;
+
+impl Plugin for AnimationProfilePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(AnimationProfileResource(load_animation_profile()))
+            .add_systems(
+                Update,
+                save_animation_profile.in_set(CubeScheduleSet::HandleEvents),
+            );
+    }
+}
+
+/// The animation profile that is used for new sequences
+#[derive(Resource)]
+pub struct AnimationProfileResource(pub &'static AnimationProfile);
+
+/// A named set of sequence animations, selectable by the user
+#[derive(Debug)]
+pub struct AnimationProfile {
+    pub name: &'static str,
+    pub scramble: SequenceAnimation,
+    pub solve: SequenceAnimation,
+}
+
+/// Describes how the moves of a sequence get animated
+#[derive(Clone, Debug)]
+pub struct SequenceAnimation {
+    /// The duration of a single move at normal speed, in seconds
+    pub move_duration: f32,
+    pub ease_function: EaseFunction,
+    pub last_move_ease_function: EaseFunction,
+    /// The first moves of the sequence take longer, speeding up to `move_duration`
+    pub acceleration: SpeedRamp,
+    /// The last moves of the sequence take longer, slowing down from `move_duration`
+    pub deceleration: SpeedRamp,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SpeedRamp {
+    pub moves: usize,
+    /// The duration of the outermost move of the ramp gets multiplied by this. Moves closer to the
+    /// middle of the sequence get multiplied by less.
+    pub duration_multiplier: f32,
+}
+
+const NO_RAMP: SpeedRamp = SpeedRamp {
+    moves: 0,
+    duration_multiplier: 1.0,
+};
+
+pub static DEFAULT_ANIMATION_PROFILE: &AnimationProfile = &ANIMATION_PROFILES[0];
+
+pub static ANIMATION_PROFILES: [AnimationProfile; 4] = [
+    AnimationProfile {
+        name: "default",
+        scramble: SequenceAnimation {
+            move_duration: 0.15,
+            ease_function: EaseFunction::Linear,
+            last_move_ease_function: EaseFunction::CubicOut,
+            acceleration: NO_RAMP,
+            deceleration: SpeedRamp {
+                moves: 2,
+                duration_multiplier: 2.0,
+            },
+        },
+        solve: SequenceAnimation {
+            move_duration: 0.35,
+            ease_function: EaseFunction::CubicOut,
+            last_move_ease_function: EaseFunction::CubicOut,
+            acceleration: NO_RAMP,
+            deceleration: NO_RAMP,
+        },
+    },
+    AnimationProfile {
+        name: "smooth",
+        scramble: SequenceAnimation {
+            move_duration: 0.18,
+            ease_function: EaseFunction::SineInOut,
+            last_move_ease_function: EaseFunction::CubicOut,
+            acceleration: SpeedRamp {
+                moves: 4,
+                duration_multiplier: 2.0,
+            },
+            deceleration: SpeedRamp {
+                moves: 4,
+                duration_multiplier: 2.5,
+            },
+        },
+        solve: SequenceAnimation {
+            move_duration: 0.4,
+            ease_function: EaseFunction::SineInOut,
+            last_move_ease_function: EaseFunction::CubicOut,
+            acceleration: SpeedRamp {
+                moves: 2,
+                duration_multiplier: 1.5,
+            },
+            deceleration: SpeedRamp {
+                moves: 2,
+                duration_multiplier: 1.5,
+            },
+        },
+    },
+    AnimationProfile {
+        name: "snappy",
+        scramble: SequenceAnimation {
+            move_duration: 0.1,
+            ease_function: EaseFunction::QuadraticOut,
+            last_move_ease_function: EaseFunction::BackOut,
+            acceleration: NO_RAMP,
+            deceleration: NO_RAMP,
+        },
+        solve: SequenceAnimation {
+            move_duration: 0.25,
+            ease_function: EaseFunction::BackOut,
+            last_move_ease_function: EaseFunction::BackOut,
+            acceleration: NO_RAMP,
+            deceleration: NO_RAMP,
+        },
+    },
+    AnimationProfile {
+        name: "linear",
+        scramble: SequenceAnimation {
+            move_duration: 0.15,
+            ease_function: EaseFunction::Linear,
+            last_move_ease_function: EaseFunction::Linear,
+            acceleration: NO_RAMP,
+            deceleration: NO_RAMP,
+        },
+        solve: SequenceAnimation {
+            move_duration: 0.35,
+            ease_function: EaseFunction::Linear,
+            last_move_ease_function: EaseFunction::Linear,
+            acceleration: NO_RAMP,
+            deceleration: NO_RAMP,
+        },
+    },
+];
+
+pub fn find_animation_profile(name: &str) -> Option<&'static AnimationProfile> {
+    ANIMATION_PROFILES
+        .iter()
+        .find(|animation_profile| animation_profile.name == name)
+}
+
+fn load_animation_profile() -> &'static AnimationProfile {
+    let Some(name) = storage::load_setting(ANIMATION_PROFILE_SETTING) else {
+        return DEFAULT_ANIMATION_PROFILE;
+    };
+
+    match find_animation_profile(&name) {
+        Some(animation_profile) => animation_profile,
+        None => {
+            warn!("unknown animation profile \"{name}\", using the default one");
+            DEFAULT_ANIMATION_PROFILE
+        }
+    }
+}
+
+fn save_animation_profile(animation_profile: Res<AnimationProfileResource>) {
+    if !animation_profile.is_changed() || animation_profile.is_added() {
+        return;
+    }
+
+    if let Err(err) = storage::save_setting(ANIMATION_PROFILE_SETTING, animation_profile.0.name) {
+        error!("failed to save animation profile: {err}");
+    }
+}
+
+impl SequenceAnimation {
+    /// Set the animation of every move in the sequence. A higher speed multiplier results in shorter
+    /// animations. If `speed_multiplier` is None, the moves are not animated.
+    pub fn apply(&self, sequence: &mut [CubeRotationEvent], speed_multiplier: Option<f32>) {
+        let sequence_len = sequence.len();
+
+        for (i, rotation_event) in sequence.iter_mut().enumerate() {
+            let Some(speed_multiplier) = speed_multiplier else {
+                rotation_event.animation = None;
+                continue;
+            };
+
+            let ease_function = if i == sequence_len - 1 {
+                self.last_move_ease_function
+            } else {
+                self.ease_function
+            };
+
+            rotation_event.animation = Some(RotationAnimation {
+                duration_in_seconds: self.move_duration * self.ramp_multiplier(i, sequence_len)
+                    / speed_multiplier,
+                ease_function: Some(ease_function),
+            });
+        }
+    }
+
+    fn ramp_multiplier(&self, move_index: usize, sequence_len: usize) -> f32 {
+        let mut multiplier: f32 = 1.0;
+
+        if move_index < self.acceleration.moves {
+            let moves_until_full_speed = self.acceleration.moves - move_index;
+            multiplier = multiplier.max(self.acceleration.at(moves_until_full_speed));
+        }
+
+        let moves_after = sequence_len - 1 - move_index;
+        if moves_after < self.deceleration.moves {
+            let moves_since_full_speed = self.deceleration.moves - moves_after;
+            multiplier = multiplier.max(self.deceleration.at(moves_since_full_speed));
+        }
+
+        multiplier
+    }
+}
+
+impl SpeedRamp {
+    /// The duration multiplier for the move that is `distance` moves away from full speed. The
+    /// duration grows quadratically, so that the change in speed feels natural.
+    fn at(&self, distance: usize) -> f32 {
+        let progress = distance as f32 / self.moves as f32;
+        1.0 + (self.duration_multiplier - 1.0) * progress * progress
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::cube::{CubeRotationEvent, EaseFunction, Rotation};
+
+    use super::{find_animation_profile, SequenceAnimation, SpeedRamp, DEFAULT_ANIMATION_PROFILE};
+
+    fn sequence(len: usize) -> Vec<CubeRotationEvent> {
+        (0..len)
+            .map(|_| CubeRotationEvent {
+                rotation: Rotation::face_x(1),
+                negative_direction: false,
+                twice: false,
+                animation: None,
+            })
+            .collect()
+    }
+
+    fn durations(sequence: &[CubeRotationEvent]) -> Vec<f32> {
+        sequence
+            .iter()
+            .map(|rotation_event| {
+                rotation_event
+                    .animation
+                    .as_ref()
+                    .unwrap()
+                    .duration_in_seconds
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_default_scramble_slows_down_at_the_end() {
+        let mut scramble = sequence(4);
+        DEFAULT_ANIMATION_PROFILE
+            .scramble
+            .apply(&mut scramble, Some(2.0));
+
+        let expected_durations = [0.075, 0.075, 0.09375, 0.15];
+        for (duration, expected_duration) in durations(&scramble).iter().zip(expected_durations) {
+            assert!((duration - expected_duration).abs() < 0.0001);
+        }
+        assert_eq!(
+            Some(EaseFunction::CubicOut),
+            scramble[3].animation.as_ref().unwrap().ease_function
+        );
+        assert_eq!(
+            Some(EaseFunction::Linear),
+            scramble[2].animation.as_ref().unwrap().ease_function
+        );
+    }
+
+    #[test]
+    fn test_speed_ramps() {
+        let sequence_animation = SequenceAnimation {
+            move_duration: 1.0,
+            ease_function: EaseFunction::Linear,
+            last_move_ease_function: EaseFunction::Linear,
+            acceleration: SpeedRamp {
+                moves: 2,
+                duration_multiplier: 3.0,
+            },
+            deceleration: SpeedRamp {
+                moves: 1,
+                duration_multiplier: 2.0,
+            },
+        };
+
+        let mut steps = sequence(5);
+        sequence_animation.apply(&mut steps, Some(1.0));
+        assert_eq!(vec![3.0, 1.5, 1.0, 1.0, 2.0], durations(&steps));
+
+        // the ramps overlap, the slowest one wins
+        let mut steps = sequence(1);
+        sequence_animation.apply(&mut steps, Some(1.0));
+        assert_eq!(vec![3.0], durations(&steps));
+
+        let mut steps = sequence(3);
+        sequence_animation.apply(&mut steps, None);
+        assert!(steps
+            .iter()
+            .all(|rotation_event| rotation_event.animation.is_none()));
+    }
+
+    #[test]
+    fn test_find_animation_profile() {
+        assert_eq!("smooth", find_animation_profile("smooth").unwrap().name);
+        assert!(find_animation_profile("unknown").is_none());
+    }
+}
@@ -0,0 +1,469 @@
+// The 3D pieces and the `CubeState` of a cube are updated independently: rotations move the pieces, and the cube
+// state swaps its facelets. This module connects the two. It can paint the stickers from a cube state, for example
+// when a state is loaded. It can also read the stickers back into facelets, which debug builds compare with the cube
+// state every time a rotation is done. When they differ, the mismatch is reported and the stickers are painted from
+// the cube state, so that the view shows the state that the game continues with.
+
+use bevy::prelude::*;
+
+use crate::schedules::CubeScheduleSet;
+
+use super::{
+    Cube, CubeState, Piece, PieceFace, COLOR_BACK, COLOR_BOTTOM, COLOR_FRONT, COLOR_LEFT,
+    COLOR_RIGHT, COLOR_TOP,
+};
+
+/// The color of every facelet, see `CubeState::to_facelets`
+const STICKER_COLORS: [(char, Color); 6] = [
+    ('U', COLOR_TOP),
+    ('R', COLOR_RIGHT),
+    ('F', COLOR_FRONT),
+    ('D', COLOR_BOTTOM),
+    ('L', COLOR_LEFT),
+    ('B', COLOR_BACK),
+];
+
+/// The column and the row of a sticker on a face, from its position
+type StickerCoordinates = fn(Vec3) -> (f32, f32);
+
+/// For each face in URFDLB order: the outward direction, and the coordinates of a sticker that increase with its
+/// column and its row in the layout of `FaceState`.
+const FACES: [(IVec3, StickerCoordinates); 6] = [
+    (IVec3::Y, |position| (position.x, position.z)),
+    (IVec3::X, |position| (-position.z, -position.y)),
+    (IVec3::Z, |position| (position.x, -position.y)),
+    (IVec3::NEG_Y, |position| (position.x, -position.z)),
+    (IVec3::NEG_X, |position| (position.z, -position.y)),
+    (IVec3::NEG_Z, |position| (-position.x, -position.y)),
+];
+
+/// The pieces of the cube with their stickers and inner sides as children
+pub type PiecesQuery<'w, 's> = Query<'w, 's, (&'static Transform, &'static Children), With<Piece>>;
+
+/// The stickers and inner sides of the pieces
+pub type PieceFacesQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Transform,
+        &'static MeshMaterial3d<StandardMaterial>,
+    ),
+    With<PieceFace>,
+>;
+
+pub struct ConsistencyPlugin;
+
+impl Plugin for ConsistencyPlugin {
+    fn build(&self, app: &mut App) {
+        if cfg!(debug_assertions) {
+            app.add_systems(
+                Update,
+                check_consistency.after(CubeScheduleSet::UpdateAnimations),
+            );
+        }
+    }
+}
+
+/// Reads the facelets that the stickers of the pieces show, in the format of `CubeState::to_facelets`. Fails while
+/// the cube is rotating, because then the stickers are not aligned with the faces.
+pub fn read_facelets(
+    cube: &Cube,
+    pieces: &PiecesQuery,
+    piece_faces: &PieceFacesQuery,
+    materials: &Assets<StandardMaterial>,
+) -> Result<String, String> {
+    let cube_size = cube.size().0 as usize;
+    let mut facelets = vec![None; 6 * cube_size * cube_size];
+
+    for (facelet_index, material) in stickers(cube, pieces, piece_faces)? {
+        let color = materials
+            .get(material)
+            .ok_or("a sticker has no material")?
+            .base_color;
+        let (facelet, _) = STICKER_COLORS
+            .iter()
+            .find(|(_, sticker_color)| *sticker_color == color)
+            .ok_or(format!("a sticker has an unknown color: {color:?}"))?;
+
+        if facelets[facelet_index].replace(*facelet).is_some() {
+            return Err(format!("facelet {facelet_index} has more than one sticker"));
+        }
+    }
+
+    facelets
+        .into_iter()
+        .enumerate()
+        .map(|(facelet_index, facelet)| {
+            facelet.ok_or(format!("facelet {facelet_index} has no sticker"))
+        })
+        .collect()
+}
+
+/// Colors the stickers of the pieces, so that the cube shows the given state. Fails while the cube is rotating.
+pub fn paint_facelets(
+    cube: &Cube,
+    cube_state: &CubeState,
+    pieces: &PiecesQuery,
+    piece_faces: &PieceFacesQuery,
+    materials: &mut Assets<StandardMaterial>,
+) -> Result<(), String> {
+    if cube_state.size() != cube.size().0 as usize {
+        return Err(format!(
+            "can not paint a {0}x{0} cube state on a {1}x{1} cube",
+            cube_state.size(),
+            cube.size().0
+        ));
+    }
+
+    let facelets: Vec<char> = cube_state.to_facelets().chars().collect();
+
+    for (facelet_index, material) in stickers(cube, pieces, piece_faces)? {
+        let color = facelet_color(facelets[facelet_index])
+            .ok_or(format!("unknown facelet: {}", facelets[facelet_index]))?;
+
+        materials
+            .get_mut(material)
+            .ok_or("a sticker has no material")?
+            .base_color = color;
+    }
+
+    Ok(())
+}
+
+/// Describes every facelet where the pieces show something else than the cube state
+pub fn find_mismatches(cube_state_facelets: &str, piece_facelets: &str) -> Vec<String> {
+    let face_length = cube_state_facelets.len() / 6;
+
+    cube_state_facelets
+        .chars()
+        .zip(piece_facelets.chars())
+        .enumerate()
+        .filter(|(_, (expected, found))| expected != found)
+        .map(|(facelet_index, (expected, found))| {
+            let (face, _) = STICKER_COLORS[facelet_index / face_length];
+            format!(
+                "{face}{}: the cube state has {expected}, the pieces show {found}",
+                facelet_index % face_length + 1
+            )
+        })
+        .collect()
+}
+
+/// The index of the facelet at a position on the surface of the cube, relative to the middle of the cube. `None` if
+/// the position is not on the surface.
+pub fn facelet_at(cube: &Cube, position: Vec3) -> Option<usize> {
+    let axis = (0..3)
+        .max_by(|a, b| position[*a].abs().total_cmp(&position[*b].abs()))
+        .unwrap();
+    let mut normal = IVec3::ZERO;
+    normal[axis] = position[axis].signum() as i32;
+
+    let half_size = (cube.size().0 as f32 * cube.piece_size()
+        + (cube.size().0 - 1) as f32 * cube.space_between_pieces())
+        / 2.0;
+    if (position[axis].abs() - half_size).abs() > cube.piece_size() / 4.0 {
+        return None;
+    }
+
+    facelet_index(cube, normal, position)
+}
+
+/// The color of a facelet, see `CubeState::to_facelets`
+pub fn facelet_color(facelet: char) -> Option<Color> {
+    STICKER_COLORS
+        .iter()
+        .find(|(sticker_facelet, _)| *sticker_facelet == facelet)
+        .map(|(_, color)| *color)
+}
+
+/// The slice indices of the piece of every facelet, in the order of `CubeState::to_facelets`
+pub fn facelet_pieces(cube_size: usize) -> Vec<IVec3> {
+    let cube_size = cube_size as i32;
+    let is_even = cube_size % 2 == 0;
+    let slices: Vec<i32> = (-cube_size / 2..=cube_size / 2)
+        .filter(|slice| !is_even || *slice != 0)
+        .collect();
+    // even sized cubes do not have a slice 0, see `Cube::piece_translation`
+    let to_grid = |slice: i32| {
+        if is_even {
+            slice as f32 - slice.signum() as f32 / 2.0
+        } else {
+            slice as f32
+        }
+    };
+
+    let mut pieces = vec![IVec3::ZERO; (6 * cube_size * cube_size) as usize];
+    for &x in &slices {
+        for &y in &slices {
+            for &z in &slices {
+                let piece = IVec3::new(x, y, z);
+                let grid_position = Vec3::new(to_grid(x), to_grid(y), to_grid(z));
+
+                for (normal, _) in FACES {
+                    if piece.dot(normal) != cube_size / 2 {
+                        continue;
+                    }
+
+                    if let Some(facelet_index) =
+                        facelet_index_on_grid(cube_size, normal, grid_position)
+                    {
+                        pieces[facelet_index] = piece;
+                    }
+                }
+            }
+        }
+    }
+
+    pieces
+}
+
+/// The index of the facelet on the face with the given normal, at the given position of a sticker or a piece
+fn facelet_index(cube: &Cube, normal: IVec3, position: Vec3) -> Option<usize> {
+    let distance_between_pieces = cube.piece_size() + cube.space_between_pieces();
+    facelet_index_on_grid(cube.size().0, normal, position / distance_between_pieces)
+}
+
+/// Like `facelet_index`, with the position measured in distances between pieces
+fn facelet_index_on_grid(cube_size: i32, normal: IVec3, grid_position: Vec3) -> Option<usize> {
+    let (face_index, (_, coordinates)) = FACES
+        .iter()
+        .enumerate()
+        .find(|(_, (face_normal, _))| *face_normal == normal)?;
+
+    // the column or row of a coordinate, counted from 0
+    let to_index = |coordinate: f32| (coordinate + (cube_size - 1) as f32 / 2.0).round() as i32;
+
+    let (column, row) = coordinates(grid_position);
+    let (column, row) = (to_index(column), to_index(row));
+    if !(0..cube_size).contains(&column) || !(0..cube_size).contains(&row) {
+        return None;
+    }
+
+    Some((face_index as i32 * cube_size * cube_size + row * cube_size + column) as usize)
+}
+
+/// The facelet index and the material of every sticker of the cube
+fn stickers<'a>(
+    cube: &Cube,
+    pieces: &PiecesQuery,
+    piece_faces: &'a PieceFacesQuery,
+) -> Result<Vec<(usize, &'a Handle<StandardMaterial>)>, String> {
+    if cube.is_animating_rotation {
+        return Err("the cube is rotating".to_string());
+    }
+
+    let mut stickers = vec![];
+    for (piece_transform, children) in pieces.iter() {
+        for (face_transform, material) in piece_faces.iter_many(children) {
+            if material.0 == *cube.inner_material() {
+                continue;
+            }
+
+            let transform = piece_transform.mul_transform(*face_transform);
+            let normal = (transform.rotation * Vec3::Z).round().as_ivec3();
+            let Some(facelet_index) = facelet_index(cube, normal, transform.translation) else {
+                return Err(format!(
+                    "a sticker is not on a side of the cube: {} facing {normal}",
+                    transform.translation
+                ));
+            };
+
+            stickers.push((facelet_index, &material.0));
+        }
+    }
+
+    Ok(stickers)
+}
+
+/// Compares the stickers with the cube state, after the cube state has changed and the cube stopped rotating
+fn check_consistency(
+    cube_query: Query<(&Cube, Ref<CubeState>)>,
+    pieces: PiecesQuery,
+    piece_faces: PieceFacesQuery,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut is_pending: Local<bool>,
+) {
+    let Ok((cube, cube_state)) = cube_query.get_single() else {
+        return;
+    };
+
+    if cube_state.is_changed() {
+        *is_pending = true;
+    }
+
+    if !*is_pending || cube.is_animating_rotation {
+        return;
+    }
+
+    *is_pending = false;
+
+    let piece_facelets = match read_facelets(cube, &pieces, &piece_faces, &materials) {
+        Ok(piece_facelets) => piece_facelets,
+        Err(err) => {
+            error!("failed to read the stickers of the cube: {err}");
+            return;
+        }
+    };
+
+    let mismatches = find_mismatches(&cube_state.to_facelets(), &piece_facelets);
+    if !mismatches.is_empty() {
+        error!(
+            "the pieces do not show the cube state, {} facelets differ: {}",
+            mismatches.len(),
+            mismatches.join(", ")
+        );
+
+        if let Err(err) = paint_facelets(cube, &cube_state, &pieces, &piece_faces, &mut materials) {
+            error!("failed to paint the stickers of the cube: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate::cube::{
+        create_seeded_scramble_sequence, scramble_length,
+        test_utils::{facelets_from_pieces, headless_app},
+        Cube, CubeRotationEvent, CubeSize, CubeState, Rotation,
+    };
+    use rubiks_cube::model::Axis;
+
+    use super::{facelet_pieces, find_mismatches, paint_facelets, PieceFacesQuery, PiecesQuery};
+
+    fn scrambled_cube_state(cube_size: usize) -> CubeState {
+        let mut cube_state = CubeState::new(cube_size);
+        for rotation_event in create_seeded_scramble_sequence(
+            &CubeSize(cube_size as i32),
+            scramble_length(&CubeSize(cube_size as i32)),
+            cube_size as u64,
+        ) {
+            cube_state.handle_rotate_event(&rotation_event);
+        }
+        cube_state
+    }
+
+    fn paint(app: &mut App, cube_state: &CubeState) -> Result<(), String> {
+        app.world_mut()
+            .run_system_cached_with(
+                |In(cube_state): In<CubeState>,
+                 cube_query: Query<&Cube>,
+                 pieces: PiecesQuery,
+                 piece_faces: PieceFacesQuery,
+                 mut materials: ResMut<Assets<StandardMaterial>>| {
+                    paint_facelets(
+                        cube_query.single(),
+                        &cube_state,
+                        &pieces,
+                        &piece_faces,
+                        &mut materials,
+                    )
+                },
+                cube_state.clone(),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn test_paint_and_read_facelets() {
+        for cube_size in 1..=7 {
+            let mut app = headless_app(cube_size);
+            let cube_state = scrambled_cube_state(cube_size);
+
+            paint(&mut app, &cube_state).unwrap();
+            assert_eq!(cube_state.to_facelets(), facelets_from_pieces(&mut app));
+
+            paint(&mut app, &CubeState::new(cube_size)).unwrap();
+            assert_eq!(
+                CubeState::new(cube_size).to_facelets(),
+                facelets_from_pieces(&mut app)
+            );
+        }
+
+        let mut app = headless_app(3);
+        assert!(paint(&mut app, &CubeState::new(4)).is_err());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn test_mismatches_are_painted_over() {
+        let mut app = headless_app(3);
+        app.update();
+
+        // only the stickers change, the cube state stays solved
+        paint(&mut app, &scrambled_cube_state(3)).unwrap();
+        assert_ne!(
+            CubeState::new(3).to_facelets(),
+            facelets_from_pieces(&mut app)
+        );
+
+        app.world_mut()
+            .query::<&mut CubeState>()
+            .single_mut(app.world_mut())
+            .set_changed();
+        app.update();
+
+        assert_eq!(
+            CubeState::new(3).to_facelets(),
+            facelets_from_pieces(&mut app)
+        );
+    }
+
+    #[test]
+    fn test_facelet_pieces() {
+        assert_eq!(IVec3::new(-1, 1, -1), facelet_pieces(3)[0]);
+        assert_eq!(IVec3::new(2, 2, 2), facelet_pieces(4)[2 * 16 + 3]);
+
+        // a turn only moves the facelets of the pieces in its slice
+        for cube_size in 1..=6 {
+            let pieces = facelet_pieces(cube_size);
+            let cube_state = scrambled_cube_state(cube_size);
+
+            for axis in [Axis::X, Axis::Y, Axis::Z] {
+                for slice in -(cube_size as i32) / 2..=cube_size as i32 / 2 {
+                    if cube_size % 2 == 0 && slice == 0 {
+                        continue;
+                    }
+
+                    let mut turned_cube_state = cube_state.clone();
+                    turned_cube_state.handle_rotate_event(&CubeRotationEvent {
+                        rotation: Rotation::face(axis, slice),
+                        negative_direction: false,
+                        twice: false,
+                        animation: None,
+                    });
+
+                    let turned_facelets = turned_cube_state.to_facelets();
+                    for (facelet_index, (before, after)) in cube_state
+                        .to_facelets()
+                        .chars()
+                        .zip(turned_facelets.chars())
+                        .enumerate()
+                    {
+                        let piece_slice = match axis {
+                            Axis::X => pieces[facelet_index].x,
+                            Axis::Y => pieces[facelet_index].y,
+                            Axis::Z => pieces[facelet_index].z,
+                        };
+                        assert!(before == after || piece_slice == slice);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_find_mismatches() {
+        let solved = CubeState::new(2).to_facelets();
+        assert!(find_mismatches(&solved, &solved).is_empty());
+
+        let mut cube_state = CubeState::new(2);
+        cube_state.handle_rotate_event(
+            &crate::cube::CubeRotationEvent::from_notation("R", &CubeSize(2)).unwrap(),
+        );
+        let mismatches = find_mismatches(&cube_state.to_facelets(), &solved);
+        assert_eq!(8, mismatches.len());
+        assert_eq!("U2: the cube state has F, the pieces show U", mismatches[0]);
+    }
+}
@@ -1,12 +1,14 @@
+use std::collections::VecDeque;
+
 use bevy::prelude::*;
 
 use crate::schedules::CubeScheduleSet;
 
 use super::{
+    animation_profile::SequenceAnimation,
     cube::Cube,
-    cube_state::CubeState,
-    rotation::{CubeRotationEventFinished, RotationAnimation},
-    CubeRotationEvent,
+    rotation::{CubeRotationEventFinished, CurrentRotationResource, FastTurningResource},
+    CubeRotationAnimation as RotationAnimation, CubeRotationEvent, CubeState,
 };
 
 pub struct ControllerPlugin;
@@ -14,6 +16,7 @@ pub struct ControllerPlugin;
 impl Plugin for ControllerPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(SequenceResource::default())
+            .insert_resource(RotationInputQueueResource::default())
             .add_systems(
                 Update,
                 (update_sequence_resouce_timer).in_set(CubeScheduleSet::Timers),
@@ -24,6 +27,7 @@ impl Plugin for ControllerPlugin {
                     check_solved_on_enter,
                     sequence_handler,
                     rotation_event_finished_event_handler,
+                    rotation_input_queue_handler,
                     random_face_rotation_on_tab,
                 )
                     .chain()
@@ -32,11 +36,25 @@ impl Plugin for ControllerPlugin {
     }
 }
 
+/// Consecutive sequence steps that are animated at least this fast get animated concurrently, if they rotate
+/// different slices of the same axis.
+const GROUP_STEPS_MAX_DURATION: f32 = 0.1; // in seconds
+
 #[derive(Resource)]
 pub struct SequenceResource {
     pub steps: Vec<CubeRotationEvent>,
     current_step: usize,
     current_step_timer: Option<Timer>,
+    /// Gets incremented every time a new sequence is set, so that others can detect that the steps changed.
+    version: usize,
+    paused: bool,
+    scrub_target: Option<usize>,
+    /// How the steps get animated. None if the steps came with their own animation.
+    sequence_animation: Option<SequenceAnimation>,
+    /// Seconds to wait after a step has been animated before the next step is sent. Empty if there are no pauses.
+    step_pauses: Vec<f32>,
+    /// True once the cube got turned by the user after the sequence was done, see `detach`
+    detached: bool,
 }
 
 impl Default for SequenceResource {
@@ -45,6 +63,12 @@ impl Default for SequenceResource {
             steps: vec![],
             current_step: 0,
             current_step_timer: None,
+            version: 0,
+            paused: false,
+            scrub_target: None,
+            sequence_animation: None,
+            step_pauses: vec![],
+            detached: false,
         }
     }
 }
@@ -53,12 +77,87 @@ impl SequenceResource {
     pub fn set(&mut self, steps: Vec<CubeRotationEvent>) {
         self.steps = steps;
         self.current_step = 0;
+        self.version += 1;
+        self.paused = false;
+        self.scrub_target = None;
+        self.sequence_animation = None;
+        self.step_pauses = vec![];
+        self.detached = false;
+    }
+
+    /// Set a sequence with a pause after each step, for example to replay turns with the timing they were made with.
+    pub fn set_with_pauses(&mut self, steps: Vec<CubeRotationEvent>, step_pauses: Vec<f32>) {
+        self.set(steps);
+        self.step_pauses = step_pauses;
+    }
+
+    /// Set a sequence of which the steps get animated by `sequence_animation`. If `speed_multiplier` is None,
+    /// the steps are not animated.
+    pub fn set_animated(
+        &mut self,
+        mut steps: Vec<CubeRotationEvent>,
+        sequence_animation: &SequenceAnimation,
+        speed_multiplier: Option<f32>,
+    ) {
+        sequence_animation.apply(&mut steps, speed_multiplier);
+        self.set(steps);
+        self.sequence_animation = Some(sequence_animation.clone());
+    }
+
+    /// Re-applies the animation of a sequence that was set with `set_animated`, using the new speed.
+    pub fn set_speed(&mut self, speed_multiplier: Option<f32>) {
+        if let Some(sequence_animation) = &self.sequence_animation {
+            sequence_animation.apply(&mut self.steps, speed_multiplier);
+        }
     }
 
     pub fn is_done(&self) -> bool {
         self.current_step >= self.steps.len() && self.current_step_timer == None
     }
 
+    /// The number of steps that have been sent. While a step is being animated, it is already included.
+    pub fn current_step(&self) -> usize {
+        self.current_step
+    }
+
+    pub fn version(&self) -> usize {
+        self.version
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Marks that the cube got turned outside of the sequence, so that the state after a step is no longer known.
+    /// The steps are kept, but they can not be scrubbed to anymore.
+    pub fn detach(&mut self) {
+        self.detached = true;
+    }
+
+    pub fn is_detached(&self) -> bool {
+        self.detached
+    }
+
+    /// Go to the state after `step` number of steps have been applied. Intermediate steps are applied or
+    /// reverted without animation, once the current animation has finished. Pauses the sequence. Does nothing
+    /// once the sequence is detached.
+    pub fn scrub_to(&mut self, step: usize) {
+        if self.detached {
+            return;
+        }
+
+        self.scrub_target = Some(step.min(self.steps.len()));
+        self.paused = true;
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
     pub fn seconds_until_complete(&self) -> f32 {
         if self.is_done() {
             return 0.0;
@@ -71,14 +170,124 @@ impl SequenceResource {
 
         let mut result = seconds_until_current_step_is_complete;
 
-        for i in (self.current_step + 1)..self.steps.len() {
-            if let Some(animation) = &self.steps[i].animation {
-                result += animation.duration_in_seconds;
-            }
+        let mut group_start = self.current_step;
+        while group_start < self.steps.len() {
+            let group_end = self.step_group_end(group_start);
+            result += self.step_group_duration(group_start, group_end);
+            group_start = group_end;
         }
 
         return result;
     }
+
+    /// Returns the index after the last step of the group that starts at `group_start`. Steps in a group are
+    /// sent at the same time so that they are animated concurrently. Steps are only grouped when they are
+    /// animated fast, as is the case when scrambling big cubes.
+    fn step_group_end(&self, group_start: usize) -> usize {
+        let is_groupable = |step: &CubeRotationEvent| match &step.animation {
+            Some(animation) => animation.duration_in_seconds <= GROUP_STEPS_MAX_DURATION,
+            None => false,
+        };
+
+        if !is_groupable(&self.steps[group_start]) {
+            return group_start + 1;
+        }
+
+        let mut group_end = group_start + 1;
+        while group_end < self.steps.len() {
+            let step = &self.steps[group_end];
+            let can_join_group = is_groupable(step)
+                && self.steps[group_start..group_end]
+                    .iter()
+                    .all(|grouped_step| grouped_step.can_animate_concurrently(step));
+
+            if !can_join_group {
+                break;
+            }
+
+            group_end += 1;
+        }
+
+        group_end
+    }
+
+    /// The group is done once its slowest step is done
+    fn step_group_duration(&self, group_start: usize, group_end: usize) -> f32 {
+        let mut duration: f32 = 0.0;
+
+        for (i, step) in self.steps[group_start..group_end].iter().enumerate() {
+            let pause = self
+                .step_pauses
+                .get(group_start + i)
+                .copied()
+                .unwrap_or(0.0);
+
+            if let Some(animation) = &step.animation {
+                duration = duration.max(animation.duration_in_seconds + pause);
+            }
+        }
+
+        duration
+    }
+}
+
+/// The maximum number of user-initiated rotations that can be waiting for the current animation to finish
+const ROTATION_INPUT_QUEUE_CAPACITY: usize = 8;
+/// The animation duration of a queued rotation gets multiplied by this when more rotations are queued after it
+const QUEUED_ROTATION_DURATION_MULTIPLIER: f32 = 0.5;
+
+/// Buffers user-initiated rotations so that no inputs get lost while the cube is animating a rotation.
+/// Rotations are sent one by one once the previous animation has finished.
+#[derive(Resource)]
+pub struct RotationInputQueueResource {
+    queue: VecDeque<CubeRotationEvent>,
+    /// If true, queued rotations are animated faster while more rotations are waiting, so that the cube catches up.
+    pub speed_up_queued_animations: bool,
+}
+
+impl Default for RotationInputQueueResource {
+    fn default() -> Self {
+        Self {
+            queue: VecDeque::with_capacity(ROTATION_INPUT_QUEUE_CAPACITY),
+            speed_up_queued_animations: true,
+        }
+    }
+}
+
+impl RotationInputQueueResource {
+    /// Returns false if the rotation was dropped because the queue is full
+    pub fn push(&mut self, rotation_event: CubeRotationEvent) -> bool {
+        if self.queue.len() >= ROTATION_INPUT_QUEUE_CAPACITY {
+            return false;
+        }
+
+        self.queue.push_back(rotation_event);
+        true
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.queue.clear();
+    }
+
+    fn peek(&self) -> Option<&CubeRotationEvent> {
+        self.queue.front()
+    }
+
+    fn pop(&mut self) -> Option<CubeRotationEvent> {
+        let mut rotation_event = self.queue.pop_front()?;
+
+        if self.speed_up_queued_animations && !self.queue.is_empty() {
+            if let Some(animation) = &mut rotation_event.animation {
+                animation.duration_in_seconds *= QUEUED_ROTATION_DURATION_MULTIPLIER;
+            }
+        }
+
+        Some(rotation_event)
+    }
 }
 
 fn update_sequence_resouce_timer(mut sequence_resource: ResMut<SequenceResource>, time: Res<Time>) {
@@ -107,22 +316,53 @@ fn sequence_handler(
         return;
     }
 
+    if let Some(scrub_target) = sequence_resource.scrub_target.take() {
+        let current_step = sequence_resource.current_step;
+
+        if scrub_target > current_step {
+            for i in current_step..scrub_target {
+                let mut rotation_event = sequence_resource.steps[i].clone();
+                rotation_event.animation = None;
+                event_writer.send(rotation_event);
+            }
+        } else {
+            for i in (scrub_target..current_step).rev() {
+                let mut rotation_event = sequence_resource.steps[i].inverted();
+                rotation_event.animation = None;
+                event_writer.send(rotation_event);
+            }
+        }
+
+        sequence_resource.current_step = scrub_target;
+        sequence_resource.current_step_timer = None;
+    }
+
+    if sequence_resource.paused {
+        return;
+    }
+
     loop {
         if sequence_resource.current_step >= sequence_resource.steps.len() {
             return;
         }
 
-        let rotation_event = sequence_resource.steps[sequence_resource.current_step].clone();
-        event_writer.send(rotation_event);
-        sequence_resource.current_step += 1;
-
-        if let Some(animation) =
-            &sequence_resource.steps[sequence_resource.current_step - 1].animation
-        {
-            sequence_resource.current_step_timer = Some(Timer::from_seconds(
-                animation.duration_in_seconds,
-                TimerMode::Once,
-            ));
+        let group_start = sequence_resource.current_step;
+        let group_end = sequence_resource.step_group_end(group_start);
+
+        for i in group_start..group_end {
+            let rotation_event = sequence_resource.steps[i].clone();
+            event_writer.send(rotation_event);
+        }
+        sequence_resource.current_step = group_end;
+
+        let is_animated = sequence_resource.steps[group_start..group_end]
+            .iter()
+            .any(|step| step.animation.is_some());
+
+        if is_animated {
+            let group_duration = sequence_resource.step_group_duration(group_start, group_end);
+            sequence_resource.current_step_timer =
+                Some(Timer::from_seconds(group_duration, TimerMode::Once));
             return;
         }
 
@@ -162,6 +402,45 @@ fn rotation_event_finished_event_handler(
     }
 }
 
+fn rotation_input_queue_handler(
+    current_rotation: Res<CurrentRotationResource>,
+    fast_turning: Res<FastTurningResource>,
+    mut sequence_resource: ResMut<SequenceResource>,
+    mut rotation_input_queue: ResMut<RotationInputQueueResource>,
+    mut event_writer: EventWriter<CubeRotationEvent>,
+) {
+    if rotation_input_queue.is_empty() {
+        return;
+    }
+
+    // user input does not mix with sequences
+    if !sequence_resource.is_done() {
+        rotation_input_queue.clear();
+        return;
+    }
+
+    // with fast turning, a rotation may start while other rotations are still animating
+    if let Some(next_rotation_event) = rotation_input_queue.peek() {
+        if !current_rotation.can_start_rotation(next_rotation_event, fast_turning.0) {
+            return;
+        }
+    }
+
+    // the steps of the finished sequence no longer lead to the state of the cube
+    if !sequence_resource.steps.is_empty() && !sequence_resource.is_detached() {
+        sequence_resource.detach();
+    }
+
+    while let Some(rotation_event) = rotation_input_queue.pop() {
+        let is_animated = rotation_event.animation.is_some();
+        event_writer.send(rotation_event);
+
+        if is_animated {
+            return;
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct LastRandomFaceRotationEvent(CubeRotationEvent);
 
@@ -170,6 +449,8 @@ fn random_face_rotation_on_tab(
     mut last_random_face_rotation_query: Query<&mut LastRandomFaceRotationEvent>,
     cube_query: Query<&Cube>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    rotation_input_queue: Res<RotationInputQueueResource>,
+    mut sequence_resource: ResMut<SequenceResource>,
     mut event_writer: EventWriter<CubeRotationEvent>,
 ) {
     if !keyboard_input.pressed(KeyCode::Tab) {
@@ -181,7 +462,8 @@ fn random_face_rotation_on_tab(
         return;
     };
 
-    if cube.is_animating_rotation {
+    // holding tab keeps rotating, so there is no need to queue these rotations
+    if cube.is_animating_rotation || !rotation_input_queue.is_empty() {
         return;
     }
 
@@ -207,6 +489,14 @@ fn random_face_rotation_on_tab(
         duration_in_seconds: 0.15,
         ease_function: None,
     });
+
+    if sequence_resource.is_done()
+        && !sequence_resource.steps.is_empty()
+        && !sequence_resource.is_detached()
+    {
+        sequence_resource.detach();
+    }
+
     event_writer.send(rotation_event);
 }
 
@@ -226,3 +516,145 @@ fn check_solved_on_enter(query: Query<&CubeState>, keyboard_input: Res<ButtonInp
         info!("Cube is not solved");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate::cube::{
+        test_utils::headless_app, CubeRotationAnimation, CubeRotationEvent, CubeSize, CubeState,
+        Rotation,
+    };
+
+    use super::{
+        RotationInputQueueResource, SequenceResource, GROUP_STEPS_MAX_DURATION,
+        ROTATION_INPUT_QUEUE_CAPACITY,
+    };
+
+    fn animated_rotation_event() -> CubeRotationEvent {
+        CubeRotationEvent {
+            rotation: Rotation::face_x(1),
+            negative_direction: false,
+            twice: false,
+            animation: Some(CubeRotationAnimation {
+                duration_in_seconds: 1.0,
+                ease_function: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_rotation_input_queue_is_bounded() {
+        let mut queue = RotationInputQueueResource::default();
+
+        for _ in 0..ROTATION_INPUT_QUEUE_CAPACITY {
+            assert!(queue.push(animated_rotation_event()));
+        }
+
+        assert!(!queue.push(animated_rotation_event()));
+    }
+
+    #[test]
+    fn test_rotation_input_queue_speeds_up_queued_animations() {
+        let mut queue = RotationInputQueueResource::default();
+        queue.push(animated_rotation_event());
+        queue.push(animated_rotation_event());
+
+        // another rotation is waiting, so this one gets sped up
+        let first = queue.pop().unwrap();
+        assert!(first.animation.unwrap().duration_in_seconds < 1.0);
+
+        let last = queue.pop().unwrap();
+        assert_eq!(last.animation.unwrap().duration_in_seconds, 1.0);
+
+        assert!(queue.pop().is_none());
+    }
+
+    #[test]
+    fn test_sequence_groups_fast_disjoint_slice_rotations() {
+        let step = |rotation: Rotation, duration_in_seconds: f32| CubeRotationEvent {
+            rotation,
+            negative_direction: false,
+            twice: false,
+            animation: Some(CubeRotationAnimation {
+                duration_in_seconds,
+                ease_function: None,
+            }),
+        };
+        let fast = GROUP_STEPS_MAX_DURATION;
+
+        let mut sequence = SequenceResource::default();
+        sequence.set(vec![
+            step(Rotation::face_x(1), fast),
+            step(Rotation::face_x(-2), fast / 2.0),
+            step(Rotation::face_x(3), fast),
+            // same slice as a step in the group
+            step(Rotation::face_x(-2), fast),
+            // different axis
+            step(Rotation::face_y(1), fast),
+            // too slow to be grouped
+            step(Rotation::face_y(2), 1.0),
+        ]);
+
+        assert_eq!(3, sequence.step_group_end(0));
+        assert_eq!(fast, sequence.step_group_duration(0, 3));
+        assert_eq!(4, sequence.step_group_end(3));
+        assert_eq!(5, sequence.step_group_end(4));
+        assert_eq!(6, sequence.step_group_end(5));
+        assert_eq!(fast * 3.0 + 1.0, sequence.seconds_until_complete());
+    }
+
+    #[test]
+    fn test_sequence_step_pauses() {
+        let mut sequence = SequenceResource::default();
+        sequence.set_with_pauses(
+            vec![animated_rotation_event(), animated_rotation_event()],
+            vec![0.5, 0.0],
+        );
+        assert_eq!(2.5, sequence.seconds_until_complete());
+
+        // setting a new sequence clears the pauses
+        sequence.set(vec![animated_rotation_event(), animated_rotation_event()]);
+        assert_eq!(2.0, sequence.seconds_until_complete());
+    }
+
+    #[test]
+    fn test_user_turns_detach_a_finished_sequence() {
+        let mut app = headless_app(3);
+        let cube_state = |app: &mut App| {
+            app.world_mut()
+                .query::<&CubeState>()
+                .single(app.world())
+                .to_facelets()
+        };
+
+        let steps = vec![
+            CubeRotationEvent::from_notation("R", &CubeSize(3)).unwrap(),
+            CubeRotationEvent::from_notation("U", &CubeSize(3)).unwrap(),
+        ];
+        app.world_mut()
+            .resource_mut::<SequenceResource>()
+            .set(steps);
+        app.update();
+        assert!(app.world().resource::<SequenceResource>().is_done());
+
+        app.world_mut()
+            .resource_mut::<RotationInputQueueResource>()
+            .push(CubeRotationEvent::from_notation("F", &CubeSize(3)).unwrap());
+        app.update();
+        let turned_state = cube_state(&mut app);
+        assert!(app.world().resource::<SequenceResource>().is_detached());
+
+        // scrubbing would revert the steps on top of the user turn
+        app.world_mut()
+            .resource_mut::<SequenceResource>()
+            .scrub_to(0);
+        app.update();
+        assert_eq!(turned_state, cube_state(&mut app));
+
+        // a new sequence can be scrubbed again
+        let mut sequence = app.world_mut().resource_mut::<SequenceResource>();
+        sequence.set(vec![]);
+        assert!(!sequence.is_detached());
+    }
+}
@@ -4,12 +4,14 @@ use bevy::{ecs::system::SystemId, prelude::*};
 
 use crate::schedules::CubeStartupSet;
 
+use rubiks_cube::model::{Axis, CubeSize, CubeState};
+
 use super::{
-    axis::Axis,
-    controller::ControllerPlugin,
-    cube_state::CubeState,
+    animation_profile::AnimationProfilePlugin,
     interact_to_rotate::{self, InteractToRotatePlugin},
-    rotation::CubeRotationPlugin,
+    keymap::KeymapPlugin,
+    ConsistencyPlugin, ControllerPlugin, CubeRotationPlugin, RotationInputQueueResource,
+    SetCubeStatePlugin,
 };
 
 const SPACE_BETWEEN_PIECES: f32 = 0.04;
@@ -36,9 +38,13 @@ impl Plugin for CubePlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(CurrentCubeSizeResource(DEFAULT_CUBE_SIZE))
             .init_resource::<CubeCommandsResource>()
+            .add_plugins(AnimationProfilePlugin)
             .add_plugins(ControllerPlugin)
             .add_plugins(CubeRotationPlugin)
+            .add_plugins(ConsistencyPlugin)
+            .add_plugins(SetCubeStatePlugin)
             .add_plugins(InteractToRotatePlugin)
+            .add_plugins(KeymapPlugin)
             .add_systems(Startup, spawn.in_set(CubeStartupSet::SpawnCube));
     }
 }
@@ -76,6 +82,16 @@ pub struct Cube {
 }
 
 impl Cube {
+    pub fn new(cube_size: usize, inner_material: Handle<StandardMaterial>) -> Self {
+        Self {
+            cube_size: CubeSize(cube_size as i32),
+            space_between_pieces: SPACE_BETWEEN_PIECES,
+            piece_size: PIECE_SIZE,
+            inner_material,
+            is_animating_rotation: false,
+        }
+    }
+
     pub fn size(&self) -> &CubeSize {
         &self.cube_size
     }
@@ -88,26 +104,29 @@ impl Cube {
     pub fn space_between_pieces(&self) -> f32 {
         self.space_between_pieces
     }
-}
-
-#[derive(Clone, Debug)]
-pub struct CubeSize(pub i32);
 
-impl CubeSize {
-    pub fn lowest_piece_index(&self) -> i32 {
-        if self.0 % 2 == 1 {
-            -(self.0 as i32 - 1) / 2
-        } else {
-            -self.0 as i32 / 2
+    /// The position of the middle of the piece with the given indices, when the cube is solved
+    pub fn piece_translation(&self, x: i32, y: i32, z: i32) -> Vec3 {
+        let distance_between_pieces = self.piece_size + self.space_between_pieces;
+        let mut result = Vec3::new(x as f32, y as f32, z as f32) * distance_between_pieces;
+
+        if self.cube_size.0 % 2 == 0 {
+            // even sized cubes like 2x2 and 4x4 do not have a slice 0, so the pieces next to the middle are only
+            // half the distance away from it
+            result -= Vec3::new(
+                x.clamp(-1, 1) as f32,
+                y.clamp(-1, 1) as f32,
+                z.clamp(-1, 1) as f32,
+            ) * distance_between_pieces
+                / 2.0;
         }
+
+        result
     }
 
-    pub fn highest_piece_index(&self) -> i32 {
-        if self.0 % 2 == 1 {
-            (self.0 as i32 - 1) / 2
-        } else {
-            self.0 as i32 / 2
-        }
+    /// The material of the sides of the pieces that are not stickers
+    pub fn inner_material(&self) -> &Handle<StandardMaterial> {
+        &self.inner_material
     }
 }
 
@@ -145,7 +164,14 @@ impl Piece {
 #[derive(Component)]
 pub struct PieceFace;
 
-fn despawn(mut commands: Commands, query: Query<Entity, With<Cube>>) {
+fn despawn(
+    mut commands: Commands,
+    query: Query<Entity, With<Cube>>,
+    mut rotation_input_queue: ResMut<RotationInputQueueResource>,
+) {
+    // queued rotations might not be valid for the next cube
+    rotation_input_queue.clear();
+
     let cube_entity = match query.get_single() {
         Ok(entity) => entity,
         Err(err) => {
@@ -170,13 +196,7 @@ fn spawn(
         return;
     }
 
-    let cube = Cube {
-        cube_size: CubeSize(cube_size as i32),
-        space_between_pieces: SPACE_BETWEEN_PIECES,
-        piece_size: PIECE_SIZE,
-        inner_material: materials.add(Color::srgb(0.1, 0.1, 0.1)),
-        is_animating_rotation: false,
-    };
+    let cube = Cube::new(cube_size, materials.add(Color::srgb(0.1, 0.1, 0.1)));
 
     let piece_face_mesh = meshes.add(Rectangle {
         half_size: (Vec2::ONE * cube.piece_size) / 2.0,
@@ -197,37 +217,12 @@ fn spawn(
     for x in range.clone() {
         for y in range.clone() {
             for z in range.clone() {
-                let piece_middle_point = if cube.size().0 % 2 == 0 {
-                    // even sized cubes like 2x2 and 4x4
-
-                    if x == 0 || y == 0 || z == 0 {
-                        // even sized cubes do not have a slice 0
-                        continue;
-                    }
-
-                    let mut result = Vec3::new(
-                        x as f32 * cube.piece_size + x as f32 * cube.space_between_pieces,
-                        y as f32 * cube.piece_size + y as f32 * cube.space_between_pieces,
-                        z as f32 * cube.piece_size + z as f32 * cube.space_between_pieces,
-                    );
-
-                    let offset = cube.piece_size / 2.0 + cube.space_between_pieces / 2.0;
-                    result -= Vec3::new(
-                        x.clamp(-1, 1) as f32 * offset,
-                        y.clamp(-1, 1) as f32 * offset,
-                        z.clamp(-1, 1) as f32 * offset,
-                    );
-
-                    result
-                } else {
-                    // even sized cubes like 3x3 and 5x5
-
-                    Vec3::new(
-                        x as f32 * cube.piece_size + x as f32 * cube.space_between_pieces,
-                        y as f32 * cube.piece_size + y as f32 * cube.space_between_pieces,
-                        z as f32 * cube.piece_size + z as f32 * cube.space_between_pieces,
-                    )
-                };
+                if cube.size().0 % 2 == 0 && (x == 0 || y == 0 || z == 0) {
+                    // even sized cubes do not have a slice 0
+                    continue;
+                }
+
+                let piece_middle_point = cube.piece_translation(x, y, z);
 
                 cube_entity.with_children(|parent| {
                     let mut piece_entity = parent.spawn((
@@ -362,7 +357,6 @@ fn spawn(
     cube_entity.with_children(|parent| {
         interact_to_rotate::spawn(
             parent,
-            &mut materials,
             &mut meshes,
             cube_size,
             cube.piece_size,
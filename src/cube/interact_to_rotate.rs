@@ -1,48 +1,161 @@
 use std::f32::consts::TAU;
 
-use bevy::{picking::pointer::PointerInteraction, prelude::*};
+use bevy::{picking::pointer::PointerInteraction, prelude::*, window::PrimaryWindow};
 
 use crate::{
-    cube::{axis::Axis, slice::column_index_to_slice, CubeRotationAnimation, Rotation},
+    cube::{CubeRotationAnimation, EaseFunction, Rotation},
+    interface::interface::DEFAULT_FONT_BOLD,
     schedules::CubeScheduleSet,
 };
 
-use super::{Cube, CubeRotationEvent, SequenceResource};
+use rubiks_cube::model::{slice::position_to_slice, Axis};
+
+use super::{
+    Cube, CubeRotationEvent, Piece, PieceFace, RotationInputQueueResource, SequenceResource,
+};
 
 pub struct InteractToRotatePlugin;
 
 impl Plugin for InteractToRotatePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            handle_picking_hover.in_set(CubeScheduleSet::HandleUserInput),
-        );
+        app.insert_resource(DragGestureResource(None))
+            .insert_resource(MovePreviewResource(None))
+            .insert_resource(DragToRotateResource(true))
+            .insert_gizmo_config(
+                MovePreviewGizmos,
+                GizmoConfig {
+                    line_width: 6.0,
+                    // draw the arrow on top of the stickers
+                    depth_bias: -1.0,
+                    ..default()
+                },
+            )
+            .add_systems(Startup, spawn_move_preview_label)
+            .add_systems(
+                Update,
+                (
+                    handle_drag_gesture,
+                    (
+                        highlight_move_preview_stickers,
+                        draw_move_preview_arrow,
+                        place_move_preview_label,
+                    ),
+                )
+                    .chain()
+                    .run_if(|drag_to_rotate: Res<DragToRotateResource>| drag_to_rotate.0)
+                    .in_set(CubeScheduleSet::HandleUserInput),
+            );
     }
 }
 
-#[derive(Component)]
+/// Whether the cube can be turned by dragging across it. Modes that use clicks on the cube for something else turn
+/// this off.
+#[derive(Resource)]
+pub struct DragToRotateResource(pub bool);
+
+#[derive(Component, Clone, Copy)]
 pub enum Face {
     Top,
+    Bottom,
     Front,
+    Back,
     Right,
+    Left,
+}
+
+impl Face {
+    fn normal(&self) -> Vec3 {
+        match self {
+            Face::Top => Vec3::Y,
+            Face::Bottom => Vec3::NEG_Y,
+            Face::Front => Vec3::Z,
+            Face::Back => Vec3::NEG_Z,
+            Face::Right => Vec3::X,
+            Face::Left => Vec3::NEG_X,
+        }
+    }
 }
 
 #[derive(Component)]
 pub struct FaceTop;
 #[derive(Component)]
+pub struct FaceBottom;
+#[derive(Component)]
 pub struct FaceFront;
 #[derive(Component)]
+pub struct FaceBack;
+#[derive(Component)]
 pub struct FaceRight;
+#[derive(Component)]
+pub struct FaceLeft;
 
 #[derive(Component)]
 pub struct OriginalColliderMeshSize(f32);
 
+/// The notation of the move that is being previewed, shown next to the cursor
 #[derive(Component)]
-pub struct Indicator;
+struct MovePreviewLabel;
+
+#[derive(Default, Reflect, GizmoConfigGroup)]
+struct MovePreviewGizmos;
+
+/// The minimum distance to drag for a gesture to turn a slice, relative to the size of a single piece
+const MIN_DRAG_DISTANCE: f32 = 0.3;
+/// How far the arrow of the move preview floats above the face, relative to the size of a single piece
+const PREVIEW_ARROW_OFFSET: f32 = 0.05;
+const PREVIEW_ARROW_COLOR: Color = Color::srgb(1.0, 1.0, 1.0);
+const PREVIEW_HIGHLIGHT_EMISSIVE: LinearRgba = LinearRgba::rgb(0.35, 0.35, 0.35);
+
+/// A press-drag-release gesture that started on one of the faces of the cube
+struct DragGesture {
+    face_entity: Entity,
+    face: Face,
+    /// Where the gesture started, relative to the center of the cube and without the scale of the cube
+    start_position: Vec3,
+}
+
+#[derive(Resource)]
+struct DragGestureResource(Option<DragGesture>);
+
+/// The rotation that a drag gesture results in
+struct GestureRotation {
+    axis: Axis,
+    slice: i32,
+    negative_direction: bool,
+    /// The direction along the face that the drag snapped to
+    direction: Vec3,
+}
+
+impl GestureRotation {
+    fn to_rotation_event(&self) -> CubeRotationEvent {
+        CubeRotationEvent {
+            rotation: Rotation::face(self.axis, self.slice),
+            negative_direction: self.negative_direction,
+            twice: false,
+            animation: Some(CubeRotationAnimation {
+                duration_in_seconds: 0.3,
+                ease_function: Some(EaseFunction::CubicOut),
+            }),
+        }
+    }
+}
+
+/// The move that would be made when releasing the current drag gesture
+struct MovePreview {
+    rotation_event: CubeRotationEvent,
+    axis: Axis,
+    slice: i32,
+    /// Start and end of the arrow in the direction of the drag, relative to the center of the cube and without the
+    /// scale of the cube
+    arrow_start: Vec3,
+    arrow_end: Vec3,
+}
+
+#[derive(Resource)]
+struct MovePreviewResource(Option<MovePreview>);
 
 pub fn spawn(
     parent: &mut ChildBuilder<'_>,
-    materials: &mut ResMut<Assets<StandardMaterial>>,
     meshes: &mut ResMut<Assets<Mesh>>,
     cube_size: usize,
     block_size: f32,
@@ -50,28 +163,14 @@ pub fn spawn(
 ) {
     let face_size = (cube_size as f32 * block_size) + ((cube_size - 1) as f32 * piece_spread);
 
-    spawn_colliders(parent, materials, meshes, face_size);
-
-    spawn_indicator(
-        parent,
-        materials,
-        meshes,
-        block_size,
-        piece_spread,
-        face_size,
-    );
+    spawn_colliders(parent, meshes, face_size);
 }
 
 pub fn spawn_colliders(
     parent: &mut ChildBuilder<'_>,
-    _materials: &mut ResMut<Assets<StandardMaterial>>,
     meshes: &mut ResMut<Assets<Mesh>>,
     face_size: f32,
 ) {
-    // let debug_material: MeshMaterial3d<StandardMaterial> =
-    //     MeshMaterial3d(materials.add(Color::srgb(0.5019608, 0.0, 0.5019608).with_alpha(0.5)));
-    let debug_material = ();
-
     let distance_from_center = face_size / 2.0;
     let collider_mesh = meshes.add(Rectangle {
         half_size: Vec2::ONE * face_size / 2.0,
@@ -87,7 +186,6 @@ pub fn spawn_colliders(
 
     parent.spawn((
         Mesh3d(collider_mesh.clone()),
-        debug_material.clone(),
         transform,
         FaceTop,
         OriginalColliderMeshSize(face_size),
@@ -103,7 +201,6 @@ pub fn spawn_colliders(
 
     parent.spawn((
         Mesh3d(collider_mesh.clone()),
-        debug_material.clone(),
         transform,
         FaceFront,
         OriginalColliderMeshSize(face_size),
@@ -120,58 +217,94 @@ pub fn spawn_colliders(
 
     parent.spawn((
         Mesh3d(collider_mesh.clone()),
-        debug_material.clone(),
         transform,
         FaceRight,
         OriginalColliderMeshSize(face_size),
         Face::Right,
     ));
-}
 
-pub fn spawn_indicator(
-    parent: &mut ChildBuilder<'_>,
-    materials: &mut ResMut<Assets<StandardMaterial>>,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    block_size: f32,
-    piece_spread: f32,
-    face_size: f32,
-) {
-    let mesh = meshes.add(Cuboid {
-        half_size: Vec3 {
-            x: (block_size + piece_spread) / 2.0,
-            y: (face_size + piece_spread) / 2.0,
-            z: (face_size + piece_spread) / 2.0,
-        },
+    // bottom
+    let mut transform = Transform::from_translation(Vec3 {
+        x: 0.0,
+        y: -distance_from_center,
+        z: 0.0,
+    });
+    transform.rotate_x(TAU / 4.0);
+
+    parent.spawn((
+        Mesh3d(collider_mesh.clone()),
+        transform,
+        FaceBottom,
+        OriginalColliderMeshSize(face_size),
+        Face::Bottom,
+    ));
+
+    // back
+    let mut transform = Transform::from_translation(Vec3 {
+        x: 0.0,
+        y: 0.0,
+        z: -distance_from_center,
     });
-    let material = MeshMaterial3d(materials.add(Color::srgba(0.0, 0.0, 0.0, 0.7)));
+    transform.rotate_local_y(TAU / 2.0);
+
     parent.spawn((
-        Mesh3d(mesh),
-        material,
-        Transform::default(),
-        Indicator,
+        Mesh3d(collider_mesh.clone()),
+        transform,
+        FaceBack,
+        OriginalColliderMeshSize(face_size),
+        Face::Back,
+    ));
+
+    // left
+    let mut transform = Transform::from_translation(Vec3 {
+        x: -distance_from_center,
+        y: 0.0,
+        z: 0.0,
+    });
+    transform.rotate_local_y(-TAU / 4.0);
+
+    parent.spawn((
+        Mesh3d(collider_mesh.clone()),
+        transform,
+        FaceLeft,
+        OriginalColliderMeshSize(face_size),
+        Face::Left,
+    ));
+}
+
+fn spawn_move_preview_label(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        MovePreviewLabel,
+        Node {
+            position_type: PositionType::Absolute,
+            padding: UiRect::axes(Val::Px(8.0), Val::Px(2.0)),
+            ..default()
+        },
+        Text::new(""),
+        TextFont {
+            font: asset_server.load(DEFAULT_FONT_BOLD),
+            font_size: 24.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        BorderRadius::all(Val::Px(4.0)),
+        GlobalZIndex(1),
         PickingBehavior::IGNORE,
         Visibility::Hidden,
     ));
 }
 
-fn handle_picking_hover(
+fn handle_drag_gesture(
     pointers: Query<&PointerInteraction>,
-    top_face_query: Query<
-        (Entity, &GlobalTransform, &OriginalColliderMeshSize, &Face),
-        With<FaceTop>,
-    >,
-    front_face_query: Query<
-        (Entity, &GlobalTransform, &OriginalColliderMeshSize, &Face),
-        With<FaceFront>,
-    >,
-    right_face_query: Query<
-        (Entity, &GlobalTransform, &OriginalColliderMeshSize, &Face),
-        With<FaceRight>,
-    >,
-    mut indicator_query: Query<(&mut Transform, &mut Visibility), With<Indicator>>,
+    face_query: Query<(Entity, &GlobalTransform, &OriginalColliderMeshSize, &Face)>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
     cube_query: Query<&Cube>,
     current_sequence: Res<SequenceResource>,
-    mut event_writer: EventWriter<CubeRotationEvent>,
+    mut drag_gesture: ResMut<DragGestureResource>,
+    mut move_preview: ResMut<MovePreviewResource>,
+    mut rotation_input_queue: ResMut<RotationInputQueueResource>,
     mouse_input: Res<ButtonInput<MouseButton>>,
 ) {
     let Ok(cube) = cube_query.get_single() else {
@@ -179,141 +312,447 @@ fn handle_picking_hover(
         return;
     };
 
-    let (mut indicator_transform, mut indicator_visibility) =
-        indicator_query.get_single_mut().unwrap();
+    move_preview.0 = None;
 
-    if cube.is_animating_rotation || !current_sequence.is_done() {
-        *indicator_visibility = Visibility::Hidden;
+    if !current_sequence.is_done() {
+        drag_gesture.0 = None;
         return;
     }
 
-    let Ok(top_face) = top_face_query.get_single() else {
-        warn!("couldn't find top face");
+    if mouse_input.just_pressed(MouseButton::Left) {
+        drag_gesture.0 = None;
+
+        for (entity, hit_data) in pointers
+            .iter()
+            .filter_map(|interaction| interaction.get_nearest_hit())
+        {
+            let Ok((_, global_transform, original_mesh_size, face)) = face_query.get(*entity)
+            else {
+                // not a hit we are interested in
+                continue;
+            };
+
+            let Some(hit_position) = hit_data.position else {
+                warn!("hit position is None, expected Some");
+                continue;
+            };
+
+            drag_gesture.0 = Some(DragGesture {
+                face_entity: *entity,
+                face: *face,
+                start_position: position_relative_to_cube(
+                    hit_position,
+                    global_transform,
+                    original_mesh_size,
+                    face,
+                ),
+            });
+            break;
+        }
+    }
+
+    let Some(gesture) = &drag_gesture.0 else {
         return;
     };
-    let Ok(front_face) = front_face_query.get_single() else {
-        warn!("couldn't find front face");
+
+    let Ok((_, global_transform, original_mesh_size, _)) = face_query.get(gesture.face_entity)
+    else {
+        warn!("couldn't find the face the drag gesture started on");
+        drag_gesture.0 = None;
         return;
     };
-    let Ok(right_face) = right_face_query.get_single() else {
-        warn!("couldn't find right face");
+
+    let Some(pointer_position) = pointer_position_on_face(
+        &camera_query,
+        &window_query,
+        global_transform,
+        original_mesh_size,
+        &gesture.face,
+    ) else {
+        // the pointer is not over the plane of the face, for example because it left the window
+        if !mouse_input.pressed(MouseButton::Left) {
+            drag_gesture.0 = None;
+        }
         return;
     };
 
-    let mut did_interact_with_face = false;
-
-    for (entity, hit_data) in pointers
-        .iter()
-        .filter_map(|interaction| interaction.get_nearest_hit())
-    {
-        let (_, global_transform, original_mesh_size, face) = if *entity == top_face.0 {
-            top_face
-        } else if *entity == front_face.0 {
-            front_face
-        } else if *entity == right_face.0 {
-            right_face
-        } else {
-            // not a hit we are interested in
-            *indicator_visibility = Visibility::Hidden;
-            break;
-        };
+    let drag = pointer_position - gesture.start_position;
+    let gesture_rotation = get_gesture_rotation(
+        gesture.face.normal(),
+        gesture.start_position,
+        drag,
+        original_mesh_size.0,
+        cube.size().0,
+    );
 
-        let Some(hit_position) = hit_data.position else {
-            warn!("hit position is None, expected Some");
-            break;
-        };
+    if mouse_input.pressed(MouseButton::Left) {
+        // Gestures are still accepted while animating, they get queued until the animation is done.
+        if let Some(gesture_rotation) = gesture_rotation {
+            if !cube.is_animating_rotation {
+                let arrow_start = gesture.start_position
+                    + gesture.face.normal() * PREVIEW_ARROW_OFFSET * cube.piece_size();
+
+                move_preview.0 = Some(MovePreview {
+                    rotation_event: gesture_rotation.to_rotation_event(),
+                    axis: gesture_rotation.axis,
+                    slice: gesture_rotation.slice,
+                    arrow_start,
+                    arrow_end: arrow_start
+                        + gesture_rotation.direction * drag.dot(gesture_rotation.direction),
+                });
+            }
+        }
 
-        did_interact_with_face = true;
-        *indicator_visibility = Visibility::Visible;
+        return;
+    }
 
-        let cube_size = cube.size().0;
+    // the gesture is done once the mouse button is released
+    drag_gesture.0 = None;
 
-        // global_transform.scale() its x, y and z will always be the same so it doesn't matter which one we pick.
-        let scale = global_transform.scale().x;
+    let Some(gesture_rotation) = gesture_rotation else {
+        return;
+    };
 
-        let face_hit_position_world_vec3 = hit_position - global_transform.translation();
-        let face_hit_position_world_vec2 = match face {
-            Face::Top => face_hit_position_world_vec3.xz(),
-            Face::Front => face_hit_position_world_vec3.xy(),
-            Face::Right => face_hit_position_world_vec3.zy() * Vec2::new(-1.0, 1.0),
-        } / scale;
+    if !rotation_input_queue.push(gesture_rotation.to_rotation_event()) {
+        debug!("rotation input queue is full, ignoring drag gesture");
+    }
+}
 
-        let mut face_hit_position_normalised =
-            face_hit_position_world_vec2 + original_mesh_size.0 / 2.0;
-        face_hit_position_normalised *= cube_size as f32 / original_mesh_size.0;
+/// Converts a world position on a face collider to a position relative to the center of the cube, without the
+/// scale of the cube.
+fn position_relative_to_cube(
+    world_position: Vec3,
+    face_global_transform: &GlobalTransform,
+    original_mesh_size: &OriginalColliderMeshSize,
+    face: &Face,
+) -> Vec3 {
+    // global_transform.scale() its x, y and z will always be the same so it doesn't matter which one we pick.
+    let scale = face_global_transform.scale().x;
+
+    (world_position - face_global_transform.translation()) / scale
+        + face.normal() * original_mesh_size.0 / 2.0
+}
 
-        // ranging from `0..cube_size`. Not what we can use to rotate cube slice slice yet
-        let (relative_slice_index_x, relative_slice_index_y) = (
-            face_hit_position_normalised.x.floor() as i32,
-            face_hit_position_normalised.y.floor() as i32,
-        );
+/// Where the pointer is on the plane of the given face, relative to the center of the cube
+fn pointer_position_on_face(
+    camera_query: &Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+    window_query: &Query<&Window, With<PrimaryWindow>>,
+    face_global_transform: &GlobalTransform,
+    original_mesh_size: &OriginalColliderMeshSize,
+    face: &Face,
+) -> Option<Vec3> {
+    let (camera, camera_transform) = camera_query.get_single().ok()?;
+    let cursor_position = window_query.get_single().ok()?.cursor_position()?;
+    let ray = camera
+        .viewport_to_world(camera_transform, cursor_position)
+        .ok()?;
+
+    let distance = ray.intersect_plane(
+        face_global_transform.translation(),
+        InfinitePlane3d::new(face.normal()),
+    )?;
+
+    Some(position_relative_to_cube(
+        ray.get_point(distance),
+        face_global_transform,
+        original_mesh_size,
+        face,
+    ))
+}
 
-        // picking the very edge of the face will result in the index being 1 too high.
-        let column_index_x = relative_slice_index_x.clamp(0, cube_size - 1);
-        let column_index_y = relative_slice_index_y.clamp(0, cube_size - 1);
+/// Get the rotation for a drag across a face. The drag snaps to the direction along the face that it is most
+/// aligned with. The slice that contains the start of the drag turns along with the drag.
+///
+/// `start_position` and `drag` are relative to the center of the cube, in the same unit as `face_size`.
+fn get_gesture_rotation(
+    face_normal: Vec3,
+    start_position: Vec3,
+    drag: Vec3,
+    face_size: f32,
+    cube_size: i32,
+) -> Option<GestureRotation> {
+    let piece_size = face_size / cube_size as f32;
+
+    // only the part of the drag that goes along the face counts
+    let drag = drag - face_normal * drag.dot(face_normal);
+    if drag.length() < MIN_DRAG_DISTANCE * piece_size {
+        return None;
+    }
 
-        let slice_x = column_index_to_slice(column_index_x, cube_size as usize);
-        let slice_y = column_index_to_slice(column_index_y, cube_size as usize);
+    let drag_axis = if drag.x.abs() >= drag.y.abs() && drag.x.abs() >= drag.z.abs() {
+        Vec3::X
+    } else if drag.y.abs() >= drag.z.abs() {
+        Vec3::Y
+    } else {
+        Vec3::Z
+    };
 
-        // TODO base this on where the mouse come from before entering the current face
-        let (axis, slice) = match face {
-            Face::Top => (Axis::Z, slice_y),
-            Face::Front => (Axis::X, slice_x),
-            Face::Right => (Axis::Y, slice_y),
-        };
+    let rotation_axis = face_normal.cross(drag_axis).abs();
+    let axis = if rotation_axis.x > 0.5 {
+        Axis::X
+    } else if rotation_axis.y > 0.5 {
+        Axis::Y
+    } else {
+        Axis::Z
+    };
 
-        let total_piece_spread = slice as f32 * cube.space_between_pieces();
-        let mut offset = slice as f32 * cube.piece_size() + total_piece_spread;
-        if cube_size % 2 == 0 {
-            offset -= slice.clamp(-1, 1) as f32
-                * (cube.piece_size() / 2.0 + cube.space_between_pieces() / 2.0);
-        }
+    // A positive rotation moves the pieces on the face in this direction
+    let positive_rotation_direction = rotation_axis.cross(face_normal);
+    let negative_direction = drag.dot(positive_rotation_direction) < 0.0;
+
+    Some(GestureRotation {
+        axis,
+        slice: position_to_slice(
+            start_position.dot(rotation_axis),
+            face_size,
+            cube_size as usize,
+        ),
+        negative_direction,
+        direction: drag_axis * drag.dot(drag_axis).signum(),
+    })
+}
 
-        match axis {
-            Axis::X => {
-                indicator_transform.translation = Vec3 {
-                    x: offset,
-                    y: 0.0,
-                    z: 0.0,
-                };
-                indicator_transform.rotation = Quat::default();
-            }
-            Axis::Y => {
-                indicator_transform.translation = Vec3 {
-                    x: 0.0,
-                    y: offset,
-                    z: 0.0,
-                };
-                indicator_transform.rotation = Quat::from_rotation_z(TAU / 4.0);
-            }
-            Axis::Z => {
-                indicator_transform.translation = Vec3 {
-                    x: 0.0,
-                    y: 0.0,
-                    z: offset,
-                };
-                indicator_transform.rotation = Quat::from_rotation_y(TAU / 4.0);
-            }
+/// Make the stickers of the slice that would turn light up
+fn highlight_move_preview_stickers(
+    move_preview: Res<MovePreviewResource>,
+    cube_query: Query<&Cube>,
+    piece_query: Query<&Piece>,
+    piece_face_query: Query<(&Parent, &MeshMaterial3d<StandardMaterial>), With<PieceFace>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let Ok(cube) = cube_query.get_single() else {
+        return;
+    };
+
+    for (parent, material) in &piece_face_query {
+        // the inner material is shared by all pieces
+        if material.0 == *cube.inner_material() {
+            continue;
         }
 
-        if !mouse_input.just_pressed(MouseButton::Left)
-            && !mouse_input.just_pressed(MouseButton::Right)
-        {
-            break;
+        let Ok(piece) = piece_query.get(parent.get()) else {
+            continue;
         };
 
-        event_writer.send(CubeRotationEvent {
-            rotation: Rotation::face(axis, slice),
-            negative_direction: mouse_input.just_pressed(MouseButton::Right),
-            twice: false,
-            animation: Some(CubeRotationAnimation {
-                duration_in_seconds: 0.3,
-                ease_function: Some(EaseFunction::CubicOut),
-            }),
+        let is_highlighted = move_preview.0.as_ref().is_some_and(|preview| {
+            let piece_slice = match preview.axis {
+                Axis::X => piece.current_x,
+                Axis::Y => piece.current_y,
+                Axis::Z => piece.current_z,
+            };
+
+            piece_slice == preview.slice
         });
+
+        let emissive = if is_highlighted {
+            PREVIEW_HIGHLIGHT_EMISSIVE
+        } else {
+            LinearRgba::BLACK
+        };
+
+        // only get the material mutably when it changes, so that it doesn't get re-uploaded every frame
+        if materials
+            .get(&material.0)
+            .is_some_and(|material| material.emissive != emissive)
+        {
+            if let Some(material) = materials.get_mut(&material.0) {
+                material.emissive = emissive;
+            }
+        }
+    }
+}
+
+fn draw_move_preview_arrow(
+    move_preview: Res<MovePreviewResource>,
+    cube_query: Query<&GlobalTransform, With<Cube>>,
+    mut gizmos: Gizmos<MovePreviewGizmos>,
+) {
+    let Some(preview) = &move_preview.0 else {
+        return;
+    };
+
+    let Ok(cube_transform) = cube_query.get_single() else {
+        return;
+    };
+
+    gizmos
+        .arrow(
+            cube_transform.transform_point(preview.arrow_start),
+            cube_transform.transform_point(preview.arrow_end),
+            PREVIEW_ARROW_COLOR,
+        )
+        .with_tip_length(0.3 * cube_transform.scale().x);
+}
+
+fn place_move_preview_label(
+    move_preview: Res<MovePreviewResource>,
+    cube_query: Query<&Cube>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut label_query: Query<(&mut Text, &mut Node, &mut Visibility), With<MovePreviewLabel>>,
+) {
+    let Ok((mut text, mut node, mut visibility)) = label_query.get_single_mut() else {
+        error!("expected exactly 1 MovePreviewLabel component");
+        return;
+    };
+
+    let cursor_position = window_query
+        .get_single()
+        .ok()
+        .and_then(|window| window.cursor_position());
+
+    let (Some(preview), Ok(cube), Some(cursor_position)) =
+        (&move_preview.0, cube_query.get_single(), cursor_position)
+    else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    *visibility = Visibility::Visible;
+    node.left = Val::Px(cursor_position.x + 16.0);
+    node.top = Val::Px(cursor_position.y - 40.0);
+
+    let notation = preview.rotation_event.to_notation(cube.size());
+    if text.0 != notation {
+        text.0 = notation;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::Vec3;
+
+    use rubiks_cube::model::Axis;
+
+    use super::get_gesture_rotation;
+
+    const FACE_SIZE: f32 = 3.0;
+
+    #[test]
+    fn test_gesture_rotation_front_face() {
+        // drag the top row of the front face to the right: U'
+        let rotation = get_gesture_rotation(
+            Vec3::Z,
+            Vec3::new(0.0, 1.2, 1.5),
+            Vec3::new(1.0, 0.1, 0.0),
+            FACE_SIZE,
+            3,
+        )
+        .unwrap();
+        assert!(matches!(rotation.axis, Axis::Y));
+        assert_eq!(1, rotation.slice);
+        assert!(!rotation.negative_direction);
+        assert_eq!(Vec3::X, rotation.direction);
+
+        // drag the right column of the front face up: R
+        let rotation = get_gesture_rotation(
+            Vec3::Z,
+            Vec3::new(1.2, -1.0, 1.5),
+            Vec3::new(-0.2, 1.0, 0.0),
+            FACE_SIZE,
+            3,
+        )
+        .unwrap();
+        assert!(matches!(rotation.axis, Axis::X));
+        assert_eq!(1, rotation.slice);
+        assert!(rotation.negative_direction);
+    }
+
+    #[test]
+    fn test_gesture_rotation_top_and_right_face() {
+        // drag the right column of the top face towards the front: R'
+        let rotation = get_gesture_rotation(
+            Vec3::Y,
+            Vec3::new(1.2, 1.5, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+            FACE_SIZE,
+            3,
+        )
+        .unwrap();
+        assert!(matches!(rotation.axis, Axis::X));
+        assert_eq!(1, rotation.slice);
+        assert!(!rotation.negative_direction);
+        assert_eq!(Vec3::Z, rotation.direction);
+
+        // drag the front column of the right face up, on a 4x4: F'
+        let rotation = get_gesture_rotation(
+            Vec3::X,
+            Vec3::new(1.5, 0.0, 1.4),
+            Vec3::new(0.0, 1.0, 0.0),
+            FACE_SIZE,
+            4,
+        )
+        .unwrap();
+        assert!(matches!(rotation.axis, Axis::Z));
+        assert_eq!(2, rotation.slice);
+        assert!(!rotation.negative_direction);
+    }
+
+    #[test]
+    fn test_gesture_rotation_ignores_short_drags() {
+        assert!(get_gesture_rotation(
+            Vec3::Z,
+            Vec3::new(0.0, 0.0, 1.5),
+            // dragging into the face does not count
+            Vec3::new(0.1, 0.0, -2.0),
+            FACE_SIZE,
+            3,
+        )
+        .is_none());
     }
 
-    if !did_interact_with_face {
-        *indicator_visibility = Visibility::Hidden;
+    #[test]
+    fn test_gesture_rotation_bottom_back_and_left_face() {
+        // drag the top row of the back face to the right (as seen from the front): U
+        let rotation = get_gesture_rotation(
+            Vec3::NEG_Z,
+            Vec3::new(0.0, 1.2, -1.5),
+            Vec3::new(1.0, 0.0, 0.0),
+            FACE_SIZE,
+            3,
+        )
+        .unwrap();
+        assert!(matches!(rotation.axis, Axis::Y));
+        assert_eq!(1, rotation.slice);
+        assert!(rotation.negative_direction);
+
+        // drag the front column of the left face up: F
+        let rotation = get_gesture_rotation(
+            Vec3::NEG_X,
+            Vec3::new(-1.5, 0.0, 1.2),
+            Vec3::new(0.0, 1.0, 0.0),
+            FACE_SIZE,
+            3,
+        )
+        .unwrap();
+        assert!(matches!(rotation.axis, Axis::Z));
+        assert_eq!(1, rotation.slice);
+        assert!(rotation.negative_direction);
+
+        // drag the right column of the bottom face towards the front: R
+        let rotation = get_gesture_rotation(
+            Vec3::NEG_Y,
+            Vec3::new(1.2, -1.5, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+            FACE_SIZE,
+            3,
+        )
+        .unwrap();
+        assert!(matches!(rotation.axis, Axis::X));
+        assert_eq!(1, rotation.slice);
+        assert!(rotation.negative_direction);
+
+        // drag the left column of the bottom face on a 4x4 towards the back: L
+        let rotation = get_gesture_rotation(
+            Vec3::NEG_Y,
+            Vec3::new(-1.3, -1.5, 0.0),
+            Vec3::new(0.0, 0.0, -1.0),
+            FACE_SIZE,
+            4,
+        )
+        .unwrap();
+        assert!(matches!(rotation.axis, Axis::X));
+        assert_eq!(-2, rotation.slice);
+        assert!(!rotation.negative_direction);
+        assert_eq!(Vec3::NEG_Z, rotation.direction);
     }
 }
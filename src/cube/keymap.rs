@@ -0,0 +1,313 @@
+// Turning the cube with the keyboard. Keys are bound to moves in cubing notation, like desktop cube timers do.
+// The bindings are stored in `keymap.txt` in the storage directory, where they can be edited by the user.
+
+use bevy::prelude::*;
+
+use crate::{
+    schedules::CubeScheduleSet,
+    utils::storage::{self, format_key_values, parse_key_values},
+};
+
+use super::{
+    Cube, CubeRotationAnimation as RotationAnimation, CubeRotationEvent, EaseFunction,
+    RotationInputQueueResource, SequenceResource,
+};
+
+const KEYMAP_FILE: &str = "keymap.txt";
+const KEYMAP_FILE_HEADER: &str =
+    "# Key bindings in the format `key = move`, for example `KeyJ = U`.
+# Moves are written in cubing notation. Delete this file to restore the default key bindings.
+";
+
+pub struct KeymapPlugin;
+
+impl Plugin for KeymapPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(load_keymap()).add_systems(
+            Update,
+            (keyboard_rotation_handler, save_keymap).in_set(CubeScheduleSet::HandleUserInput),
+        );
+    }
+}
+
+/// Maps keys to moves in cubing notation
+#[derive(Resource)]
+pub struct KeymapResource {
+    bindings: Vec<(KeyCode, String)>,
+}
+
+impl KeymapResource {
+    pub fn get(&self, key_code: KeyCode) -> Option<&str> {
+        self.bindings
+            .iter()
+            .find(|(bound_key_code, _)| *bound_key_code == key_code)
+            .map(|(_, notation)| notation.as_str())
+    }
+
+    /// Bind the key to the move, replacing the move the key was bound to
+    pub fn bind(&mut self, key_code: KeyCode, notation: String) {
+        match self
+            .bindings
+            .iter_mut()
+            .find(|(bound_key_code, _)| *bound_key_code == key_code)
+        {
+            Some((_, bound_notation)) => *bound_notation = notation,
+            None => self.bindings.push((key_code, notation)),
+        }
+    }
+
+    pub fn unbind(&mut self, key_code: KeyCode) {
+        self.bindings
+            .retain(|(bound_key_code, _)| *bound_key_code != key_code);
+    }
+
+    fn to_file_contents(&self) -> String {
+        let key_values: Vec<(String, String)> = self
+            .bindings
+            .iter()
+            .filter_map(|(key_code, notation)| {
+                Some((key_code_name(*key_code)?.to_string(), notation.clone()))
+            })
+            .collect();
+
+        format!("{KEYMAP_FILE_HEADER}{}", format_key_values(&key_values))
+    }
+
+    fn from_file_contents(contents: &str) -> Self {
+        let mut keymap = Self { bindings: vec![] };
+
+        for (key_name, notation) in parse_key_values(contents) {
+            match key_code_from_name(&key_name) {
+                Some(key_code) => keymap.bind(key_code, notation),
+                None => warn!("keymap: unknown key \"{key_name}\""),
+            }
+        }
+
+        keymap
+    }
+}
+
+/// The keymap that is used by most desktop cube timers
+impl Default for KeymapResource {
+    fn default() -> Self {
+        let bindings = [
+            (KeyCode::KeyI, "R"),
+            (KeyCode::KeyK, "R'"),
+            (KeyCode::KeyD, "L"),
+            (KeyCode::KeyE, "L'"),
+            (KeyCode::KeyJ, "U"),
+            (KeyCode::KeyF, "U'"),
+            (KeyCode::KeyS, "D"),
+            (KeyCode::KeyL, "D'"),
+            (KeyCode::KeyH, "F"),
+            (KeyCode::KeyG, "F'"),
+            (KeyCode::KeyW, "B"),
+            (KeyCode::KeyO, "B'"),
+            (KeyCode::KeyU, "r"),
+            (KeyCode::KeyM, "r'"),
+            (KeyCode::KeyV, "l"),
+            (KeyCode::KeyR, "l'"),
+            (KeyCode::Comma, "u"),
+            (KeyCode::KeyC, "u'"),
+            (KeyCode::KeyZ, "d"),
+            (KeyCode::Slash, "d'"),
+            (KeyCode::Digit5, "M"),
+            (KeyCode::Digit6, "M"),
+            (KeyCode::KeyX, "M'"),
+            (KeyCode::Period, "M'"),
+            (KeyCode::KeyT, "x"),
+            (KeyCode::KeyY, "x"),
+            (KeyCode::KeyB, "x'"),
+            (KeyCode::KeyN, "x'"),
+            (KeyCode::Semicolon, "y"),
+            (KeyCode::KeyA, "y'"),
+            (KeyCode::KeyP, "z"),
+            (KeyCode::KeyQ, "z'"),
+        ];
+
+        Self {
+            bindings: bindings
+                .into_iter()
+                .map(|(key_code, notation)| (key_code, notation.to_string()))
+                .collect(),
+        }
+    }
+}
+
+/// The keys that can be bound, with the names that are used for them in the keymap file
+const KEY_NAMES: [(KeyCode, &str); 47] = [
+    (KeyCode::KeyA, "KeyA"),
+    (KeyCode::KeyB, "KeyB"),
+    (KeyCode::KeyC, "KeyC"),
+    (KeyCode::KeyD, "KeyD"),
+    (KeyCode::KeyE, "KeyE"),
+    (KeyCode::KeyF, "KeyF"),
+    (KeyCode::KeyG, "KeyG"),
+    (KeyCode::KeyH, "KeyH"),
+    (KeyCode::KeyI, "KeyI"),
+    (KeyCode::KeyJ, "KeyJ"),
+    (KeyCode::KeyK, "KeyK"),
+    (KeyCode::KeyL, "KeyL"),
+    (KeyCode::KeyM, "KeyM"),
+    (KeyCode::KeyN, "KeyN"),
+    (KeyCode::KeyO, "KeyO"),
+    (KeyCode::KeyP, "KeyP"),
+    (KeyCode::KeyQ, "KeyQ"),
+    (KeyCode::KeyR, "KeyR"),
+    (KeyCode::KeyS, "KeyS"),
+    (KeyCode::KeyT, "KeyT"),
+    (KeyCode::KeyU, "KeyU"),
+    (KeyCode::KeyV, "KeyV"),
+    (KeyCode::KeyW, "KeyW"),
+    (KeyCode::KeyX, "KeyX"),
+    (KeyCode::KeyY, "KeyY"),
+    (KeyCode::KeyZ, "KeyZ"),
+    (KeyCode::Digit0, "Digit0"),
+    (KeyCode::Digit1, "Digit1"),
+    (KeyCode::Digit2, "Digit2"),
+    (KeyCode::Digit3, "Digit3"),
+    (KeyCode::Digit4, "Digit4"),
+    (KeyCode::Digit5, "Digit5"),
+    (KeyCode::Digit6, "Digit6"),
+    (KeyCode::Digit7, "Digit7"),
+    (KeyCode::Digit8, "Digit8"),
+    (KeyCode::Digit9, "Digit9"),
+    (KeyCode::Comma, "Comma"),
+    (KeyCode::Period, "Period"),
+    (KeyCode::Slash, "Slash"),
+    (KeyCode::Semicolon, "Semicolon"),
+    (KeyCode::Quote, "Quote"),
+    (KeyCode::BracketLeft, "BracketLeft"),
+    (KeyCode::BracketRight, "BracketRight"),
+    (KeyCode::Backslash, "Backslash"),
+    (KeyCode::Minus, "Minus"),
+    (KeyCode::Equal, "Equal"),
+    (KeyCode::Backquote, "Backquote"),
+];
+
+fn key_code_name(key_code: KeyCode) -> Option<&'static str> {
+    KEY_NAMES
+        .iter()
+        .find(|(named_key_code, _)| *named_key_code == key_code)
+        .map(|(_, name)| *name)
+}
+
+fn key_code_from_name(name: &str) -> Option<KeyCode> {
+    KEY_NAMES
+        .iter()
+        .find(|(_, key_name)| *key_name == name)
+        .map(|(key_code, _)| *key_code)
+}
+
+/// Loads the keymap from disk. If there is no keymap yet, the default keymap gets saved so that the user can edit it.
+fn load_keymap() -> KeymapResource {
+    match storage::read(KEYMAP_FILE) {
+        Ok(contents) => KeymapResource::from_file_contents(&contents),
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+            warn!("failed to load keymap, using the default keymap: {err}");
+            KeymapResource::default()
+        }
+        Err(_) => {
+            let keymap = KeymapResource::default();
+            if let Err(err) = storage::write(KEYMAP_FILE, &keymap.to_file_contents()) {
+                warn!("failed to save default keymap: {err}");
+            }
+            keymap
+        }
+    }
+}
+
+fn save_keymap(keymap: Res<KeymapResource>) {
+    if !keymap.is_changed() || keymap.is_added() {
+        return;
+    }
+
+    if let Err(err) = storage::write(KEYMAP_FILE, &keymap.to_file_contents()) {
+        error!("failed to save keymap: {err}");
+    }
+}
+
+fn keyboard_rotation_handler(
+    keymap: Res<KeymapResource>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    cube_query: Query<&Cube>,
+    current_sequence: Res<SequenceResource>,
+    mut rotation_input_queue: ResMut<RotationInputQueueResource>,
+) {
+    if !current_sequence.is_done() {
+        return;
+    }
+
+    let Ok(cube) = cube_query.get_single() else {
+        warn!("couldn't find cube");
+        return;
+    };
+
+    for key_code in keyboard_input.get_just_pressed() {
+        let Some(notation) = keymap.get(*key_code) else {
+            continue;
+        };
+
+        let mut rotation_event = match CubeRotationEvent::from_notation(notation, cube.size()) {
+            Ok(rotation_event) => rotation_event,
+            Err(err) => {
+                warn!("keymap: {err}");
+                continue;
+            }
+        };
+
+        rotation_event.animation = Some(RotationAnimation {
+            duration_in_seconds: 0.3,
+            ease_function: Some(EaseFunction::CubicOut),
+        });
+
+        if !rotation_input_queue.push(rotation_event) {
+            debug!("rotation input queue is full, ignoring key press");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::input::keyboard::KeyCode;
+
+    use crate::cube::{CubeRotationEvent, CubeSize};
+
+    use super::{key_code_from_name, key_code_name, KeymapResource, KEY_NAMES};
+
+    #[test]
+    fn test_default_keymap_is_valid() {
+        let keymap = KeymapResource::default();
+        let cube_size = CubeSize(3);
+
+        for (key_code, notation) in &keymap.bindings {
+            assert!(key_code_name(*key_code).is_some());
+            assert!(CubeRotationEvent::from_notation(notation, &cube_size).is_ok());
+        }
+
+        assert_eq!(Some("U"), keymap.get(KeyCode::KeyJ));
+        assert_eq!(Some("U'"), keymap.get(KeyCode::KeyF));
+    }
+
+    #[test]
+    fn test_keymap_file_round_trip() {
+        let mut keymap = KeymapResource::default();
+        keymap.bind(KeyCode::KeyJ, "2R".to_string());
+        keymap.unbind(KeyCode::KeyF);
+
+        let loaded_keymap = KeymapResource::from_file_contents(&keymap.to_file_contents());
+
+        assert_eq!(keymap.bindings, loaded_keymap.bindings);
+        assert_eq!(Some("2R"), loaded_keymap.get(KeyCode::KeyJ));
+        assert_eq!(None, loaded_keymap.get(KeyCode::KeyF));
+    }
+
+    #[test]
+    fn test_key_names() {
+        for (key_code, name) in KEY_NAMES {
+            assert_eq!(Some(key_code), key_code_from_name(name));
+        }
+
+        assert_eq!(None, key_code_from_name("Tab"));
+    }
+}
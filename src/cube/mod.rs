@@ -1,33 +1,50 @@
 mod cube;
 pub use cube::*;
 
-mod cube_state;
-pub use cube_state::CubeState;
+mod animation_profile;
+pub use animation_profile::AnimationProfileResource;
+pub use animation_profile::ANIMATION_PROFILES;
 
 mod controller;
+pub use controller::ControllerPlugin;
+pub use controller::RotationInputQueueResource;
 pub use controller::SequenceResource;
 
-#[allow(dead_code)]
-mod cubing_notation_rotations;
-pub use cubing_notation_rotations::*;
+mod consistency;
+pub use consistency::facelet_at;
+pub use consistency::facelet_color;
+pub use consistency::facelet_pieces;
+pub use consistency::ConsistencyPlugin;
 
-mod rotation;
-pub use rotation::CubeRotationEvent;
-pub use rotation::Rotation;
-pub use rotation::RotationAnimation as CubeRotationAnimation;
-
-mod axis;
-
-mod scramble;
-pub use scramble::create_random_scramble_sequence;
-#[allow(unused_imports)]
-pub use scramble::create_scramble_sequence_from_algorithm;
+mod set_state;
+pub use set_state::SetCubeStateEvent;
+pub use set_state::SetCubeStatePlugin;
 
-pub mod solver;
-
-#[allow(dead_code)]
-pub mod algorithms;
+mod rotation;
+pub use rotation::CubeRotationEventFinished;
+pub use rotation::CubeRotationPlugin;
+pub use rotation::CurrentRotationResource;
+pub use rotation::FastTurningResource;
+
+// The model of the cube lives in the library, so that it can be used without Bevy
+pub use rubiks_cube::model::algorithm_from_notation;
+pub use rubiks_cube::model::algorithm_to_notation;
+pub use rubiks_cube::model::create_random_scramble_sequence;
+pub use rubiks_cube::model::create_seeded_scramble_sequence;
+pub use rubiks_cube::model::scramble_length;
+pub use rubiks_cube::model::solver;
+pub use rubiks_cube::model::CubeRotationEvent;
+pub use rubiks_cube::model::CubeSize;
+pub use rubiks_cube::model::CubeState;
+pub use rubiks_cube::model::EaseFunction;
+pub use rubiks_cube::model::FaceRotation;
+pub use rubiks_cube::model::Rotation;
+pub use rubiks_cube::model::RotationAnimation as CubeRotationAnimation;
 
 mod interact_to_rotate;
+pub use interact_to_rotate::DragToRotateResource;
+
+mod keymap;
 
-mod slice;
+#[cfg(test)]
+pub mod test_utils;
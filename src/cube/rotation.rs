@@ -1,15 +1,15 @@
 use std::{f32::consts::TAU, time::Duration};
 
 use bevy::{log, prelude::*, time::Stopwatch};
-use rand::Rng;
+use rubiks_cube::model::{
+    rotation::{CubeRotation, FaceRotation},
+    rotations_conflict, Axis, CubeRotationEvent, CubeState, EaseFunction, Rotation,
+    RotationAnimation,
+};
 
 use crate::schedules::CubeScheduleSet;
 
-use super::{
-    axis::Axis,
-    cube::{Cube, CubeSize, Piece},
-    cube_state::CubeState,
-};
+use super::cube::{Cube, Piece};
 
 pub struct CubeRotationPlugin;
 
@@ -18,6 +18,7 @@ impl Plugin for CubeRotationPlugin {
         app.add_event::<CubeRotationEvent>()
             .add_event::<CubeRotationEventFinished>()
             .insert_resource(CurrentRotationResource::new())
+            .insert_resource(FastTurningResource(false))
             .add_systems(
                 Update,
                 rotation_events_handler.in_set(CubeScheduleSet::HandleEvents),
@@ -29,16 +30,139 @@ impl Plugin for CubeRotationPlugin {
     }
 }
 
+/// If true, rotations that do not share any pieces with the rotations that are being animated can start right away,
+/// and consecutive rotations of the same slices get merged into a single animation.
+#[derive(Resource)]
+pub struct FastTurningResource(pub bool);
+
+/// Keeps track of the rotations that are being animated
 #[derive(Resource)]
+pub struct CurrentRotationResource {
+    rotations: Vec<ActiveRotation>,
+    next_id: u32,
+}
+
+/// How the pieces of a rotation event should be animated
+enum RotationStart {
+    /// Start a new animation with the given rotation id
+    New(u32),
+    /// Continue the animation with the given rotation id. `previous_fraction` is the fraction the animation was
+    /// at before it got merged.
+    Merged { id: u32, previous_fraction: f32 },
+}
+
+impl CurrentRotationResource {
+    fn new() -> Self {
+        Self {
+            rotations: vec![],
+            next_id: 0,
+        }
+    }
+
+    pub fn is_animating(&self) -> bool {
+        !self.rotations.is_empty()
+    }
+
+    /// The rotations that are being animated
+    pub fn rotations(&self) -> impl Iterator<Item = &Rotation> {
+        self.rotations
+            .iter()
+            .map(|active_rotation| &active_rotation.rotation)
+    }
+
+    /// Check if the given event can be handled right now, without interfering with the rotations that are being animated.
+    /// If `allow_concurrent` is false, no rotation can start until the current rotations have finished.
+    pub fn can_start_rotation(&self, event: &CubeRotationEvent, allow_concurrent: bool) -> bool {
+        if self.rotations.is_empty() {
+            return true;
+        }
+
+        if !allow_concurrent {
+            return false;
+        }
+
+        self.rotations.iter().all(|active_rotation| {
+            active_rotation.rotation == event.rotation
+                || !rotations_conflict(&active_rotation.rotation, &event.rotation)
+        })
+    }
+
+    /// Returns None if the event does not need to be animated
+    fn start(
+        &mut self,
+        event: &CubeRotationEvent,
+        allow_concurrent: bool,
+    ) -> Option<RotationStart> {
+        let duration_in_seconds = match &event.animation {
+            Some(animation) => animation.duration_in_seconds,
+            None => 0.0,
+        };
+
+        if allow_concurrent {
+            if let Some(active_rotation) = self
+                .rotations
+                .iter_mut()
+                .find(|active_rotation| active_rotation.rotation == event.rotation)
+            {
+                let previous_fraction = active_rotation.fraction();
+                let remaining_seconds = active_rotation.remaining_secs();
+                active_rotation.start_timer(remaining_seconds + duration_in_seconds);
+
+                return Some(RotationStart::Merged {
+                    id: active_rotation.id,
+                    previous_fraction,
+                });
+            }
+        }
+
+        // events without animation are applied instantly
+        event.animation.as_ref()?;
+
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+
+        let mut active_rotation = ActiveRotation::new(id, event.rotation.clone());
+        active_rotation.start_timer(duration_in_seconds);
+        self.rotations.push(active_rotation);
+
+        Some(RotationStart::New(id))
+    }
+
+    fn get(&self, id: u32) -> Option<&ActiveRotation> {
+        self.rotations
+            .iter()
+            .find(|active_rotation| active_rotation.id == id)
+    }
+
+    fn tick(&mut self, delta: Duration) {
+        for active_rotation in &mut self.rotations {
+            active_rotation.tick(delta);
+        }
+    }
+
+    /// Removes the finished rotations and returns them
+    fn take_finished(&mut self) -> Vec<ActiveRotation> {
+        let (finished, active) = std::mem::take(&mut self.rotations)
+            .into_iter()
+            .partition(|active_rotation| active_rotation.is_finished());
+        self.rotations = active;
+        finished
+    }
+}
+
 /// is very similar to a timer but keeps the overflowed duration
-struct CurrentRotationResource {
+struct ActiveRotation {
+    id: u32,
+    rotation: Rotation,
     timer: Option<Stopwatch>,
     duration: Duration,
 }
 
-impl CurrentRotationResource {
-    fn new() -> Self {
+impl ActiveRotation {
+    fn new(id: u32, rotation: Rotation) -> Self {
         Self {
+            id,
+            rotation,
             timer: None,
             duration: Duration::ZERO,
         }
@@ -49,11 +173,6 @@ impl CurrentRotationResource {
         self.duration = Duration::from_secs_f32(seconds);
     }
 
-    fn stop(&mut self) {
-        self.timer = None;
-        self.duration = Duration::ZERO;
-    }
-
     fn is_finished(&self) -> bool {
         match &self.timer {
             Some(stopwatch) => stopwatch.elapsed_secs() >= self.duration.as_secs_f32(),
@@ -65,6 +184,10 @@ impl CurrentRotationResource {
     fn fraction(&self) -> f32 {
         match &self.timer {
             Some(stopwatch) => {
+                if self.duration.is_zero() {
+                    return 1.0;
+                }
+
                 let fraction = stopwatch.elapsed_secs() / self.duration.as_secs_f32();
                 return fraction.clamp(0.0, 1.0);
             }
@@ -72,6 +195,13 @@ impl CurrentRotationResource {
         }
     }
 
+    fn remaining_secs(&self) -> f32 {
+        match &self.timer {
+            Some(stopwatch) => (self.duration.as_secs_f32() - stopwatch.elapsed_secs()).max(0.0),
+            None => 0.0,
+        }
+    }
+
     fn tick(&mut self, delta: Duration) {
         match &mut self.timer {
             Some(timer) => {
@@ -95,12 +225,6 @@ impl CurrentRotationResource {
     }
 }
 
-#[derive(Clone, Debug)]
-pub struct RotationAnimation {
-    pub duration_in_seconds: f32,
-    pub ease_function: Option<EaseFunction>,
-}
-
 #[derive(Component)]
 struct RotationAnimator {
     start: Transform,
@@ -108,32 +232,42 @@ struct RotationAnimator {
     amount_to_rotate: f32, // in radians
     axis: Axis,
     pivot_point: Vec3,
+    /// The id of the `ActiveRotation` that drives this animation
+    rotation_id: u32,
 }
 
 impl RotationAnimator {
     fn new(
         start: Transform,
-        animation: &RotationAnimation,
+        animation: &Option<RotationAnimation>,
         amount_to_rotate: f32,
         axis: Axis,
         pivot_point: Vec3,
+        rotation_id: u32,
     ) -> Self {
+        let ease_function = match animation {
+            Some(animation) => animation.ease_function,
+            None => None,
+        };
+
         Self {
             start,
-            ease_function: animation.ease_function.unwrap_or(EaseFunction::Linear),
+            ease_function: ease_function.unwrap_or(EaseFunction::Linear),
             amount_to_rotate,
             axis,
             pivot_point,
+            rotation_id,
         }
     }
-}
 
-#[derive(Event, Clone, Debug)]
-pub struct CubeRotationEvent {
-    pub rotation: Rotation,
-    pub negative_direction: bool,
-    pub twice: bool,
-    pub animation: Option<RotationAnimation>,
+    /// The angle that has been rotated so far, at the given fraction of the animation
+    fn rotated_angle(&self, fraction: f32) -> f32 {
+        let eased_progress = EasingCurve::new(0.0, 1.0, self.ease_function.into())
+            .sample(fraction)
+            .unwrap();
+
+        eased_progress * self.amount_to_rotate
+    }
 }
 
 #[derive(Event)]
@@ -141,181 +275,15 @@ pub struct CubeRotationEventFinished {
     pub overflowing_secs: f32,
 }
 
-impl CubeRotationEvent {
-    pub fn random_face_rotation(cube_size: &CubeSize) -> Self {
-        let face_rotation = FaceRotation::random(cube_size);
-
-        let mut rng = rand::thread_rng();
-        let direction = if rng.gen_range(0..=1) == 0 {
-            true
-        } else {
-            false
-        };
-
-        CubeRotationEvent {
-            rotation: Rotation::Face(face_rotation),
-            negative_direction: direction,
-            twice: false,
-            animation: None,
-        }
-    }
-
-    /// check wether the given event negates (undoes) self
-    pub fn negates(&self, event: &Self) -> bool {
-        if self.rotation != event.rotation {
-            return false;
-        }
-
-        if self.twice != event.twice {
-            return false;
-        }
-
-        if self.negative_direction != event.negative_direction {
-            return true;
-        }
-
-        return false;
-    }
-
-    pub fn equals(&self, comparison: &Self) -> bool {
-        return self.rotation == comparison.rotation
-            && self.negative_direction == comparison.negative_direction
-            && self.twice == comparison.twice;
-    }
-}
-
-#[derive(Clone, Debug, PartialEq)]
-pub enum Rotation {
-    Face(FaceRotation),
-    Cube(CubeRotation),
-}
-
-impl Rotation {
-    pub fn face_x(slice: i32) -> Self {
-        Self::Face(FaceRotation::x(slice))
-    }
-    pub fn face_y(slice: i32) -> Self {
-        Self::Face(FaceRotation::y(slice))
-    }
-    pub fn face_z(slice: i32) -> Self {
-        Self::Face(FaceRotation::z(slice))
-    }
-    pub fn face(axis: Axis, slice: i32) -> Self {
-        match axis {
-            Axis::X => Self::face_x(slice),
-            Axis::Y => Self::face_y(slice),
-            Axis::Z => Self::face_z(slice),
-        }
-    }
-    pub fn cube_x() -> Self {
-        Self::Cube(CubeRotation::X)
-    }
-    pub fn cube_y() -> Self {
-        Self::Cube(CubeRotation::Y)
-    }
-    pub fn cube_z() -> Self {
-        Self::Cube(CubeRotation::Z)
-    }
-    pub fn cube(axis: Axis) -> Self {
-        match axis {
-            Axis::X => Self::cube_x(),
-            Axis::Y => Self::cube_y(),
-            Axis::Z => Self::cube_z(),
-        }
-    }
-}
-
-/// Rotate the given faces (e.g. slices) of the cube on a given axis. This is relative to the current cube rotation.
-/// For even sized cubes (2x2, 4x4) there is no slice 0.
-#[derive(Clone, Debug, PartialEq)]
-pub enum FaceRotation {
-    /// Rotate the given slices on the x axis.
-    /// For a rotation in the default direction, when looking at the front of the cube, the front row ends up at the bottom.
-    /// The positive slice indices are on the right of the cube and the negative slice indices are at the left of the cube.
-    X(Vec<i32>),
-    /// Rotate the given slices on the y axis.
-    /// For a rotation in the default direction, when looking at the front of the cube, the front row ends up at the right side.
-    /// The positive slice indices are on the top of the cube and the negative slice indices are at the bottom of the cube.
-    Y(Vec<i32>),
-    /// Rotate the given slices on the z axis.
-    /// For a rotation in the default direction, when looking at the front of the cube, the top row ends up at the left side.
-    /// The positive slice indices are on the front of the cube and the negative slice indices are on the back of the cube.
-    Z(Vec<i32>),
-}
-
-impl FaceRotation {
-    pub fn random(cube_size: &CubeSize) -> Self {
-        let mut rng = rand::thread_rng();
-
-        let slice = if cube_size.0 % 2 == 1 {
-            rng.gen_range(cube_size.lowest_piece_index()..=cube_size.highest_piece_index())
-        } else {
-            let mut result = if cube_size.0 == 2 {
-                1
-            } else {
-                rng.gen_range(1..=(cube_size.0 / 2))
-            };
-            let negative = rng.gen_range(0..=1);
-
-            if negative == 0 {
-                result *= -1
-            }
-
-            result
-        };
-        let axis = rng.gen_range(0..=2);
-
-        if axis == 0 {
-            Self::X(vec![slice])
-        } else if axis == 1 {
-            Self::Y(vec![slice])
-        } else {
-            Self::Z(vec![slice])
-        }
-    }
-
-    pub fn x(slice: i32) -> Self {
-        Self::X(vec![slice])
-    }
-    pub fn y(slice: i32) -> Self {
-        Self::Y(vec![slice])
-    }
-    pub fn z(slice: i32) -> Self {
-        Self::Z(vec![slice])
-    }
-}
-
-/// Rotate the whole cube on a given axis. This also changes which faces gets rotated for FaceRotation events.
-#[derive(Clone, Debug, PartialEq)]
-pub enum CubeRotation {
-    /// Move the whole cube on the x axis.
-    /// For the default direction, when looking at the front of the cube, the front face ends up at the bottom.
-    X,
-    /// Move the whole cube on the y axis
-    /// For the default direction, when looking at the front of the cube, the front face ends up at the left side.
-    Y,
-    /// Move the whole cube on the z axis.
-    /// For the default direction, when looking at the front of the cube, the top row ends up at the left side.
-    Z,
-}
-
-impl Into<Axis> for &CubeRotation {
-    fn into(self) -> Axis {
-        match self {
-            CubeRotation::X => Axis::X,
-            CubeRotation::Y => Axis::Y,
-            CubeRotation::Z => Axis::Z,
-        }
-    }
-}
-
 fn rotation_events_handler(
     mut commands: Commands,
     mut cube_query: Query<&mut Cube>,
     mut cube_state_query: Query<&mut CubeState>,
     mut cube_pieces_query: Query<(Entity, &mut Piece, &mut Transform)>,
+    animator_query: Query<&RotationAnimator>,
     mut event_reader: EventReader<CubeRotationEvent>,
     mut current_rotation_resource: ResMut<CurrentRotationResource>,
+    fast_turning_resource: Res<FastTurningResource>,
 ) {
     let Ok(mut cube) = cube_query.get_single_mut() else {
         error!("expected exactly 1 Cube entity");
@@ -338,15 +306,17 @@ fn rotation_events_handler(
         number_of_pieces += 1;
     }
 
+    // Events that are sent in the same frame form a batch. Rotations of a batch that do not share any pieces
+    // are animated concurrently, even without fast turning.
+    let allow_concurrent = fast_turning_resource.0 || !current_rotation_resource.is_animating();
+
     for cube_rotation_event in event_reader.read() {
-        if current_rotation_resource.timer.is_some() {
+        if !current_rotation_resource.can_start_rotation(cube_rotation_event, allow_concurrent) {
             warn!("Skipping cube rotation event because cube is already rotating");
             continue;
         }
 
-        if let Some(animation) = &cube_rotation_event.animation {
-            current_rotation_resource.start_timer(animation.duration_in_seconds);
-        }
+        let rotation_start = current_rotation_resource.start(cube_rotation_event, allow_concurrent);
 
         cube_state.handle_rotate_event(cube_rotation_event);
 
@@ -386,9 +356,10 @@ fn rotation_events_handler(
                                     &mut commands,
                                     cube_piece_entities[*piece_index_to_rotate],
                                     cube_piece_transforms[*piece_index_to_rotate],
-                                    &mut cube,
                                     pivot_point,
                                     &cube_rotation_event.animation,
+                                    &rotation_start,
+                                    &animator_query,
                                     Axis::X,
                                     rotation_amount,
                                 );
@@ -444,9 +415,10 @@ fn rotation_events_handler(
                                     &mut commands,
                                     cube_piece_entities[*piece_index_to_rotate],
                                     &mut cube_piece_transforms[*piece_index_to_rotate],
-                                    &mut cube,
                                     pivot_point,
                                     &cube_rotation_event.animation,
+                                    &rotation_start,
+                                    &animator_query,
                                     Axis::Y,
                                     rotation_amount,
                                 );
@@ -502,9 +474,10 @@ fn rotation_events_handler(
                                     &mut commands,
                                     cube_piece_entities[*piece_index_to_rotate],
                                     &mut cube_piece_transforms[*piece_index_to_rotate],
-                                    &mut cube,
                                     pivot_point,
                                     &cube_rotation_event.animation,
+                                    &rotation_start,
+                                    &animator_query,
                                     Axis::Z,
                                     rotation_amount,
                                 );
@@ -616,9 +589,10 @@ fn rotation_events_handler(
                         &mut commands,
                         cube_piece_entities[piece_index_to_rotate],
                         &mut cube_piece_transforms[piece_index_to_rotate],
-                        &mut cube,
                         Vec3::ZERO,
                         &cube_rotation_event.animation,
+                        &rotation_start,
+                        &animator_query,
                         cube_rotation.into(),
                         rotation_amount,
                     );
@@ -626,30 +600,58 @@ fn rotation_events_handler(
             }
         }
     }
+
+    if current_rotation_resource.is_animating() {
+        cube.is_animating_rotation = true;
+    }
 }
 
 fn rotate_piece(
     commands: &mut Commands,
     piece_entity: Entity,
     piece_transform: &mut Transform,
-    cube: &mut Cube,
     pivot_point: Vec3,
     animation: &Option<RotationAnimation>,
+    rotation_start: &Option<RotationStart>,
+    animator_query: &Query<&RotationAnimator>,
     axis: Axis,
     rotation_amount: f32,
 ) {
-    match animation {
-        Some(animation_properties) => {
+    match rotation_start {
+        Some(RotationStart::New(rotation_id)) => {
             let animator = RotationAnimator::new(
-                piece_transform.clone(),
-                animation_properties,
+                *piece_transform,
+                animation,
                 rotation_amount,
                 axis,
                 pivot_point,
+                *rotation_id,
             );
             commands.entity(piece_entity).insert(animator);
+        }
+        Some(RotationStart::Merged {
+            id: rotation_id,
+            previous_fraction,
+        }) => {
+            // The piece transform is where the previous animation currently is, so we continue from there
+            // with whatever the previous animation did not rotate yet.
+            let remaining_amount = match animator_query.get(piece_entity) {
+                Ok(previous_animator) => {
+                    previous_animator.amount_to_rotate
+                        - previous_animator.rotated_angle(*previous_fraction)
+                }
+                Err(_) => 0.0,
+            };
 
-            cube.is_animating_rotation = true;
+            let animator = RotationAnimator::new(
+                *piece_transform,
+                animation,
+                remaining_amount + rotation_amount,
+                axis,
+                pivot_point,
+                *rotation_id,
+            );
+            commands.entity(piece_entity).insert(animator);
         }
         None => {
             let rotation = match axis {
@@ -670,20 +672,20 @@ fn handle_rotation_animations(
     mut current_rotation: ResMut<CurrentRotationResource>,
     mut event_writer_cube_rotation_event_finished: EventWriter<CubeRotationEventFinished>,
 ) {
-    if current_rotation.is_finished() {
+    if !current_rotation.is_animating() {
         return;
     }
 
     current_rotation.tick(time.delta());
 
     for (entity, mut transform, animation) in query.iter_mut() {
-        let animation_progress = current_rotation.fraction();
-
-        let eased_progress = EasingCurve::new(0.0, 1.0, animation.ease_function)
-            .sample(animation_progress)
-            .unwrap();
+        let Some(active_rotation) = current_rotation.get(animation.rotation_id) else {
+            // the rotation that this animator belonged to no longer exists
+            commands.entity(entity).remove::<RotationAnimator>();
+            continue;
+        };
 
-        let angle = eased_progress * animation.amount_to_rotate;
+        let angle = animation.rotated_angle(active_rotation.fraction());
         let rotation = match animation.axis {
             Axis::X => Quat::from_rotation_x(angle),
             Axis::Y => Quat::from_rotation_y(angle),
@@ -696,17 +698,18 @@ fn handle_rotation_animations(
         transform.translation = new_transform.translation;
 
         // cleanup if animation is done
-        if current_rotation.is_finished() {
+        if active_rotation.is_finished() {
             commands.entity(entity).remove::<RotationAnimator>();
         }
     }
 
-    if current_rotation.is_finished() {
+    for finished_rotation in current_rotation.take_finished() {
         event_writer_cube_rotation_event_finished.send(CubeRotationEventFinished {
-            overflowing_secs: current_rotation.overflowed_secs(),
+            overflowing_secs: finished_rotation.overflowed_secs(),
         });
-        current_rotation.stop();
+    }
 
+    if !current_rotation.is_animating() {
         match cube_query.get_single_mut() {
             Ok(mut cube) => {
                 cube.is_animating_rotation = false;
@@ -721,36 +724,159 @@ fn handle_rotation_animations(
 
 #[cfg(test)]
 mod tests {
-    use super::{CubeRotationEvent, Rotation};
+    use bevy::prelude::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rubiks_cube::model::{CubeState, EaseFunction};
+
+    use crate::cube::{
+        test_utils::{facelets_from_pieces, headless_app},
+        Cube, CubeSize, SequenceResource,
+    };
+
+    use super::{
+        CubeRotationEvent, CurrentRotationResource, FastTurningResource, Rotation,
+        RotationAnimation,
+    };
+
+    /// Random turns of single slices and of the whole cube, with and without animation
+    fn random_rotation_events(cube_size: usize, count: usize, seed: u64) -> Vec<CubeRotationEvent> {
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        (0..count)
+            .map(|_| {
+                let mut rotation_event = CubeRotationEvent::random_face_rotation_with_rng(
+                    &CubeSize(cube_size as i32),
+                    &mut rng,
+                );
+
+                if rng.gen_ratio(1, 5) {
+                    rotation_event.rotation = match rng.gen_range(0..3) {
+                        0 => Rotation::cube_x(),
+                        1 => Rotation::cube_y(),
+                        _ => Rotation::cube_z(),
+                    };
+                }
+
+                rotation_event.twice = rng.gen_ratio(1, 4);
+                rotation_event.animation = match rng.gen_range(0..3) {
+                    0 => None,
+                    1 => Some(RotationAnimation {
+                        duration_in_seconds: 0.05,
+                        ease_function: None,
+                    }),
+                    _ => Some(RotationAnimation {
+                        duration_in_seconds: 0.3,
+                        ease_function: Some(EaseFunction::BackOut),
+                    }),
+                };
+
+                rotation_event
+            })
+            .collect()
+    }
+
+    /// Plays the rotation events as a sequence, and updates the app until all animations are done
+    fn play(app: &mut App, rotation_events: Vec<CubeRotationEvent>) {
+        app.world_mut()
+            .resource_mut::<SequenceResource>()
+            .set(rotation_events);
+
+        for _ in 0..1000 {
+            app.update();
+
+            let is_animating = app
+                .world_mut()
+                .query::<&Cube>()
+                .single(app.world())
+                .is_animating_rotation;
+            if app.world().resource::<SequenceResource>().is_done() && !is_animating {
+                return;
+            }
+        }
+
+        panic!("the rotations did not finish");
+    }
+
+    fn cube_state_facelets(app: &mut App) -> String {
+        app.world_mut()
+            .query::<&CubeState>()
+            .single(app.world())
+            .to_facelets()
+    }
 
     #[test]
-    fn cube_rotation_event_negates() {
-        let rotation_event = CubeRotationEvent {
-            rotation: Rotation::face_x(-1),
-            negative_direction: true,
-            twice: false,
-            animation: None,
-        };
+    fn test_piece_transforms_agree_with_cube_state() {
+        for cube_size in 1..=7 {
+            let mut app = headless_app(cube_size);
+            assert_eq!(
+                cube_state_facelets(&mut app),
+                facelets_from_pieces(&mut app)
+            );
 
-        assert_eq!(rotation_event.negates(&rotation_event), false);
+            for (i, rotation_event) in random_rotation_events(cube_size, 12, cube_size as u64)
+                .into_iter()
+                .enumerate()
+            {
+                let notation = rotation_event.to_notation(&CubeSize(cube_size as i32));
+                play(&mut app, vec![rotation_event]);
+
+                assert_eq!(
+                    cube_state_facelets(&mut app),
+                    facelets_from_pieces(&mut app),
+                    "{cube_size}x{cube_size} cube, move {i}: {notation}"
+                );
+            }
+        }
+    }
 
-        let negating_event = CubeRotationEvent {
-            rotation: Rotation::face_x(-1),
-            negative_direction: false,
-            twice: false,
-            animation: None,
-        };
+    #[test]
+    fn test_concurrent_rotations_agree_with_cube_state() {
+        for cube_size in 1..=7 {
+            let mut app = headless_app(cube_size);
+            app.world_mut().resource_mut::<FastTurningResource>().0 = true;
+
+            play(
+                &mut app,
+                random_rotation_events(cube_size, 30, 100 + cube_size as u64),
+            );
 
-        assert!(negating_event.negates(&rotation_event));
+            assert_eq!(
+                cube_state_facelets(&mut app),
+                facelets_from_pieces(&mut app),
+                "{cube_size}x{cube_size} cube"
+            );
+        }
+    }
 
-        // it does not negate because its on a different axis
-        let non_negating_event = CubeRotationEvent {
-            rotation: Rotation::face_y(-1),
+    #[test]
+    fn current_rotation_resource_fast_turning() {
+        let animated_event = |rotation: Rotation| CubeRotationEvent {
+            rotation,
             negative_direction: false,
             twice: false,
-            animation: None,
+            animation: Some(RotationAnimation {
+                duration_in_seconds: 1.0,
+                ease_function: None,
+            }),
         };
 
-        assert_eq!(non_negating_event.negates(&rotation_event), false);
+        let mut current_rotation = CurrentRotationResource::new();
+        assert!(current_rotation.can_start_rotation(&animated_event(Rotation::face_x(1)), false));
+
+        current_rotation.start(&animated_event(Rotation::face_x(1)), true);
+        assert!(current_rotation.is_animating());
+
+        // without fast turning nothing can start while a rotation is animating
+        assert!(!current_rotation.can_start_rotation(&animated_event(Rotation::face_x(-1)), false));
+
+        // slices of the same axis that do not share pieces
+        assert!(current_rotation.can_start_rotation(&animated_event(Rotation::face_x(-1)), true));
+
+        // the same slice gets merged
+        assert!(current_rotation.can_start_rotation(&animated_event(Rotation::face_x(1)), true));
+
+        // different axes share pieces
+        assert!(!current_rotation.can_start_rotation(&animated_event(Rotation::face_y(1)), true));
+        assert!(!current_rotation.can_start_rotation(&animated_event(Rotation::cube_x()), true));
     }
 }
@@ -0,0 +1,211 @@
+// Shows any state on the 3D cube at once, without playing moves to get there. The pieces are put back where they
+// are on a solved cube, and the stickers are painted from the new state. If the state is of another cube size, the
+// cube is spawned again first.
+
+use bevy::prelude::*;
+
+use crate::schedules::CubeScheduleSet;
+
+use super::{
+    consistency::{paint_facelets, PieceFacesQuery, PiecesQuery},
+    Cube, CubeCommandsResource, CubeState, CurrentCubeSizeResource, Piece, PieceFace,
+    RotationInputQueueResource, SequenceResource,
+};
+
+/// Replaces the state of the cube. A running sequence and queued rotations are dropped. Rotations that are being
+/// animated are finished first.
+#[derive(Event, Clone)]
+pub struct SetCubeStateEvent {
+    pub cube_state: CubeState,
+}
+
+/// The state that the cube gets, as soon as it has the right size and stopped rotating
+#[derive(Resource, Default)]
+struct PendingCubeStateResource(Option<CubeState>);
+
+/// The pieces, to put them back where they are on a solved cube
+type MovablePiecesQuery<'w, 's> =
+    Query<'w, 's, (&'static mut Piece, &'static mut Transform), Without<PieceFace>>;
+
+pub struct SetCubeStatePlugin;
+
+impl Plugin for SetCubeStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SetCubeStateEvent>()
+            .init_resource::<PendingCubeStateResource>()
+            .add_systems(
+                Update,
+                (set_cube_state_events_handler, apply_pending_cube_state)
+                    .chain()
+                    .in_set(CubeScheduleSet::HandleEvents),
+            );
+    }
+}
+
+fn set_cube_state_events_handler(
+    mut commands: Commands,
+    mut event_reader: EventReader<SetCubeStateEvent>,
+    mut pending_cube_state: ResMut<PendingCubeStateResource>,
+    mut sequence_resource: ResMut<SequenceResource>,
+    mut rotation_input_queue: ResMut<RotationInputQueueResource>,
+    mut cube_size_resource: ResMut<CurrentCubeSizeResource>,
+    cube_commands: Res<CubeCommandsResource>,
+) {
+    // only the latest state matters
+    let Some(event) = event_reader.read().last() else {
+        return;
+    };
+
+    pending_cube_state.0 = Some(event.cube_state.clone());
+    sequence_resource.set(vec![]);
+    rotation_input_queue.clear();
+
+    if cube_size_resource.0 != event.cube_state.size() {
+        cube_size_resource.0 = event.cube_state.size();
+        commands.run_system(cube_commands.despawn);
+        commands.run_system(cube_commands.spawn);
+    }
+}
+
+fn apply_pending_cube_state(
+    mut cube_query: Query<(&Cube, &mut CubeState)>,
+    mut pieces: ParamSet<(MovablePiecesQuery, PiecesQuery)>,
+    piece_faces: PieceFacesQuery,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut pending_cube_state: ResMut<PendingCubeStateResource>,
+) {
+    let Some(new_cube_state) = &pending_cube_state.0 else {
+        return;
+    };
+
+    let Ok((cube, mut cube_state)) = cube_query.get_single_mut() else {
+        // the cube is being spawned again
+        return;
+    };
+
+    if cube.size().0 as usize != new_cube_state.size() || cube.is_animating_rotation {
+        return;
+    }
+
+    // Every move turns pieces around the middle of the cube, so turning a piece back by its own rotation brings it
+    // to where it was on the solved cube.
+    for (mut piece, mut transform) in pieces.p0().iter_mut() {
+        let indices = Vec3::new(
+            piece.current_x as f32,
+            piece.current_y as f32,
+            piece.current_z as f32,
+        );
+        let solved_indices = (transform.rotation.inverse() * indices).round().as_ivec3();
+
+        piece.current_x = solved_indices.x;
+        piece.current_y = solved_indices.y;
+        piece.current_z = solved_indices.z;
+        *transform = Transform::from_translation(cube.piece_translation(
+            solved_indices.x,
+            solved_indices.y,
+            solved_indices.z,
+        ));
+    }
+
+    *cube_state = pending_cube_state.0.take().unwrap();
+
+    if let Err(err) = paint_facelets(
+        cube,
+        &cube_state,
+        &pieces.p1(),
+        &piece_faces,
+        &mut materials,
+    ) {
+        error!("failed to paint the stickers of the cube: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate::cube::{
+        create_seeded_scramble_sequence, scramble_length,
+        test_utils::{facelets_from_pieces, headless_app},
+        Cube, CubeRotationEvent, CubeSize, CubeState, SequenceResource,
+    };
+
+    use super::SetCubeStateEvent;
+
+    fn scrambled_cube_state(cube_size: usize, seed: u64) -> CubeState {
+        let mut cube_state = CubeState::new(cube_size);
+        for rotation_event in create_seeded_scramble_sequence(
+            &CubeSize(cube_size as i32),
+            scramble_length(&CubeSize(cube_size as i32)),
+            seed,
+        ) {
+            cube_state.handle_rotate_event(&rotation_event);
+        }
+        cube_state
+    }
+
+    fn cube_state(app: &mut App) -> CubeState {
+        app.world_mut()
+            .query::<&CubeState>()
+            .single(app.world())
+            .clone()
+    }
+
+    #[test]
+    fn test_set_state_of_a_turned_cube() {
+        for cube_size in 1..=6 {
+            let mut app = headless_app(cube_size);
+
+            // turn the pieces away from where they were spawned
+            let moves = create_seeded_scramble_sequence(&CubeSize(cube_size as i32), 20, 1);
+            app.world_mut()
+                .resource_mut::<SequenceResource>()
+                .set(moves);
+            for _ in 0..40 {
+                app.update();
+            }
+
+            let new_cube_state = scrambled_cube_state(cube_size, 2);
+            app.world_mut().send_event(SetCubeStateEvent {
+                cube_state: new_cube_state.clone(),
+            });
+            app.update();
+
+            assert!(new_cube_state == cube_state(&mut app));
+            assert_eq!(new_cube_state.to_facelets(), facelets_from_pieces(&mut app));
+
+            // the pieces keep turning like the cube state
+            let rotation_event =
+                CubeRotationEvent::from_notation("R", &CubeSize(cube_size as i32)).unwrap();
+            app.world_mut().send_event(rotation_event);
+            app.update();
+            assert_eq!(
+                cube_state(&mut app).to_facelets(),
+                facelets_from_pieces(&mut app)
+            );
+        }
+    }
+
+    #[test]
+    fn test_set_state_of_another_size() {
+        let mut app = headless_app(3);
+        let new_cube_state = scrambled_cube_state(4, 3);
+
+        app.world_mut().send_event(SetCubeStateEvent {
+            cube_state: new_cube_state.clone(),
+        });
+        for _ in 0..3 {
+            app.update();
+        }
+
+        let cube_size = app
+            .world_mut()
+            .query::<&Cube>()
+            .single(app.world())
+            .size()
+            .0;
+        assert_eq!(4, cube_size);
+        assert!(new_cube_state == cube_state(&mut app));
+        assert_eq!(new_cube_state.to_facelets(), facelets_from_pieces(&mut app));
+    }
+}
@@ -0,0 +1,58 @@
+// Helpers for tests that need a cube in a headless app, without a window or rendering
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use crate::schedules::SchedulesPlugin;
+
+use super::{
+    consistency::{read_facelets, ConsistencyPlugin, PieceFacesQuery, PiecesQuery},
+    AnimationProfileResource, ControllerPlugin, Cube, CubeCommandsResource, CubeRotationPlugin,
+    CurrentCubeSizeResource, SetCubeStatePlugin, ANIMATION_PROFILES,
+};
+
+/// The time that passes with every update of a headless app
+pub const FRAME_DURATION: Duration = Duration::from_millis(50);
+
+/// An app with a spawned cube of the given size, that turns it like the game does. Every update advances the time
+/// by `FRAME_DURATION`.
+pub fn headless_app(cube_size: usize) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        SchedulesPlugin,
+        ControllerPlugin,
+        CubeRotationPlugin,
+        ConsistencyPlugin,
+        SetCubeStatePlugin,
+    ))
+    .init_asset::<Mesh>()
+    .init_asset::<StandardMaterial>()
+    .init_resource::<ButtonInput<KeyCode>>()
+    .init_resource::<CubeCommandsResource>()
+    .insert_resource(CurrentCubeSizeResource(cube_size))
+    .insert_resource(AnimationProfileResource(&ANIMATION_PROFILES[0]))
+    .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME_DURATION));
+
+    let spawn = app.world().resource::<CubeCommandsResource>().spawn;
+    app.world_mut().run_system(spawn).unwrap();
+
+    app
+}
+
+/// The facelets of the cube as they are shown, see `consistency::read_facelets`
+pub fn facelets_from_pieces(app: &mut App) -> String {
+    app.world_mut()
+        .run_system_cached(
+            |cube_query: Query<&Cube>,
+             pieces: PiecesQuery,
+             piece_faces: PieceFacesQuery,
+             materials: Res<Assets<StandardMaterial>>| {
+                read_facelets(cube_query.single(), &pieces, &piece_faces, &materials)
+            },
+        )
+        .unwrap()
+        .unwrap()
+}
@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+
+use crate::{
+    camera::{self, OrbitCamera},
+    schedules::CubeScheduleSet,
+};
+
+use super::{
+    interface::{BUTTON_TEXT_COLOR, DEFAULT_FONT_BOLD},
+    widget::{self, dropdown::DropdownOption},
+};
+
+pub struct CameraViewsPlugin;
+
+impl Plugin for CameraViewsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            handle_camera_view_dropdown.in_set(CubeScheduleSet::HandleEvents),
+        );
+    }
+}
+
+#[derive(Component)]
+struct CameraViewDropdownButton;
+
+#[derive(Component, Clone, Debug)]
+enum CameraView {
+    Front,
+    Back,
+    Isometric,
+}
+
+pub fn spawn(parent: &mut ChildBuilder<'_>, asset_server: &Res<AssetServer>) {
+    widget::dropdown::spawn_type_menu(
+        vec![
+            DropdownOption {
+                label: "front".to_string(),
+                value: CameraView::Front,
+            },
+            DropdownOption {
+                label: "back".to_string(),
+                value: CameraView::Back,
+            },
+            DropdownOption {
+                label: "isometric".to_string(),
+                value: CameraView::Isometric,
+            },
+        ],
+        true,
+        CameraViewDropdownButton,
+        (
+            Text::new("view"),
+            TextFont {
+                font: asset_server.load(DEFAULT_FONT_BOLD),
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(BUTTON_TEXT_COLOR),
+        ),
+        parent,
+        asset_server,
+    );
+}
+
+fn handle_camera_view_dropdown(
+    event_query: Query<(&CameraView, &Interaction), Changed<Interaction>>,
+    mut orbit_camera_query: Query<&mut OrbitCamera>,
+) {
+    let Ok((camera_view, interaction)) = event_query.get_single() else {
+        return;
+    };
+    if *interaction != Interaction::Pressed {
+        return;
+    }
+
+    let Ok(mut orbit_camera) = orbit_camera_query.get_single_mut() else {
+        error!("expected exactly 1 OrbitCamera component");
+        return;
+    };
+
+    orbit_camera.set_view(match camera_view {
+        CameraView::Front => camera::CameraView::Front,
+        CameraView::Back => camera::CameraView::Back,
+        CameraView::Isometric => camera::CameraView::Isometric,
+    });
+}
@@ -0,0 +1,192 @@
+use bevy::prelude::*;
+
+use crate::{
+    schedules::CubeScheduleSet,
+    timer::{self, Penalty, SolveTimerResource, TimerState, INSPECTION_SECONDS},
+};
+
+use super::{
+    interface::{BUTTON_BACKGROUND_COLOR, COLOR_MAIN, DEFAULT_FONT, DEFAULT_FONT_BOLD},
+    widget::{self, dropdown::DropdownOption},
+};
+
+pub struct ClockPlugin;
+
+impl Plugin for ClockPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            handle_timer_mode_dropdown.in_set(CubeScheduleSet::HandleEvents),
+        )
+        .add_systems(
+            Update,
+            update_clock.in_set(CubeScheduleSet::UpdateAnimations),
+        );
+    }
+}
+
+#[derive(Component)]
+struct TimerModeDropdown;
+
+#[derive(Component, Clone, Debug)]
+enum TimerMode {
+    Off,
+    /// Every scramble is followed by inspection and a timed solve
+    On,
+}
+
+#[derive(Component)]
+struct Clock;
+#[derive(Component)]
+struct ClockTimeLabel;
+#[derive(Component)]
+struct ClockStatusLabel;
+
+/// Spawns the dropdown to turn timed solves on and off
+pub fn spawn(parent: &mut ChildBuilder<'_>, asset_server: &Res<AssetServer>) {
+    widget::dropdown::spawn_type_select::<TimerMode>(
+        vec![
+            DropdownOption {
+                label: "timer off".to_string(),
+                value: TimerMode::Off,
+            },
+            DropdownOption {
+                label: "timer on".to_string(),
+                value: TimerMode::On,
+            },
+        ],
+        0,
+        true,
+        TimerModeDropdown,
+        parent,
+        asset_server,
+    );
+}
+
+/// Spawns the clock that shows the inspection and solve time. It is only visible when timed solves are on.
+pub fn spawn_clock(parent: &mut ChildBuilder<'_>, asset_server: &Res<AssetServer>) {
+    parent
+        .spawn((
+            Clock,
+            Node {
+                width: Val::Percent(100.),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                display: Display::None,
+                ..default()
+            },
+            PickingBehavior::IGNORE,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                ClockTimeLabel,
+                Text::new(""),
+                TextFont {
+                    font: asset_server.load(DEFAULT_FONT_BOLD),
+                    font_size: 56.0,
+                    ..default()
+                },
+                TextColor(BUTTON_BACKGROUND_COLOR),
+            ));
+
+            parent.spawn((
+                ClockStatusLabel,
+                Text::new(""),
+                TextFont {
+                    font: asset_server.load(DEFAULT_FONT),
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(BUTTON_BACKGROUND_COLOR),
+            ));
+        });
+}
+
+fn handle_timer_mode_dropdown(
+    query: Query<(&TimerMode, &Interaction), Changed<Interaction>>,
+    mut solve_timer: ResMut<SolveTimerResource>,
+) {
+    let Ok((timer_mode, interaction)) = query.get_single() else {
+        return;
+    };
+
+    if *interaction != Interaction::Pressed {
+        return;
+    }
+
+    solve_timer.enabled = match timer_mode {
+        TimerMode::Off => false,
+        TimerMode::On => true,
+    };
+}
+
+fn update_clock(
+    solve_timer: Res<SolveTimerResource>,
+    mut clock_query: Query<&mut Node, With<Clock>>,
+    mut time_label_query: Query<&mut Text, (With<ClockTimeLabel>, Without<ClockStatusLabel>)>,
+    mut status_label_query: Query<(&mut Text, &mut TextColor), With<ClockStatusLabel>>,
+) {
+    let Ok(mut clock_node) = clock_query.get_single_mut() else {
+        error!("expected exactly 1 Clock component");
+        return;
+    };
+
+    clock_node.display = if solve_timer.enabled {
+        Display::Flex
+    } else {
+        Display::None
+    };
+
+    if !solve_timer.enabled {
+        return;
+    }
+
+    let (Ok(mut time_label), Ok((mut status_label, mut status_color))) = (
+        time_label_query.get_single_mut(),
+        status_label_query.get_single_mut(),
+    ) else {
+        error!("expected exactly 1 ClockTimeLabel and 1 ClockStatusLabel component");
+        return;
+    };
+
+    let (time, status) = match solve_timer.state() {
+        TimerState::Idle => ("0.00".to_string(), "scramble to start".to_string()),
+        TimerState::WaitingForScramble => ("0.00".to_string(), "scrambling".to_string()),
+        TimerState::Inspecting { elapsed } => {
+            let time = if *elapsed <= INSPECTION_SECONDS {
+                (INSPECTION_SECONDS - elapsed).ceil().to_string()
+            } else {
+                "+2".to_string()
+            };
+
+            let status = match solve_timer.inspection_warning() {
+                Some(warning) => format!("{warning} seconds!"),
+                None => "inspection".to_string(),
+            };
+
+            (time, status)
+        }
+        TimerState::Solving { elapsed, .. } => (timer::format_time(*elapsed), String::new()),
+        TimerState::Finished(result) => {
+            let status = match result.penalty {
+                Some(Penalty::Dnf) => "inspection took too long",
+                _ => "solved",
+            };
+
+            (result.to_string(), status.to_string())
+        }
+    };
+
+    status_color.0 = if solve_timer.inspection_warning().is_some() {
+        COLOR_MAIN
+    } else {
+        BUTTON_BACKGROUND_COLOR
+    };
+
+    if time_label.0 != time {
+        time_label.0 = time;
+    }
+    if status_label.0 != status {
+        status_label.0 = status;
+    }
+}
@@ -4,9 +4,11 @@ use crate::{
     cube::{
         self,
         solver::{self, SolveStrategy},
-        CubeRotationAnimation, CubeRotationEvent, CubeState, SequenceResource,
+        AnimationProfileResource, CubeState, FastTurningResource, SequenceResource,
+        ANIMATION_PROFILES,
     },
     schedules::CubeScheduleSet,
+    timer::SolveTimerResource,
 };
 
 use super::{
@@ -22,9 +24,6 @@ use super::{
     },
 };
 
-const SCRAMBLING_ROTATION_SPEED: f32 = 0.15; // in seconds
-const SOLVING_ROTATION_SPEED: f32 = 0.35; // in seconds
-
 pub struct CubeActionsPlugin;
 
 impl Plugin for CubeActionsPlugin {
@@ -39,7 +38,12 @@ impl Plugin for CubeActionsPlugin {
             )
             .add_systems(
                 Update,
-                handle_sequence_speed_dropdown.in_set(CubeScheduleSet::HandleEvents),
+                (
+                    handle_sequence_speed_dropdown,
+                    handle_turning_mode_dropdown,
+                    handle_animation_profile_dropdown,
+                )
+                    .in_set(CubeScheduleSet::HandleEvents),
             );
     }
 }
@@ -52,8 +56,6 @@ pub struct ScrambleButtonProgressBar;
 #[derive(Component)]
 pub struct SolveButton;
 #[derive(Component)]
-pub struct SolveButtonProgressBar;
-#[derive(Component)]
 struct SequenceSpeedDropdown;
 
 #[derive(Component, Clone, Debug)]
@@ -62,9 +64,36 @@ enum SequenceSpeed {
     Instant,
 }
 
+impl SequenceSpeed {
+    fn multiplier(&self) -> Option<f32> {
+        match self {
+            SequenceSpeed::Multiplier(multiplier) => Some(*multiplier),
+            SequenceSpeed::Instant => None,
+        }
+    }
+}
+
 #[derive(Resource)]
 struct SequenceSpeedResource(SequenceSpeed);
 
+#[derive(Component)]
+struct AnimationProfileDropdown;
+
+/// Index into `ANIMATION_PROFILES`
+#[derive(Component, Clone)]
+struct AnimationProfileOption(usize);
+
+#[derive(Component)]
+struct TurningModeDropdown;
+
+#[derive(Component, Clone, Debug)]
+enum TurningMode {
+    /// Wait for a rotation to finish before starting the next one
+    Smooth,
+    /// Let rotations overlap. See `FastTurningResource`
+    Fast,
+}
+
 enum SequenceType {
     Scramble,
     Solve,
@@ -73,13 +102,58 @@ enum SequenceType {
 #[derive(Resource)]
 struct CurrentSequenceTypeResource(Option<SequenceType>);
 
-pub fn spawn(parent: &mut ChildBuilder<'_>, asset_server: &Res<AssetServer>) {
+pub fn spawn(
+    parent: &mut ChildBuilder<'_>,
+    asset_server: &Res<AssetServer>,
+    animation_profile: &AnimationProfileResource,
+) {
+    let selected_animation_profile_index = ANIMATION_PROFILES
+        .iter()
+        .position(|option| option.name == animation_profile.0.name)
+        .unwrap_or(0);
+
     parent
         .spawn(Node {
             column_gap: Val::Px(8.),
             ..default()
         })
         .with_children(|parent| {
+            // turning mode dropdown
+            widget::dropdown::spawn_type_select::<TurningMode>(
+                vec![
+                    DropdownOption {
+                        label: "smooth turning".to_string(),
+                        value: TurningMode::Smooth,
+                    },
+                    DropdownOption {
+                        label: "fast turning".to_string(),
+                        value: TurningMode::Fast,
+                    },
+                ],
+                0,
+                true,
+                TurningModeDropdown,
+                parent,
+                asset_server,
+            );
+
+            // animation profile dropdown
+            widget::dropdown::spawn_type_select::<AnimationProfileOption>(
+                ANIMATION_PROFILES
+                    .iter()
+                    .enumerate()
+                    .map(|(i, animation_profile)| DropdownOption {
+                        label: animation_profile.name.to_string(),
+                        value: AnimationProfileOption(i),
+                    })
+                    .collect(),
+                selected_animation_profile_index,
+                true,
+                AnimationProfileDropdown,
+                parent,
+                asset_server,
+            );
+
             // sequence speed dropdown
             widget::dropdown::spawn_type_select::<SequenceSpeed>(
                 vec![
@@ -209,33 +283,15 @@ pub fn spawn(parent: &mut ChildBuilder<'_>, asset_server: &Res<AssetServer>) {
                         blur_radius: Val::Px(1.),
                     },
                 ))
-                .with_children(|parent| {
-                    // progress bar
-                    parent.spawn((
-                        SolveButtonProgressBar,
-                        ProgressBar::default(),
-                        Node {
-                            width: Val::Percent(0.),
-                            height: Val::Percent(100.0),
-                            position_type: PositionType::Absolute,
-                            left: Val::ZERO,
-                            ..default()
-                        },
-                        BackgroundColor(BUTTON_BACKGROUND_COLOR),
-                        BorderRadius::all(Val::Px(4.)),
-                    ));
-
-                    // label
-                    parent.spawn((
-                        Text::new("solve"),
-                        TextFont {
-                            font: asset_server.load(DEFAULT_FONT_BOLD),
-                            font_size: 16.0,
-                            ..default()
-                        },
-                        TextColor(BUTTON_TEXT_COLOR),
-                    ));
-                });
+                .with_child((
+                    Text::new("solve"),
+                    TextFont {
+                        font: asset_server.load(DEFAULT_FONT_BOLD),
+                        font_size: 16.0,
+                        ..default()
+                    },
+                    TextColor(BUTTON_TEXT_COLOR),
+                ));
         });
 }
 
@@ -249,8 +305,10 @@ fn scramble_button_action(
     mut sequence_resource: ResMut<SequenceResource>,
     mut progress_bar_query: Query<&mut ProgressBar, With<ScrambleButtonProgressBar>>,
     sequence_speed: Res<SequenceSpeedResource>,
+    animation_profile: Res<AnimationProfileResource>,
     mut sequence_type: ResMut<CurrentSequenceTypeResource>,
     mut disable_button_event_writer: EventWriter<DisableButtonEvent>,
+    mut solve_timer: ResMut<SolveTimerResource>,
     time: Res<Time>,
 ) {
     let Ok((scramble_button_entity, interaction, disabled_handler)) =
@@ -272,32 +330,22 @@ fn scramble_button_action(
         return;
     };
 
-    let scramble_length = (cube.size().0 + 1) as usize * 6;
+    let scramble_length = cube::scramble_length(cube.size());
 
-    let mut scramble_sequence = cube::create_random_scramble_sequence(cube.size(), scramble_length);
+    let scramble_sequence = cube::create_random_scramble_sequence(cube.size(), scramble_length);
 
-    match sequence_speed.0 {
-        SequenceSpeed::Multiplier(multiplier) => {
-            for cube_rotation in scramble_sequence.iter_mut() {
-                cube_rotation.animation = Some(CubeRotationAnimation {
-                    duration_in_seconds: SCRAMBLING_ROTATION_SPEED / multiplier,
-                    ease_function: Some(EaseFunction::Linear),
-                });
-            }
-
-            ease_out_scramble_sequence(&mut scramble_sequence);
-        }
-        SequenceSpeed::Instant => (),
-    }
+    sequence_resource.set_animated(
+        scramble_sequence,
+        &animation_profile.0.scramble,
+        sequence_speed.0.multiplier(),
+    );
 
-    let mut scramble_duration: f32 = 0.0;
-    for rotation in &scramble_sequence {
-        if let Some(animation) = &rotation.animation {
-            scramble_duration += animation.duration_in_seconds;
-        }
+    if solve_timer.enabled {
+        solve_timer.wait_for_scramble();
     }
 
-    sequence_resource.set(scramble_sequence);
+    // steps can be animated concurrently, so let the sequence resource figure out the duration
+    let scramble_duration = sequence_resource.seconds_until_complete();
 
     if scramble_duration == 0.0 {
         return;
@@ -332,8 +380,8 @@ fn solve_button_action(
     scramble_button_query: Query<Entity, With<ScrambleButton>>,
     cube_state_query: Query<&CubeState>,
     mut sequence_resource: ResMut<SequenceResource>,
-    mut progress_bar_query: Query<&mut ProgressBar, With<SolveButtonProgressBar>>,
     sequence_speed: Res<SequenceSpeedResource>,
+    animation_profile: Res<AnimationProfileResource>,
     mut sequence_type: ResMut<CurrentSequenceTypeResource>,
     mut disable_button_event_writer: EventWriter<DisableButtonEvent>,
     time: Res<Time>,
@@ -356,50 +404,31 @@ fn solve_button_action(
         return;
     };
 
-    let mut solve_sequence = solver::get_solve_sequence(SolveStrategy::Kociemba, cube_state);
-
-    match sequence_speed.0 {
-        SequenceSpeed::Multiplier(multiplier) => {
-            for cube_rotation in solve_sequence.iter_mut() {
-                cube_rotation.animation = Some(CubeRotationAnimation {
-                    duration_in_seconds: SOLVING_ROTATION_SPEED / multiplier,
-                    ease_function: Some(EaseFunction::CubicOut),
-                });
-            }
-        }
-        SequenceSpeed::Instant => (),
-    }
+    let solve_sequence = solver::get_solve_sequence(SolveStrategy::Kociemba, cube_state);
 
-    let mut solve_duration: f32 = 0.0;
-    for rotation in &solve_sequence {
-        match &rotation.animation {
-            Some(animation) => solve_duration += animation.duration_in_seconds,
-            None => (),
-        }
-    }
+    sequence_resource.set_animated(
+        solve_sequence,
+        &animation_profile.0.solve,
+        sequence_speed.0.multiplier(),
+    );
 
-    sequence_resource.set(solve_sequence);
+    let solve_duration = sequence_resource.seconds_until_complete();
 
     if solve_duration == 0.0 {
         return;
     }
 
-    let Ok(mut progress_bar) = progress_bar_query.get_single_mut() else {
-        error!("solve_button_action: failed to get solve button progress bar");
-        return;
-    };
-
-    // we subtract one tick because the first tick of the cube rotation animation will already be performed in the current frame.
-    let progress_bar_duration = solve_duration - time.delta_secs();
-    progress_bar.set_timer(Timer::from_seconds(progress_bar_duration, TimerMode::Once));
+    // The progress of a solve is shown in the timeline. We subtract one tick because the first tick of
+    // the cube rotation animation will already be performed in the current frame.
+    let solve_button_disabled_duration = solve_duration - time.delta_secs();
 
     disable_button_event_writer.send(DisableButtonEvent {
         entity: scramble_button_query.get_single().unwrap(),
-        enable_after: Some(progress_bar_duration),
+        enable_after: Some(solve_button_disabled_duration),
     });
     disable_button_event_writer.send(DisableButtonEvent {
         entity: solve_button_entity,
-        enable_after: Some(progress_bar_duration),
+        enable_after: Some(solve_button_disabled_duration),
     });
 
     sequence_type.0 = Some(SequenceType::Solve);
@@ -411,17 +440,7 @@ fn handle_sequence_speed_dropdown(
     mut sequence_resource: ResMut<SequenceResource>,
     mut scramble_button_progress_bar_query: Query<
         &mut ProgressBar,
-        (
-            With<ScrambleButtonProgressBar>,
-            Without<SolveButtonProgressBar>,
-        ),
-    >,
-    mut solve_button_progress_bar_query: Query<
-        &mut ProgressBar,
-        (
-            With<SolveButtonProgressBar>,
-            Without<ScrambleButtonProgressBar>,
-        ),
+        With<ScrambleButtonProgressBar>,
     >,
     scramble_button_entity_query: Query<Entity, With<ScrambleButton>>,
     solve_button_entity_query: Query<Entity, With<SolveButton>>,
@@ -448,44 +467,25 @@ fn handle_sequence_speed_dropdown(
         None => return,
     };
 
-    // update sequence
-    {
-        let rotation_speed = match sequence_type {
-            SequenceType::Scramble => SCRAMBLING_ROTATION_SPEED,
-            SequenceType::Solve => SOLVING_ROTATION_SPEED,
-        };
-        let ease_function = EaseFunction::Linear;
-
-        for sequence_step in &mut sequence_resource.steps {
-            match sequence_speed_resource.0 {
-                SequenceSpeed::Multiplier(multipler) => {
-                    sequence_step.animation = Some(CubeRotationAnimation {
-                        duration_in_seconds: rotation_speed / multipler,
-                        ease_function: Some(ease_function),
-                    })
-                }
-                SequenceSpeed::Instant => {
-                    sequence_step.animation = None;
-                }
-            }
-        }
+    sequence_resource.set_speed(sequence_speed_resource.0.multiplier());
 
-        ease_out_scramble_sequence(&mut sequence_resource.steps);
+    // a paused sequence does not block the buttons
+    if sequence_resource.is_paused() {
+        return;
     }
 
     // update progress bar
     {
-        let mut progress_bar = match sequence_type {
-            SequenceType::Scramble => scramble_button_progress_bar_query.get_single_mut().unwrap(),
-            SequenceType::Solve => solve_button_progress_bar_query.get_single_mut().unwrap(),
-        };
-
         let progress_bar_duration = match sequence_speed_resource.0 {
             SequenceSpeed::Multiplier(_) => sequence_resource.seconds_until_complete(),
             SequenceSpeed::Instant => 0.0,
         };
 
-        progress_bar.update_timer(progress_bar_duration);
+        // the progress of a solve is shown in the timeline instead of with a progress bar
+        if let SequenceType::Scramble = sequence_type {
+            let mut progress_bar = scramble_button_progress_bar_query.get_single_mut().unwrap();
+            progress_bar.update_timer(progress_bar_duration);
+        }
 
         // Subtract 1 tick to prevent the progress bar flickering when done. There is probably a deeper
         // issue at play here with the scheduling, but this does the job for now.
@@ -501,25 +501,35 @@ fn handle_sequence_speed_dropdown(
     }
 }
 
-fn ease_out_scramble_sequence(sequence: &mut Vec<CubeRotationEvent>) {
-    if sequence.len() < 2 {
+fn handle_turning_mode_dropdown(
+    query: Query<(&TurningMode, &Interaction), Changed<Interaction>>,
+    mut fast_turning_resource: ResMut<FastTurningResource>,
+) {
+    let Ok((turning_mode, interaction)) = query.get_single() else {
+        return;
+    };
+
+    if *interaction != Interaction::Pressed {
         return;
     }
 
-    let sequence_len = sequence.len();
+    fast_turning_resource.0 = match turning_mode {
+        TurningMode::Smooth => false,
+        TurningMode::Fast => true,
+    };
+}
 
-    match &mut sequence[sequence_len - 2].animation {
-        Some(animation) => {
-            animation.duration_in_seconds *= 1.3;
-        }
-        None => (),
-    }
+fn handle_animation_profile_dropdown(
+    query: Query<(&AnimationProfileOption, &Interaction), Changed<Interaction>>,
+    mut animation_profile_resource: ResMut<AnimationProfileResource>,
+) {
+    let Ok((animation_profile_option, interaction)) = query.get_single() else {
+        return;
+    };
 
-    match &mut sequence[sequence_len - 1].animation {
-        Some(animation) => {
-            animation.duration_in_seconds *= 2.0;
-            animation.ease_function = Some(EaseFunction::CubicOut);
-        }
-        None => (),
+    if *interaction != Interaction::Pressed {
+        return;
     }
+
+    animation_profile_resource.0 = &ANIMATION_PROFILES[animation_profile_option.0];
 }
@@ -0,0 +1,232 @@
+// A folded out view of the cube, in the layout of the documentation of `FaceStates`. Every sticker of the net has the
+// color of its facelet in the cube state, so the net also shows the faces that are hidden in 3D. The stickers of the
+// slices that are being turned are outlined.
+
+use bevy::prelude::*;
+
+use crate::{
+    cube::{
+        facelet_color, facelet_pieces, CubeState, CurrentRotationResource, FaceRotation, Rotation,
+    },
+    schedules::CubeScheduleSet,
+};
+
+use super::interface::{CaptureClick, COLOR_MAIN};
+
+/// The grid row and column of every face in the net, in the order of `CubeState::to_facelets`
+const FACE_POSITIONS: [(i16, i16); 6] = [(1, 2), (2, 3), (2, 2), (3, 2), (2, 1), (2, 4)];
+
+pub struct CubeNetPlugin;
+
+impl Plugin for CubeNetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                spawn_net_stickers,
+                color_net_stickers,
+                highlight_rotating_net_stickers,
+            )
+                .chain()
+                .in_set(CubeScheduleSet::UpdateAnimations),
+        );
+    }
+}
+
+/// A net of the cube. Its stickers are spawned as children for the size of the cube.
+#[derive(Component)]
+#[require(Node)]
+pub struct CubeNet {
+    /// The width and height of a face in pixels
+    face_size: f32,
+    /// Whether the stickers are buttons
+    clickable: bool,
+    /// The cube size that the stickers were spawned for
+    cube_size: Option<usize>,
+}
+
+impl CubeNet {
+    pub fn new(face_size: f32, clickable: bool) -> Self {
+        Self {
+            face_size,
+            clickable,
+            cube_size: None,
+        }
+    }
+}
+
+/// A sticker of a net, with the index of its facelet in `CubeState::to_facelets`
+#[derive(Component)]
+pub struct NetSticker(pub usize);
+
+/// The slice indices of the piece that a sticker of a net belongs to
+#[derive(Component)]
+struct NetStickerPiece(IVec3);
+
+fn spawn_net_stickers(
+    mut commands: Commands,
+    mut net_query: Query<(Entity, &mut CubeNet, &mut Node)>,
+    cube_state_query: Query<&CubeState>,
+) {
+    let Ok(cube_state) = cube_state_query.get_single() else {
+        return;
+    };
+    let cube_size = cube_state.size();
+
+    for (entity, mut net, mut node) in net_query.iter_mut() {
+        if net.cube_size == Some(cube_size) {
+            continue;
+        }
+        net.cube_size = Some(cube_size);
+
+        *node = Node {
+            display: Display::Grid,
+            grid_template_columns: RepeatedGridTrack::px(4, net.face_size),
+            grid_template_rows: RepeatedGridTrack::px(3, net.face_size),
+            column_gap: Val::Px(3.),
+            row_gap: Val::Px(3.),
+            ..default()
+        };
+
+        let clickable = net.clickable;
+        let pieces = facelet_pieces(cube_size);
+        commands
+            .entity(entity)
+            .despawn_descendants()
+            .with_children(|parent| {
+                for (face_index, (row, column)) in FACE_POSITIONS.iter().enumerate() {
+                    parent
+                        .spawn(Node {
+                            display: Display::Grid,
+                            grid_row: GridPlacement::start(*row),
+                            grid_column: GridPlacement::start(*column),
+                            grid_template_columns: RepeatedGridTrack::flex(cube_size as u16, 1.),
+                            grid_template_rows: RepeatedGridTrack::flex(cube_size as u16, 1.),
+                            column_gap: Val::Px(1.),
+                            row_gap: Val::Px(1.),
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            for i in 0..cube_size * cube_size {
+                                let facelet_index = face_index * cube_size * cube_size + i;
+                                let mut sticker = parent.spawn((
+                                    NetSticker(facelet_index),
+                                    NetStickerPiece(pieces[facelet_index]),
+                                    Node::default(),
+                                    BackgroundColor(Color::BLACK),
+                                    BorderRadius::all(Val::Px(1.)),
+                                    Outline::new(Val::Px(2.), Val::ZERO, Color::NONE),
+                                ));
+
+                                if clickable {
+                                    sticker.insert((Button, CaptureClick));
+                                }
+                            }
+                        });
+                }
+            });
+    }
+}
+
+fn color_net_stickers(
+    cube_state_query: Query<Ref<CubeState>>,
+    added_sticker_query: Query<(), Added<NetSticker>>,
+    mut sticker_query: Query<(&NetSticker, &mut BackgroundColor)>,
+) {
+    let Ok(cube_state) = cube_state_query.get_single() else {
+        return;
+    };
+
+    if !cube_state.is_changed() && added_sticker_query.is_empty() {
+        return;
+    }
+
+    let facelets: Vec<char> = cube_state.to_facelets().chars().collect();
+    for (sticker, mut background_color) in sticker_query.iter_mut() {
+        // a net of another cube size gets new stickers soon
+        if let Some(color) = facelets.get(sticker.0).and_then(|f| facelet_color(*f)) {
+            background_color.0 = color;
+        }
+    }
+}
+
+fn highlight_rotating_net_stickers(
+    current_rotation: Res<CurrentRotationResource>,
+    mut sticker_query: Query<(&NetStickerPiece, &mut Outline)>,
+) {
+    for (piece, mut outline) in sticker_query.iter_mut() {
+        let color = if current_rotation
+            .rotations()
+            .any(|rotation| is_in_rotation(piece.0, rotation))
+        {
+            COLOR_MAIN
+        } else {
+            Color::NONE
+        };
+
+        // only touch the outlines that change, so that the ui is not laid out again every frame
+        if outline.color != color {
+            outline.color = color;
+        }
+    }
+}
+
+/// Whether the piece with the given slice indices is turned by a rotation
+fn is_in_rotation(piece: IVec3, rotation: &Rotation) -> bool {
+    match rotation {
+        Rotation::Face(FaceRotation::X(slices)) => slices.contains(&piece.x),
+        Rotation::Face(FaceRotation::Y(slices)) => slices.contains(&piece.y),
+        Rotation::Face(FaceRotation::Z(slices)) => slices.contains(&piece.z),
+        Rotation::Cube(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cube::{facelet_pieces, CubeRotationEvent, CubeSize};
+
+    use super::is_in_rotation;
+
+    /// The facelets of the R layer: the right face, the right column of the top, front and bottom faces, and the left
+    /// column of the back face
+    fn r_layer_facelets(cube_size: usize) -> Vec<usize> {
+        let face_size = cube_size * cube_size;
+        let column = |face: usize, column: usize| {
+            (0..cube_size).map(move |row| face * face_size + row * cube_size + column)
+        };
+
+        let mut facelets: Vec<usize> = (face_size..2 * face_size)
+            .chain(column(0, cube_size - 1))
+            .chain(column(2, cube_size - 1))
+            .chain(column(3, cube_size - 1))
+            .chain(column(5, 0))
+            .collect();
+        facelets.sort_unstable();
+        facelets
+    }
+
+    #[test]
+    fn test_is_in_rotation() {
+        for cube_size in [3, 4] {
+            let pieces = facelet_pieces(cube_size);
+            let outlined = |notation: &str| -> Vec<usize> {
+                let rotation_event =
+                    CubeRotationEvent::from_notation(notation, &CubeSize(cube_size as i32))
+                        .unwrap();
+                (0..pieces.len())
+                    .filter(|i| is_in_rotation(pieces[*i], &rotation_event.rotation))
+                    .collect()
+            };
+
+            assert_eq!(
+                r_layer_facelets(cube_size),
+                outlined("R"),
+                "{cube_size}x{cube_size}"
+            );
+            assert_eq!(
+                (0..cube_size * cube_size * 6).collect::<Vec<_>>(),
+                outlined("x")
+            );
+        }
+    }
+}
@@ -1,7 +1,9 @@
 use bevy::prelude::*;
 
 use crate::{
-    cube::{self, Cube, CubeRotationAnimation, CubeRotationEvent},
+    cube::{
+        self, CubeRotationAnimation, CubeRotationEvent, EaseFunction, RotationInputQueueResource,
+    },
     schedules::CubeScheduleSet,
 };
 
@@ -122,8 +124,7 @@ pub fn spawn(parent: &mut ChildBuilder<'_>, asset_server: &Res<AssetServer>) {
 
 fn handle_cube_rotation_dropdown(
     event_query: Query<(&CubeRotation, &Interaction), Changed<Interaction>>,
-    cube_query: Query<&Cube>,
-    mut event_writer: EventWriter<CubeRotationEvent>,
+    mut rotation_input_queue: ResMut<RotationInputQueueResource>,
 ) {
     let Ok((cube_rotation, interaction)) = event_query.get_single() else {
         return;
@@ -132,14 +133,6 @@ fn handle_cube_rotation_dropdown(
         return;
     }
 
-    let Ok(cube) = cube_query.get_single() else {
-        error!("failed to get cube");
-        return;
-    };
-    if cube.is_animating_rotation {
-        return;
-    }
-
     let (rotation, negative_direction) = match cube_rotation {
         CubeRotation::X => (cube::Rotation::cube_x(), true),
         CubeRotation::XPrime => (cube::Rotation::cube_x(), false),
@@ -149,7 +142,7 @@ fn handle_cube_rotation_dropdown(
         CubeRotation::ZPrime => (cube::Rotation::cube_z(), true),
     };
 
-    event_writer.send(CubeRotationEvent {
+    let rotation_event = CubeRotationEvent {
         rotation,
         negative_direction,
         twice: false,
@@ -157,5 +150,9 @@ fn handle_cube_rotation_dropdown(
             duration_in_seconds: 0.4,
             ease_function: Some(EaseFunction::CubicOut),
         }),
-    });
+    };
+
+    if !rotation_input_queue.push(rotation_event) {
+        debug!("rotation input queue is full, ignoring cube rotation");
+    }
 }
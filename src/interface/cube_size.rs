@@ -31,6 +31,10 @@ impl Plugin for CubeSizePlugin {
                 increase_cube_size_button_action,
             )
                 .in_set(CubeScheduleSet::HandleUserInput),
+        )
+        .add_systems(
+            Update,
+            update_cube_size_label.in_set(CubeScheduleSet::UpdateAnimations),
         );
     }
 }
@@ -248,3 +252,22 @@ fn increase_cube_size_button_action(
     enable_button_event_writer.send(EnableButtonEvent::new(scramble_button_query.single()));
     enable_button_event_writer.send(EnableButtonEvent::new(solve_button_query.single()));
 }
+
+/// Keeps the label up to date when the size gets changed without the buttons, for example by a script
+fn update_cube_size_label(
+    cube_size_resource: Res<CurrentCubeSizeResource>,
+    mut cube_size_label_query: Query<&mut Text, With<CubeSizeLabel>>,
+) {
+    if !cube_size_resource.is_changed() {
+        return;
+    }
+
+    let Ok(mut cube_size_label) = cube_size_label_query.get_single_mut() else {
+        return;
+    };
+
+    let cube_size = cube_size_resource.0.to_string();
+    if cube_size_label.0 != cube_size {
+        cube_size_label.0 = cube_size;
+    }
+}
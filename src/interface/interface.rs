@@ -1,18 +1,26 @@
 use bevy::prelude::*;
 
 use crate::{
-    cube::{self},
+    cube::{self, AnimationProfileResource},
     schedules::CubeScheduleSet,
 };
 
 use super::{
+    camera_views::{self, CameraViewsPlugin},
+    clock::{self, ClockPlugin},
     cube_actions::{self, CubeActionsPlugin},
+    cube_net::CubeNetPlugin,
     cube_rotations::{self, CubeRotationsPlugin},
     cube_size::{self, CubeSizePlugin},
     gradient_shader::{
         BackgroundGradientMaterial, BackgroundGradientMaterialBuilder, ColorSize,
         GradientShaderPlugin, GradientType,
     },
+    net_view::{self, NetViewPlugin},
+    reconstruction::{self, ReconstructionMenuPlugin},
+    state_editor::{self, StateEditorPlugin},
+    statistics::{self, StatisticsPlugin},
+    timeline::{self, TimelinePlugin},
     widget,
 };
 
@@ -53,6 +61,14 @@ impl Plugin for InterfacePlugin {
         .add_plugins(CubeSizePlugin)
         .add_plugins(CubeActionsPlugin)
         .add_plugins(CubeRotationsPlugin)
+        .add_plugins(CameraViewsPlugin)
+        .add_plugins(ClockPlugin)
+        .add_plugins(StatisticsPlugin)
+        .add_plugins(ReconstructionMenuPlugin)
+        .add_plugins(TimelinePlugin)
+        .add_plugins(CubeNetPlugin)
+        .add_plugins(NetViewPlugin)
+        .add_plugins(StateEditorPlugin)
         .add_systems(Startup, init)
         .add_systems(
             Update,
@@ -85,6 +101,7 @@ fn init(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut ui_materials: ResMut<Assets<BackgroundGradientMaterial>>,
+    animation_profile: Res<AnimationProfileResource>,
 ) {
     let colored_border_alpha = 0.7;
 
@@ -144,9 +161,105 @@ fn init(
                     ..default()
                 })
                 .with_children(|parent| {
-                    cube_rotations::spawn(parent, &asset_server);
-                    cube_actions::spawn(parent, &asset_server);
+                    parent
+                        .spawn(Node {
+                            column_gap: Val::Px(8.),
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            cube_rotations::spawn(parent, &asset_server);
+                            camera_views::spawn(parent, &asset_server);
+                            clock::spawn(parent, &asset_server);
+                            reconstruction::spawn(parent, &asset_server);
+                            net_view::spawn(parent, &asset_server);
+                            state_editor::spawn(parent, &asset_server);
+                        });
+                    cube_actions::spawn(parent, &asset_server, &animation_profile);
                     cube_size::spawn(parent, &asset_server);
                 });
         });
+
+    // clock container element
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                top: Val::Px(72.),
+                ..default()
+            },
+            PickingBehavior::IGNORE,
+        ))
+        .with_children(|parent| {
+            clock::spawn_clock(parent, &asset_server);
+        });
+
+    // statistics container element
+    commands
+        .spawn(Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(72.),
+            right: Val::Px(12.),
+            ..default()
+        })
+        .with_children(|parent| {
+            statistics::spawn(parent, &asset_server);
+        });
+
+    // reconstruction file container element
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(72.),
+                left: Val::Px(12.),
+                ..default()
+            },
+            PickingBehavior::IGNORE,
+        ))
+        .with_children(|parent| {
+            reconstruction::spawn_file_bar(parent, &asset_server);
+        });
+
+    // state editor container element
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(128.),
+                left: Val::Px(12.),
+                ..default()
+            },
+            PickingBehavior::IGNORE,
+        ))
+        .with_children(|parent| {
+            state_editor::spawn_panel(parent, &asset_server);
+        });
+
+    // net view container element
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(96.),
+                right: Val::Px(12.),
+                ..default()
+            },
+            PickingBehavior::IGNORE,
+        ))
+        .with_children(|parent| {
+            net_view::spawn_panel(parent);
+        });
+
+    // timeline container element
+    commands
+        .spawn(Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.),
+            bottom: Val::ZERO,
+            ..default()
+        })
+        .with_children(|parent| {
+            timeline::spawn(parent, &asset_server);
+        });
 }
@@ -1,8 +1,16 @@
 pub mod interface;
 
+mod camera_views;
+mod clock;
 mod cube_actions;
+mod cube_net;
 mod cube_rotations;
 mod cube_size;
+mod net_view;
+mod reconstruction;
+mod state_editor;
+mod statistics;
+mod timeline;
 
 #[allow(dead_code)]
 mod widget;
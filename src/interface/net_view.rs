@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+
+use crate::schedules::CubeScheduleSet;
+
+use super::{
+    cube_net::CubeNet,
+    interface::{BUTTON_BORDER, BUTTON_BORDER_RADIUS, COLOR_BLUE, COLOR_MAIN},
+    widget::{self, dropdown::DropdownOption},
+};
+
+pub struct NetViewPlugin;
+
+impl Plugin for NetViewPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            handle_net_view_mode_dropdown.in_set(CubeScheduleSet::HandleEvents),
+        );
+    }
+}
+
+#[derive(Component)]
+struct NetViewModeDropdown;
+
+#[derive(Component, Clone, Debug)]
+enum NetViewMode {
+    Off,
+    /// The net of the current state is shown next to the cube
+    On,
+}
+
+/// Shows the current state of the cube as a net. It is only visible when the net view is on.
+#[derive(Component)]
+struct NetViewPanel;
+
+/// Spawns the dropdown to show and hide the net
+pub fn spawn(parent: &mut ChildBuilder<'_>, asset_server: &Res<AssetServer>) {
+    widget::dropdown::spawn_type_select::<NetViewMode>(
+        vec![
+            DropdownOption {
+                label: "net off".to_string(),
+                value: NetViewMode::Off,
+            },
+            DropdownOption {
+                label: "net on".to_string(),
+                value: NetViewMode::On,
+            },
+        ],
+        0,
+        true,
+        NetViewModeDropdown,
+        parent,
+        asset_server,
+    );
+}
+
+pub fn spawn_panel(parent: &mut ChildBuilder<'_>) {
+    parent
+        .spawn((
+            NetViewPanel,
+            Node {
+                padding: UiRect::all(Val::Px(12.)),
+                border: BUTTON_BORDER,
+                display: Display::None,
+                ..default()
+            },
+            BorderColor(COLOR_MAIN),
+            BUTTON_BORDER_RADIUS,
+            BackgroundColor(COLOR_BLUE.with_alpha(0.8)),
+            PickingBehavior::IGNORE,
+        ))
+        .with_child(CubeNet::new(40., false));
+}
+
+fn handle_net_view_mode_dropdown(
+    query: Query<(&NetViewMode, &Interaction), Changed<Interaction>>,
+    mut panel_query: Query<&mut Node, With<NetViewPanel>>,
+) {
+    let Ok((net_view_mode, interaction)) = query.get_single() else {
+        return;
+    };
+
+    if *interaction != Interaction::Pressed {
+        return;
+    }
+
+    let Ok(mut panel) = panel_query.get_single_mut() else {
+        error!("expected exactly 1 NetViewPanel component");
+        return;
+    };
+
+    panel.display = match net_view_mode {
+        NetViewMode::Off => Display::None,
+        NetViewMode::On => Display::Flex,
+    };
+}
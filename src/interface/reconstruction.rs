@@ -0,0 +1,297 @@
+use std::path::Path;
+
+use bevy::prelude::*;
+
+use crate::{
+    cube::{CubeState, SequenceResource},
+    reconstruction::{self, ReconstructionResource},
+    schedules::CubeScheduleSet,
+    utils::storage,
+};
+
+use super::{
+    interface::{
+        CaptureClick, BUTTON_BACKGROUND_COLOR, BUTTON_BORDER, BUTTON_BORDER_RADIUS,
+        BUTTON_TEXT_COLOR, COLOR_BLUE, DEFAULT_FONT, DEFAULT_FONT_BOLD,
+    },
+    widget::{self, dropdown::DropdownOption, text_input::TextInput},
+};
+
+const EXPORT_FILE: &str = "reconstruction.txt";
+const DEFAULT_SAVE_FILE: &str = "solve.reconstruction";
+
+pub struct ReconstructionMenuPlugin;
+
+impl Plugin for ReconstructionMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (save_button_action, open_button_action).in_set(CubeScheduleSet::HandleUserInput),
+        )
+        .add_systems(
+            Update,
+            handle_reconstruction_dropdown.in_set(CubeScheduleSet::HandleEvents),
+        );
+    }
+}
+
+#[derive(Component)]
+struct ReconstructionDropdownButton;
+
+#[derive(Component)]
+struct ReconstructionPathInput;
+#[derive(Component)]
+struct SaveReconstructionButton;
+#[derive(Component)]
+struct OpenReconstructionButton;
+/// Shows the result of saving or opening a reconstruction
+#[derive(Component)]
+struct ReconstructionFileStatusLabel;
+
+#[derive(Component, Clone, Debug)]
+enum ReconstructionAction {
+    /// Replay the last recorded solve with the given speed multiplier
+    Replay(f32),
+    Export,
+}
+
+pub fn spawn(parent: &mut ChildBuilder<'_>, asset_server: &Res<AssetServer>) {
+    widget::dropdown::spawn_type_menu(
+        vec![
+            DropdownOption {
+                label: "replay x0.5".to_string(),
+                value: ReconstructionAction::Replay(0.5),
+            },
+            DropdownOption {
+                label: "replay x1.0".to_string(),
+                value: ReconstructionAction::Replay(1.0),
+            },
+            DropdownOption {
+                label: "replay x2.0".to_string(),
+                value: ReconstructionAction::Replay(2.0),
+            },
+            DropdownOption {
+                label: "export".to_string(),
+                value: ReconstructionAction::Export,
+            },
+        ],
+        true,
+        ReconstructionDropdownButton,
+        (
+            Text::new("reconstruction"),
+            TextFont {
+                font: asset_server.load(DEFAULT_FONT_BOLD),
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(BUTTON_TEXT_COLOR),
+        ),
+        parent,
+        asset_server,
+    );
+}
+
+/// Spawns a file path input with buttons to save the last reconstruction to that file, or to open a reconstruction
+/// from it so that it can be replayed
+pub fn spawn_file_bar(parent: &mut ChildBuilder<'_>, asset_server: &Res<AssetServer>) {
+    parent
+        .spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.),
+                ..default()
+            },
+            PickingBehavior::IGNORE,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(Node {
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(8.),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    widget::text_input::spawn_text_input(
+                        storage::path(DEFAULT_SAVE_FILE).display().to_string(),
+                        Val::Px(320.),
+                        ReconstructionPathInput,
+                        parent,
+                        asset_server,
+                    );
+                    spawn_file_button("save", SaveReconstructionButton, parent, asset_server);
+                    spawn_file_button("open", OpenReconstructionButton, parent, asset_server);
+                });
+
+            parent.spawn((
+                ReconstructionFileStatusLabel,
+                Text::new(""),
+                TextFont {
+                    font: asset_server.load(DEFAULT_FONT),
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(BUTTON_BACKGROUND_COLOR),
+                BackgroundColor(COLOR_BLUE.with_alpha(0.8)),
+            ));
+        });
+}
+
+fn spawn_file_button(
+    label: &str,
+    marker: impl Bundle,
+    parent: &mut ChildBuilder<'_>,
+    asset_server: &Res<AssetServer>,
+) {
+    parent
+        .spawn((
+            marker,
+            CaptureClick,
+            Node {
+                justify_content: JustifyContent::Center,
+                padding: UiRect::axes(Val::Px(16.), Val::Px(8.)),
+                border: BUTTON_BORDER,
+                ..default()
+            },
+            BorderColor(Color::BLACK),
+            BUTTON_BORDER_RADIUS,
+            BackgroundColor(BUTTON_BACKGROUND_COLOR),
+        ))
+        .with_child((
+            Text::new(label),
+            TextFont {
+                font: asset_server.load(DEFAULT_FONT_BOLD),
+                font_size: 14.0,
+                ..default()
+            },
+            TextColor(BUTTON_TEXT_COLOR),
+        ));
+}
+
+fn save_button_action(
+    button_query: Query<&Interaction, (With<SaveReconstructionButton>, Changed<Interaction>)>,
+    path_input_query: Query<&TextInput, With<ReconstructionPathInput>>,
+    mut status_label_query: Query<&mut Text, With<ReconstructionFileStatusLabel>>,
+    reconstruction_resource: Res<ReconstructionResource>,
+) {
+    let Ok(interaction) = button_query.get_single() else {
+        return;
+    };
+    if *interaction != Interaction::Pressed {
+        return;
+    }
+
+    let (Ok(path_input), Ok(mut status_label)) = (
+        path_input_query.get_single(),
+        status_label_query.get_single_mut(),
+    ) else {
+        error!("expected exactly 1 ReconstructionPathInput and 1 ReconstructionFileStatusLabel");
+        return;
+    };
+
+    let Some(reconstruction) = reconstruction_resource.last() else {
+        status_label.0 = "there is no reconstruction to save".to_string();
+        return;
+    };
+
+    let path = Path::new(path_input.value.trim());
+    status_label.0 = match reconstruction::file::save(path, reconstruction) {
+        Ok(()) => format!("saved to {}", path.display()),
+        Err(err) => err,
+    };
+}
+
+fn open_button_action(
+    button_query: Query<&Interaction, (With<OpenReconstructionButton>, Changed<Interaction>)>,
+    path_input_query: Query<&TextInput, With<ReconstructionPathInput>>,
+    mut status_label_query: Query<&mut Text, With<ReconstructionFileStatusLabel>>,
+    mut reconstruction_resource: ResMut<ReconstructionResource>,
+    cube_state_query: Query<&CubeState>,
+) {
+    let Ok(interaction) = button_query.get_single() else {
+        return;
+    };
+    if *interaction != Interaction::Pressed {
+        return;
+    }
+
+    let (Ok(path_input), Ok(mut status_label)) = (
+        path_input_query.get_single(),
+        status_label_query.get_single_mut(),
+    ) else {
+        error!("expected exactly 1 ReconstructionPathInput and 1 ReconstructionFileStatusLabel");
+        return;
+    };
+
+    let reconstruction = match reconstruction::file::load(Path::new(path_input.value.trim())) {
+        Ok(reconstruction) => reconstruction,
+        Err(err) => {
+            status_label.0 = err;
+            return;
+        }
+    };
+
+    let cube_size = reconstruction.cube_size;
+    status_label.0 = match cube_state_query.get_single() {
+        Ok(cube_state) if cube_state.size() != cube_size => format!(
+            "opened a {0}x{0} solve of {1} moves, switch to a {0}x{0} cube to replay it",
+            cube_size,
+            reconstruction.move_count()
+        ),
+        _ => format!(
+            "opened a {0}x{0} solve of {1} moves",
+            cube_size,
+            reconstruction.move_count()
+        ),
+    };
+
+    reconstruction_resource.set_last(reconstruction);
+}
+
+fn handle_reconstruction_dropdown(
+    event_query: Query<(&ReconstructionAction, &Interaction), Changed<Interaction>>,
+    reconstruction_resource: Res<ReconstructionResource>,
+    mut sequence_resource: ResMut<SequenceResource>,
+    cube_state_query: Query<&CubeState>,
+) {
+    let Ok((action, interaction)) = event_query.get_single() else {
+        return;
+    };
+    if *interaction != Interaction::Pressed {
+        return;
+    }
+
+    match action {
+        ReconstructionAction::Replay(speed_multiplier) => {
+            if !sequence_resource.is_done() {
+                warn!("can not replay the reconstruction while a sequence is running");
+                return;
+            }
+
+            let Ok(cube_state) = cube_state_query.get_single() else {
+                error!("expected exactly 1 CubeState component");
+                return;
+            };
+
+            match reconstruction_resource.replay_sequence(cube_state, *speed_multiplier) {
+                Some((steps, step_pauses)) => sequence_resource.set_with_pauses(steps, step_pauses),
+                None => warn!(
+                    "there is no reconstruction to replay on this cube, it can only be replayed on a solved cube of the same size"
+                ),
+            }
+        }
+        ReconstructionAction::Export => {
+            let Some(reconstruction) = reconstruction_resource.last() else {
+                warn!("there is no reconstruction to export");
+                return;
+            };
+
+            match storage::write(EXPORT_FILE, &reconstruction.to_timed_notation()) {
+                Ok(()) => info!(
+                    "exported reconstruction to {}",
+                    storage::path(EXPORT_FILE).display()
+                ),
+                Err(err) => error!("failed to export reconstruction: {err}"),
+            }
+        }
+    }
+}
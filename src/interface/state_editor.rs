@@ -0,0 +1,437 @@
+// An editor to enter a cube state sticker by sticker, for example to solve a physical cube. While the editor is open,
+// clicking a sticker on the cube or on the net paints it with the color that is picked in the palette. Clicking a
+// sticker that already has that color gives it the next color instead. The state is checked after every change, and
+// "solve this" plays the solution of the solver for the entered state.
+
+use bevy::{picking::pointer::PointerInteraction, prelude::*};
+use rubiks_cube::model::check_solvable;
+
+use crate::{
+    cube::{
+        facelet_at, facelet_color,
+        solver::{self, SolveStrategy},
+        AnimationProfileResource, Cube, CubeState, DragToRotateResource, SequenceResource,
+        SetCubeStateEvent,
+    },
+    schedules::CubeScheduleSet,
+};
+
+use super::{
+    cube_net::{CubeNet, NetSticker},
+    interface::{
+        CaptureClick, BUTTON_BACKGROUND_COLOR, BUTTON_BORDER, BUTTON_BORDER_RADIUS,
+        BUTTON_TEXT_COLOR, COLOR_BLUE, COLOR_MAIN, DEFAULT_FONT, DEFAULT_FONT_BOLD,
+    },
+    widget::button::{ButtonDisabledHandler, DisableButtonEvent, EnableButtonEvent, UiButton},
+};
+
+/// The colors of the palette, in the order that clicking a sticker cycles through them
+const PALETTE: [char; 6] = ['U', 'R', 'F', 'D', 'L', 'B'];
+
+pub struct StateEditorPlugin;
+
+impl Plugin for StateEditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(StateEditorResource {
+            is_open: false,
+            color: PALETTE[0],
+        })
+        .add_systems(
+            Update,
+            (
+                edit_button_action,
+                palette_action,
+                paint_net_sticker,
+                paint_cube_sticker,
+                reset_button_action,
+                solve_this_button_action,
+            )
+                .chain()
+                .in_set(CubeScheduleSet::HandleUserInput),
+        )
+        .add_systems(
+            Update,
+            (update_editor_panel, update_editor_status).in_set(CubeScheduleSet::UpdateAnimations),
+        );
+    }
+}
+
+#[derive(Resource)]
+struct StateEditorResource {
+    is_open: bool,
+    /// The facelet of the color that is picked in the palette
+    color: char,
+}
+
+#[derive(Component)]
+struct EditButton;
+#[derive(Component)]
+struct StateEditorPanel;
+#[derive(Component)]
+struct PaletteColor(char);
+#[derive(Component)]
+struct EditorStatusLabel;
+#[derive(Component)]
+struct ResetButton;
+#[derive(Component)]
+struct SolveThisButton;
+
+type SolveThisButtonQuery<'w, 's> = Query<
+    'w,
+    's,
+    (&'static Interaction, &'static ButtonDisabledHandler),
+    (With<SolveThisButton>, Changed<Interaction>),
+>;
+
+/// Spawns the button that opens and closes the editor
+pub fn spawn(parent: &mut ChildBuilder<'_>, asset_server: &Res<AssetServer>) {
+    spawn_button(parent, asset_server, EditButton, "edit");
+}
+
+pub fn spawn_panel(parent: &mut ChildBuilder<'_>, asset_server: &Res<AssetServer>) {
+    parent
+        .spawn((
+            StateEditorPanel,
+            CaptureClick,
+            Node {
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(8.),
+                padding: UiRect::all(Val::Px(12.)),
+                border: BUTTON_BORDER,
+                display: Display::None,
+                ..default()
+            },
+            BorderColor(COLOR_MAIN),
+            BUTTON_BORDER_RADIUS,
+            BackgroundColor(COLOR_BLUE.with_alpha(0.8)),
+        ))
+        .with_children(|parent| {
+            // palette
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(6.),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for facelet in PALETTE {
+                        parent.spawn((
+                            PaletteColor(facelet),
+                            CaptureClick,
+                            Node {
+                                width: Val::Px(24.),
+                                height: Val::Px(24.),
+                                border: BUTTON_BORDER,
+                                ..default()
+                            },
+                            BorderColor(Color::BLACK),
+                            BUTTON_BORDER_RADIUS,
+                            BackgroundColor(facelet_color(facelet).unwrap()),
+                        ));
+                    }
+                });
+
+            parent.spawn(CubeNet::new(48., true));
+
+            parent.spawn((
+                EditorStatusLabel,
+                Text::new(""),
+                TextFont {
+                    font: asset_server.load(DEFAULT_FONT),
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(BUTTON_BACKGROUND_COLOR),
+            ));
+
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(8.),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    spawn_button(parent, asset_server, ResetButton, "reset");
+                    spawn_button(parent, asset_server, SolveThisButton, "solve this");
+                });
+        });
+}
+
+fn spawn_button(
+    parent: &mut ChildBuilder<'_>,
+    asset_server: &Res<AssetServer>,
+    marker: impl Component,
+    label: &str,
+) {
+    parent
+        .spawn((
+            marker,
+            CaptureClick,
+            UiButton,
+            Node {
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                padding: UiRect::axes(Val::Px(16.), Val::Px(8.)),
+                border: BUTTON_BORDER,
+                ..default()
+            },
+            BorderColor(Color::BLACK),
+            BUTTON_BORDER_RADIUS,
+            BackgroundColor(BUTTON_BACKGROUND_COLOR),
+        ))
+        .with_child((
+            Text::new(label),
+            TextFont {
+                font: asset_server.load(DEFAULT_FONT_BOLD),
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(BUTTON_TEXT_COLOR),
+        ));
+}
+
+/// The cube state with one facelet painted. If it already has the color, it gets the next color of the palette.
+fn paint_facelet(cube_state: &CubeState, facelet_index: usize, color: char) -> Option<CubeState> {
+    let mut facelets: Vec<char> = cube_state.to_facelets().chars().collect();
+    let facelet = facelets.get_mut(facelet_index)?;
+
+    *facelet = if *facelet == color {
+        let i = PALETTE.iter().position(|f| f == facelet)?;
+        PALETTE[(i + 1) % PALETTE.len()]
+    } else {
+        color
+    };
+
+    CubeState::parse_facelets(&facelets.into_iter().collect::<String>()).ok()
+}
+
+fn edit_button_action(
+    query: Query<&Interaction, (With<EditButton>, Changed<Interaction>)>,
+    mut editor: ResMut<StateEditorResource>,
+    mut drag_to_rotate: ResMut<DragToRotateResource>,
+) {
+    let Ok(interaction) = query.get_single() else {
+        return;
+    };
+
+    if *interaction != Interaction::Pressed {
+        return;
+    }
+
+    editor.is_open = !editor.is_open;
+    // clicks on the cube paint stickers instead
+    drag_to_rotate.0 = !editor.is_open;
+}
+
+fn palette_action(
+    query: Query<(&Interaction, &PaletteColor), Changed<Interaction>>,
+    mut editor: ResMut<StateEditorResource>,
+) {
+    for (interaction, palette_color) in query.iter() {
+        if *interaction == Interaction::Pressed {
+            editor.color = palette_color.0;
+        }
+    }
+}
+
+fn paint_net_sticker(
+    query: Query<(&Interaction, &NetSticker), Changed<Interaction>>,
+    editor: Res<StateEditorResource>,
+    cube_state_query: Query<&CubeState>,
+    mut set_cube_state_event_writer: EventWriter<SetCubeStateEvent>,
+) {
+    let Ok(cube_state) = cube_state_query.get_single() else {
+        return;
+    };
+
+    for (interaction, sticker) in query.iter() {
+        if *interaction != Interaction::Pressed || !editor.is_open {
+            continue;
+        }
+
+        if let Some(cube_state) = paint_facelet(cube_state, sticker.0, editor.color) {
+            set_cube_state_event_writer.send(SetCubeStateEvent { cube_state });
+        }
+    }
+}
+
+fn paint_cube_sticker(
+    pointers: Query<&PointerInteraction>,
+    cube_query: Query<(&Cube, &CubeState, &GlobalTransform)>,
+    editor: Res<StateEditorResource>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    mut set_cube_state_event_writer: EventWriter<SetCubeStateEvent>,
+) {
+    if !editor.is_open || !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    let Ok((cube, cube_state, cube_transform)) = cube_query.get_single() else {
+        return;
+    };
+
+    for (_, hit_data) in pointers
+        .iter()
+        .filter_map(|interaction| interaction.get_nearest_hit())
+    {
+        // hits on the interface have no position
+        let Some(hit_position) = hit_data.position else {
+            continue;
+        };
+
+        let position = cube_transform
+            .affine()
+            .inverse()
+            .transform_point3(hit_position);
+        let Some(facelet_index) = facelet_at(cube, position) else {
+            continue;
+        };
+
+        if let Some(cube_state) = paint_facelet(cube_state, facelet_index, editor.color) {
+            set_cube_state_event_writer.send(SetCubeStateEvent { cube_state });
+        }
+        return;
+    }
+}
+
+fn reset_button_action(
+    query: Query<&Interaction, (With<ResetButton>, Changed<Interaction>)>,
+    cube_state_query: Query<&CubeState>,
+    mut set_cube_state_event_writer: EventWriter<SetCubeStateEvent>,
+) {
+    let Ok(interaction) = query.get_single() else {
+        return;
+    };
+
+    if *interaction != Interaction::Pressed {
+        return;
+    }
+
+    let Ok(cube_state) = cube_state_query.get_single() else {
+        return;
+    };
+
+    set_cube_state_event_writer.send(SetCubeStateEvent {
+        cube_state: CubeState::new(cube_state.size()),
+    });
+}
+
+fn solve_this_button_action(
+    query: SolveThisButtonQuery,
+    cube_state_query: Query<&CubeState>,
+    mut editor: ResMut<StateEditorResource>,
+    mut drag_to_rotate: ResMut<DragToRotateResource>,
+    mut sequence_resource: ResMut<SequenceResource>,
+    animation_profile: Res<AnimationProfileResource>,
+    mut status_label_query: Query<&mut Text, With<EditorStatusLabel>>,
+) {
+    let Ok((interaction, disabled_handler)) = query.get_single() else {
+        return;
+    };
+
+    if *interaction != Interaction::Pressed || disabled_handler.is_disabled() {
+        return;
+    }
+
+    let Ok(cube_state) = cube_state_query.get_single() else {
+        return;
+    };
+
+    let solve_sequence = solver::get_solve_sequence(SolveStrategy::Kociemba, cube_state);
+    if solve_sequence.is_empty() && !cube_state.is_solved() {
+        if let Ok(mut status_label) = status_label_query.get_single_mut() {
+            status_label.0 = "no solution found for this cube".to_string();
+        }
+        return;
+    }
+
+    sequence_resource.set_animated(solve_sequence, &animation_profile.0.solve, Some(1.0));
+
+    editor.is_open = false;
+    drag_to_rotate.0 = true;
+}
+
+fn update_editor_panel(
+    editor: Res<StateEditorResource>,
+    mut panel_query: Query<&mut Node, With<StateEditorPanel>>,
+    mut palette_query: Query<(&PaletteColor, &mut BorderColor)>,
+) {
+    if !editor.is_changed() {
+        return;
+    }
+
+    let Ok(mut panel) = panel_query.get_single_mut() else {
+        return;
+    };
+
+    panel.display = if editor.is_open {
+        Display::Flex
+    } else {
+        Display::None
+    };
+
+    for (palette_color, mut border_color) in palette_query.iter_mut() {
+        border_color.0 = if palette_color.0 == editor.color {
+            COLOR_MAIN
+        } else {
+            Color::BLACK
+        };
+    }
+}
+
+/// Checks whether the cube can be solved whenever it changes, and only lets it be solved if it can
+fn update_editor_status(
+    editor: Res<StateEditorResource>,
+    cube_state_query: Query<Ref<CubeState>>,
+    mut status_label_query: Query<&mut Text, With<EditorStatusLabel>>,
+    solve_this_button_query: Query<Entity, With<SolveThisButton>>,
+    mut disable_button_event_writer: EventWriter<DisableButtonEvent>,
+    mut enable_button_event_writer: EventWriter<EnableButtonEvent>,
+) {
+    let Ok(cube_state) = cube_state_query.get_single() else {
+        return;
+    };
+
+    if !editor.is_open || (!editor.is_changed() && !cube_state.is_changed()) {
+        return;
+    }
+
+    let (Ok(mut status_label), Ok(solve_this_button)) = (
+        status_label_query.get_single_mut(),
+        solve_this_button_query.get_single(),
+    ) else {
+        return;
+    };
+
+    match check_solvable(&cube_state) {
+        Ok(()) => {
+            status_label.0 = "this cube can be solved".to_string();
+            enable_button_event_writer.send(EnableButtonEvent::new(solve_this_button));
+        }
+        Err(err) => {
+            status_label.0 = format!("this cube can not be solved: {err}");
+            disable_button_event_writer.send(DisableButtonEvent::new(solve_this_button));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rubiks_cube::model::CubeState;
+
+    use super::paint_facelet;
+
+    #[test]
+    fn test_paint_facelet() {
+        let cube_state = CubeState::new(2);
+
+        let painted = paint_facelet(&cube_state, 0, 'F').unwrap();
+        assert_eq!("FUUURRRRFFFFDDDDLLLLBBBB", painted.to_facelets());
+
+        // painting a sticker with the color it has cycles through the colors
+        let painted = paint_facelet(&cube_state, 0, 'U').unwrap();
+        assert_eq!("RUUURRRRFFFFDDDDLLLLBBBB", painted.to_facelets());
+        let painted = paint_facelet(&cube_state, 23, 'B').unwrap();
+        assert_eq!("UUUURRRRFFFFDDDDLLLLBBBU", painted.to_facelets());
+
+        assert!(paint_facelet(&cube_state, 24, 'U').is_none());
+    }
+}
@@ -0,0 +1,175 @@
+use bevy::prelude::*;
+
+use crate::{
+    schedules::CubeScheduleSet,
+    session::{FinalTime, SessionResource},
+    timer::{self, SolveTimerResource},
+};
+
+use super::interface::{
+    CaptureClick, BUTTON_BACKGROUND_COLOR, BUTTON_BORDER, BUTTON_BORDER_RADIUS, BUTTON_TEXT_COLOR,
+    COLOR_BLUE, COLOR_MAIN, DEFAULT_FONT, DEFAULT_FONT_BOLD,
+};
+
+pub struct StatisticsPlugin;
+
+impl Plugin for StatisticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            new_session_button_action.in_set(CubeScheduleSet::HandleUserInput),
+        )
+        .add_systems(
+            Update,
+            update_statistics_panel.in_set(CubeScheduleSet::UpdateAnimations),
+        );
+    }
+}
+
+/// Shows the statistics of the current session. It is only visible when timed solves are on.
+#[derive(Component)]
+struct StatisticsPanel;
+#[derive(Component)]
+struct StatisticsLabel;
+#[derive(Component)]
+struct NewSessionButton;
+
+pub fn spawn(parent: &mut ChildBuilder<'_>, asset_server: &Res<AssetServer>) {
+    parent
+        .spawn((
+            StatisticsPanel,
+            Node {
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(8.),
+                padding: UiRect::all(Val::Px(12.)),
+                border: BUTTON_BORDER,
+                display: Display::None,
+                ..default()
+            },
+            BorderColor(COLOR_MAIN),
+            BUTTON_BORDER_RADIUS,
+            BackgroundColor(COLOR_BLUE.with_alpha(0.8)),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                StatisticsLabel,
+                Text::new(""),
+                TextFont {
+                    font: asset_server.load(DEFAULT_FONT),
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(BUTTON_BACKGROUND_COLOR),
+            ));
+
+            parent
+                .spawn((
+                    NewSessionButton,
+                    CaptureClick,
+                    Node {
+                        justify_content: JustifyContent::Center,
+                        padding: UiRect::axes(Val::Px(16.), Val::Px(8.)),
+                        border: BUTTON_BORDER,
+                        ..default()
+                    },
+                    BorderColor(COLOR_MAIN),
+                    BUTTON_BORDER_RADIUS,
+                    BackgroundColor(BUTTON_BACKGROUND_COLOR),
+                ))
+                .with_child((
+                    Text::new("new session"),
+                    TextFont {
+                        font: asset_server.load(DEFAULT_FONT_BOLD),
+                        font_size: 16.0,
+                        ..default()
+                    },
+                    TextColor(BUTTON_TEXT_COLOR),
+                ));
+        });
+}
+
+fn new_session_button_action(
+    query: Query<&Interaction, (With<NewSessionButton>, Changed<Interaction>)>,
+    mut sessions: ResMut<SessionResource>,
+) {
+    let Ok(interaction) = query.get_single() else {
+        return;
+    };
+
+    if *interaction != Interaction::Pressed {
+        return;
+    }
+
+    sessions.new_session();
+}
+
+fn update_statistics_panel(
+    solve_timer: Res<SolveTimerResource>,
+    sessions: Res<SessionResource>,
+    mut panel_query: Query<&mut Node, With<StatisticsPanel>>,
+    mut label_query: Query<&mut Text, With<StatisticsLabel>>,
+) {
+    let Ok(mut panel_node) = panel_query.get_single_mut() else {
+        error!("expected exactly 1 StatisticsPanel component");
+        return;
+    };
+
+    panel_node.display = if solve_timer.enabled {
+        Display::Flex
+    } else {
+        Display::None
+    };
+
+    if !sessions.is_changed() {
+        return;
+    }
+
+    let Ok(mut label) = label_query.get_single_mut() else {
+        error!("expected exactly 1 StatisticsLabel component");
+        return;
+    };
+
+    let statistics = sessions.current().statistics();
+
+    label.0 = [
+        format!("session {}", sessions.current_number()),
+        format!(
+            "solves: {} (+2: {}, DNF: {})",
+            statistics.solves, statistics.plus_twos, statistics.dnfs
+        ),
+        format!("best: {}", format_statistic(statistics.best)),
+        format!("ao5: {}", format_average(statistics.ao5)),
+        format!("ao12: {}", format_average(statistics.ao12)),
+        format!("ao100: {}", format_average(statistics.ao100)),
+        format!("mean: {}", format_statistic(statistics.mean)),
+        format!(
+            "std dev: {}",
+            format_statistic(statistics.standard_deviation)
+        ),
+        format!("moves: {}", format_number(statistics.mean_moves, 1)),
+        format!("tps: {}", format_number(statistics.tps, 2)),
+    ]
+    .join("\n");
+}
+
+fn format_statistic(seconds: Option<f32>) -> String {
+    match seconds {
+        Some(seconds) => timer::format_time(seconds),
+        None => "-".to_string(),
+    }
+}
+
+fn format_average(average: Option<FinalTime>) -> String {
+    match average {
+        Some(Some(seconds)) => timer::format_time(seconds),
+        Some(None) => "DNF".to_string(),
+        None => "-".to_string(),
+    }
+}
+
+fn format_number(number: Option<f32>, decimals: usize) -> String {
+    match number {
+        Some(number) => format!("{number:.decimals$}"),
+        None => "-".to_string(),
+    }
+}
@@ -0,0 +1,327 @@
+use bevy::prelude::*;
+
+use crate::{
+    cube::{Cube, SequenceResource},
+    schedules::CubeScheduleSet,
+};
+
+use super::{
+    cube_actions::{ScrambleButton, ScrambleButtonProgressBar, SolveButton},
+    interface::{
+        CaptureClick, BUTTON_BACKGROUND_COLOR, BUTTON_BORDER_RADIUS, BUTTON_TEXT_COLOR, COLOR_BLUE,
+        COLOR_GREY, COLOR_MAIN, DEFAULT_FONT_BOLD,
+    },
+    widget::{
+        button::{DisableButtonEvent, EnableButtonEvent},
+        progress_bar::ProgressBar,
+    },
+};
+
+pub struct TimelinePlugin;
+
+impl Plugin for TimelinePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (timeline_token_action, timeline_play_button_action)
+                .in_set(CubeScheduleSet::HandleUserInput),
+        )
+        .add_systems(
+            Update,
+            (spawn_timeline_tokens, highlight_timeline_tokens)
+                .chain()
+                .in_set(CubeScheduleSet::UpdateAnimations),
+        );
+    }
+}
+
+/// Shows the steps of the current sequence in cubing notation.
+#[derive(Component)]
+pub struct Timeline {
+    /// The `SequenceResource` version for which the tokens were spawned
+    spawned_version: Option<usize>,
+}
+
+#[derive(Component)]
+struct TimelineTokensContainer;
+
+/// Clicking the token scrubs the sequence to the given step.
+#[derive(Component)]
+struct TimelineToken(usize);
+
+#[derive(Component)]
+struct TimelinePlayButton;
+#[derive(Component)]
+struct TimelinePlayButtonLabel;
+
+const TOKEN_COLOR_APPLIED: Color = BUTTON_BACKGROUND_COLOR;
+const TOKEN_COLOR_CURRENT: Color = COLOR_MAIN;
+const TOKEN_COLOR_UPCOMING: Color = COLOR_GREY;
+
+pub fn spawn(parent: &mut ChildBuilder<'_>, asset_server: &Res<AssetServer>) {
+    parent
+        .spawn((
+            Timeline {
+                spawned_version: None,
+            },
+            CaptureClick,
+            Node {
+                width: Val::Percent(100.),
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(8.),
+                padding: UiRect::px(12.0, 12.0, 8.0, 8.0),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(COLOR_BLUE.with_alpha(0.8)),
+        ))
+        .with_children(|parent| {
+            // play/pause button
+            parent
+                .spawn((
+                    TimelinePlayButton,
+                    CaptureClick,
+                    Node {
+                        justify_content: JustifyContent::Center,
+                        padding: UiRect::px(12.0, 12.0, 4.0, 4.0),
+                        min_width: Val::Px(64.),
+                        ..default()
+                    },
+                    BUTTON_BORDER_RADIUS,
+                    BackgroundColor(BUTTON_BACKGROUND_COLOR),
+                ))
+                .with_child((
+                    TimelinePlayButtonLabel,
+                    Text::new("pause"),
+                    TextFont {
+                        font: asset_server.load(DEFAULT_FONT_BOLD),
+                        font_size: 14.0,
+                        ..default()
+                    },
+                    TextColor(BUTTON_TEXT_COLOR),
+                ));
+
+            // tokens
+            parent.spawn((
+                TimelineTokensContainer,
+                Node {
+                    flex_wrap: FlexWrap::Wrap,
+                    column_gap: Val::Px(4.),
+                    row_gap: Val::Px(4.),
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn spawn_timeline_token(
+    parent: &mut ChildBuilder<'_>,
+    asset_server: &Res<AssetServer>,
+    label: String,
+    step: usize,
+) {
+    parent
+        .spawn((
+            TimelineToken(step),
+            CaptureClick,
+            Node {
+                padding: UiRect::px(6.0, 6.0, 2.0, 2.0),
+                ..default()
+            },
+            BUTTON_BORDER_RADIUS,
+            BackgroundColor(TOKEN_COLOR_UPCOMING),
+        ))
+        .with_child((
+            Text::new(label),
+            TextFont {
+                font: asset_server.load(DEFAULT_FONT_BOLD),
+                font_size: 14.0,
+                ..default()
+            },
+            TextColor(BUTTON_TEXT_COLOR),
+        ));
+}
+
+fn spawn_timeline_tokens(
+    mut commands: Commands,
+    mut timeline_query: Query<(&mut Timeline, &mut Node)>,
+    tokens_container_query: Query<Entity, With<TimelineTokensContainer>>,
+    cube_query: Query<&Cube>,
+    sequence_resource: Res<SequenceResource>,
+    asset_server: Res<AssetServer>,
+) {
+    let Ok((mut timeline, mut timeline_node)) = timeline_query.get_single_mut() else {
+        error!("expected exactly 1 Timeline component");
+        return;
+    };
+
+    // the tokens can not be scrubbed to anymore once the cube got turned after the sequence
+    if sequence_resource.is_detached() {
+        if timeline_node.display != Display::None {
+            timeline_node.display = Display::None;
+        }
+        return;
+    }
+
+    if timeline.spawned_version == Some(sequence_resource.version()) {
+        return;
+    }
+
+    let Ok(cube) = cube_query.get_single() else {
+        // the cube might be respawning, try again next frame
+        return;
+    };
+
+    let Ok(tokens_container) = tokens_container_query.get_single() else {
+        error!("expected exactly 1 TimelineTokensContainer component");
+        return;
+    };
+
+    timeline.spawned_version = Some(sequence_resource.version());
+
+    commands.entity(tokens_container).despawn_descendants();
+
+    if sequence_resource.steps.is_empty() {
+        timeline_node.display = Display::None;
+        return;
+    }
+
+    timeline_node.display = Display::Flex;
+
+    commands.entity(tokens_container).with_children(|parent| {
+        spawn_timeline_token(parent, &asset_server, "start".to_string(), 0);
+
+        for (i, step) in sequence_resource.steps.iter().enumerate() {
+            // clicking a step goes to the state right after that step is applied
+            spawn_timeline_token(parent, &asset_server, step.to_notation(cube.size()), i + 1);
+        }
+    });
+}
+
+fn highlight_timeline_tokens(
+    mut token_query: Query<(&TimelineToken, &mut BackgroundColor)>,
+    mut play_button_label_query: Query<&mut Text, With<TimelinePlayButtonLabel>>,
+    sequence_resource: Res<SequenceResource>,
+) {
+    for (token, mut background_color) in token_query.iter_mut() {
+        let color = if token.0 == sequence_resource.current_step() {
+            TOKEN_COLOR_CURRENT
+        } else if token.0 < sequence_resource.current_step() {
+            TOKEN_COLOR_APPLIED
+        } else {
+            TOKEN_COLOR_UPCOMING
+        };
+
+        if background_color.0 != color {
+            background_color.0 = color;
+        }
+    }
+
+    let Ok(mut play_button_label) = play_button_label_query.get_single_mut() else {
+        return;
+    };
+
+    let label = if sequence_resource.is_paused() {
+        "play"
+    } else {
+        "pause"
+    };
+
+    if play_button_label.0 != label {
+        play_button_label.0 = label.to_string();
+    }
+}
+
+fn timeline_token_action(
+    token_query: Query<(&TimelineToken, &Interaction), Changed<Interaction>>,
+    mut sequence_resource: ResMut<SequenceResource>,
+    mut scramble_button_progress_bar_query: Query<
+        (&mut ProgressBar, &mut Node),
+        With<ScrambleButtonProgressBar>,
+    >,
+    scramble_button_query: Query<Entity, With<ScrambleButton>>,
+    solve_button_query: Query<Entity, With<SolveButton>>,
+    mut enable_button_event_writer: EventWriter<EnableButtonEvent>,
+) {
+    for (token, interaction) in token_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        sequence_resource.scrub_to(token.0);
+
+        pause_sequence_ui(
+            &mut scramble_button_progress_bar_query,
+            &scramble_button_query,
+            &solve_button_query,
+            &mut enable_button_event_writer,
+        );
+    }
+}
+
+fn timeline_play_button_action(
+    play_button_query: Query<&Interaction, (With<TimelinePlayButton>, Changed<Interaction>)>,
+    mut sequence_resource: ResMut<SequenceResource>,
+    mut scramble_button_progress_bar_query: Query<
+        (&mut ProgressBar, &mut Node),
+        With<ScrambleButtonProgressBar>,
+    >,
+    scramble_button_query: Query<Entity, With<ScrambleButton>>,
+    solve_button_query: Query<Entity, With<SolveButton>>,
+    mut enable_button_event_writer: EventWriter<EnableButtonEvent>,
+    mut disable_button_event_writer: EventWriter<DisableButtonEvent>,
+) {
+    let Ok(interaction) = play_button_query.get_single() else {
+        return;
+    };
+
+    if *interaction != Interaction::Pressed {
+        return;
+    }
+
+    if !sequence_resource.is_paused() {
+        sequence_resource.pause();
+
+        pause_sequence_ui(
+            &mut scramble_button_progress_bar_query,
+            &scramble_button_query,
+            &solve_button_query,
+            &mut enable_button_event_writer,
+        );
+        return;
+    }
+
+    sequence_resource.resume();
+
+    let seconds_until_complete = sequence_resource.seconds_until_complete();
+    if seconds_until_complete == 0.0 {
+        return;
+    }
+
+    disable_button_event_writer.send(DisableButtonEvent {
+        entity: scramble_button_query.single(),
+        enable_after: Some(seconds_until_complete),
+    });
+    disable_button_event_writer.send(DisableButtonEvent {
+        entity: solve_button_query.single(),
+        enable_after: Some(seconds_until_complete),
+    });
+}
+
+/// While a sequence is paused the user is free to start a new scramble or solve.
+fn pause_sequence_ui(
+    scramble_button_progress_bar_query: &mut Query<
+        (&mut ProgressBar, &mut Node),
+        With<ScrambleButtonProgressBar>,
+    >,
+    scramble_button_query: &Query<Entity, With<ScrambleButton>>,
+    solve_button_query: &Query<Entity, With<SolveButton>>,
+    enable_button_event_writer: &mut EventWriter<EnableButtonEvent>,
+) {
+    if let Ok((mut progress_bar, mut node)) = scramble_button_progress_bar_query.get_single_mut() {
+        progress_bar.cancel(&mut node);
+    }
+
+    enable_button_event_writer.send(EnableButtonEvent::new(scramble_button_query.single()));
+    enable_button_event_writer.send(EnableButtonEvent::new(solve_button_query.single()));
+}
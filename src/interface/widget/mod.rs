@@ -1,6 +1,7 @@
 pub mod button;
 pub mod dropdown;
 pub mod progress_bar;
+pub mod text_input;
 
 pub struct WidgetPlugin;
 
@@ -10,6 +11,7 @@ impl bevy::prelude::Plugin for WidgetPlugin {
             button::ButtonPlugin,
             dropdown::DropdownPlugin,
             progress_bar::ProgressBarPlugin,
+            text_input::TextInputPlugin,
         ));
     }
 }
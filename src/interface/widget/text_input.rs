@@ -0,0 +1,162 @@
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+};
+
+use crate::{
+    interface::interface::{
+        CaptureClick, BUTTON_BACKGROUND_COLOR, BUTTON_BORDER, BUTTON_BORDER_RADIUS,
+        BUTTON_TEXT_COLOR, COLOR_MAIN, DEFAULT_FONT,
+    },
+    schedules::CubeScheduleSet,
+};
+
+pub struct TextInputPlugin;
+
+impl Plugin for TextInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                focus_text_inputs,
+                type_in_text_input,
+                update_text_input_label,
+            )
+                .chain()
+                .in_set(CubeScheduleSet::Timers),
+        );
+    }
+}
+
+/// A single line of text that can be edited after clicking on it. While it is focused, the keyboard does not
+/// control anything else, like turning the cube.
+#[derive(Component)]
+#[require(Button)]
+pub struct TextInput {
+    pub value: String,
+    is_focused: bool,
+}
+
+impl TextInput {
+    pub fn is_focused(&self) -> bool {
+        self.is_focused
+    }
+}
+
+#[derive(Component)]
+struct TextInputLabel;
+
+pub fn spawn_text_input(
+    value: String,
+    width: Val,
+    marker: impl Bundle,
+    parent: &mut ChildBuilder<'_>,
+    asset_server: &Res<AssetServer>,
+) {
+    parent
+        .spawn((
+            marker,
+            TextInput {
+                value: value.clone(),
+                is_focused: false,
+            },
+            CaptureClick,
+            Node {
+                width,
+                padding: UiRect::axes(Val::Px(8.), Val::Px(8.)),
+                border: BUTTON_BORDER,
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            BorderColor(Color::BLACK),
+            BUTTON_BORDER_RADIUS,
+            BackgroundColor(BUTTON_BACKGROUND_COLOR),
+        ))
+        .with_child((
+            TextInputLabel,
+            Text::new(value),
+            TextFont {
+                font: asset_server.load(DEFAULT_FONT),
+                font_size: 14.0,
+                ..default()
+            },
+            TextColor(BUTTON_TEXT_COLOR),
+            TextLayout::new_with_no_wrap(),
+        ));
+}
+
+/// Clicking a text input focuses it, clicking anywhere else unfocuses it
+fn focus_text_inputs(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    mut query: Query<(&Interaction, &mut TextInput, &mut BorderColor)>,
+) {
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    for (interaction, mut text_input, mut border_color) in query.iter_mut() {
+        let is_focused = *interaction == Interaction::Pressed;
+        if text_input.is_focused != is_focused {
+            text_input.is_focused = is_focused;
+            border_color.0 = if is_focused { COLOR_MAIN } else { Color::BLACK };
+        }
+    }
+}
+
+fn type_in_text_input(
+    mut keyboard_input_reader: EventReader<KeyboardInput>,
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+    mut query: Query<(&mut TextInput, &mut BorderColor)>,
+) {
+    let Some((mut text_input, mut border_color)) = query
+        .iter_mut()
+        .find(|(text_input, _)| text_input.is_focused)
+    else {
+        keyboard_input_reader.clear();
+        return;
+    };
+
+    for event in keyboard_input_reader.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+
+        match &event.logical_key {
+            Key::Character(characters) => text_input.value.push_str(characters),
+            Key::Space => text_input.value.push(' '),
+            Key::Backspace => {
+                text_input.value.pop();
+            }
+            Key::Enter | Key::Escape => {
+                text_input.is_focused = false;
+                border_color.0 = Color::BLACK;
+            }
+            _ => (),
+        }
+    }
+
+    // keep other systems, like the keymap, from reacting to the typing
+    keyboard_input.reset_all();
+}
+
+fn update_text_input_label(
+    text_input_query: Query<(&TextInput, &Children), Changed<TextInput>>,
+    mut label_query: Query<&mut Text, With<TextInputLabel>>,
+) {
+    for (text_input, children) in text_input_query.iter() {
+        for child in children.iter() {
+            let Ok(mut label) = label_query.get_mut(*child) else {
+                continue;
+            };
+
+            label.0 = if text_input.is_focused {
+                format!("{}|", text_input.value)
+            } else {
+                text_input.value.clone()
+            };
+        }
+    }
+}
@@ -0,0 +1,5 @@
+// The model of a Rubik's cube: its state, rotations, cubing notation, scrambling and solving. It does not depend on
+// Bevy, so that it can be used without a renderer. With the `bevy` feature, which is enabled by default, the cube
+// state can be used as a component and rotations can be sent as events.
+
+pub mod model;
@@ -1,19 +1,29 @@
 use bevy::prelude::*;
 use camera::CameraPlugin;
+use control_server::ControlServerPlugin;
 use controls::ControlsPlugin;
 use cube::CubePlugin;
 use interface::interface::InterfacePlugin;
+use reconstruction::ReconstructionPlugin;
 use schedules::SchedulesPlugin;
+use script::ScriptPlugin;
+use session::SessionPlugin;
+use timer::TimerPlugin;
 
 mod camera;
+mod control_server;
 mod controls;
 #[allow(dead_code)]
 mod cube;
 mod interface;
+mod reconstruction;
 mod schedules;
+mod script;
+mod session;
+mod timer;
 mod utils;
 
-fn main() {
+fn main() -> AppExit {
     App::new()
         .add_plugins((
             DefaultPlugins.set(WindowPlugin {
@@ -25,14 +35,23 @@ fn main() {
             }),
             MeshPickingPlugin,
             CameraPlugin,
+            ControlServerPlugin {
+                port: control_server::port_from_args(std::env::args()),
+            },
             ControlsPlugin,
             CubePlugin,
             InterfacePlugin,
+            ReconstructionPlugin,
             SchedulesPlugin,
+            ScriptPlugin {
+                path: script::path_from_args(std::env::args()),
+            },
+            SessionPlugin,
+            TimerPlugin,
         ))
         .insert_resource(ClearColor(Color::srgb_u8(91, 145, 222)))
         .add_systems(Startup, spawn_light)
-        .run();
+        .run()
 }
 
 fn spawn_light(mut commands: Commands) {
@@ -1,7 +1,7 @@
 // This file contains cubing algorithms. A cubing algorithm is a sequence of moves.
 
 pub mod size_3x3 {
-    use crate::cube::Rotation3x3;
+    use crate::model::Rotation3x3;
 
     // Keep the pieces in the same place, but with 2 edge pieces be flipped.
     // Run this algorithm 2 times to end up in the initial state.
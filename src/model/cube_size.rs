@@ -0,0 +1,23 @@
+/// The largest cube size that the model is used with. Bigger cubes take too much memory and time to be of any use.
+pub const MAX_CUBE_SIZE: usize = 100;
+
+#[derive(Clone, Debug)]
+pub struct CubeSize(pub i32);
+
+impl CubeSize {
+    pub fn lowest_piece_index(&self) -> i32 {
+        if self.0 % 2 == 1 {
+            -(self.0 as i32 - 1) / 2
+        } else {
+            -self.0 as i32 / 2
+        }
+    }
+
+    pub fn highest_piece_index(&self) -> i32 {
+        if self.0 % 2 == 1 {
+            (self.0 as i32 - 1) / 2
+        } else {
+            self.0 as i32 / 2
+        }
+    }
+}
@@ -1,13 +1,17 @@
-use bevy::{log, prelude::*};
-
-use crate::utils::console;
-
-use super::{slice::slice_to_column_index, CubeRotationEvent};
+#[cfg(test)]
+use super::console;
+use super::{
+    cube_size::CubeSize,
+    rotation::{CubeRotation, FaceRotation},
+    slice::slice_to_column_index,
+    CubeRotationEvent,
+};
 
 /// Holds an efficient and precise state of a cube.
 ///
 /// Only the outwards facing faces are stored, resulting in `n^2 * 6` stored elements where `n = cube_size`.
-#[derive(Component)]
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "bevy", derive(bevy::prelude::Component))]
 pub struct CubeState {
     cube_size: usize,
     face_states: FaceStates,
@@ -24,16 +28,31 @@ pub enum Face {
 }
 
 impl Face {
-    #[allow(dead_code)]
-    pub fn as_colored_string(&self) -> String {
-        return format!(
-            "{}{:?}{}",
-            self.to_console_color(),
-            self,
-            console::COLOR_CLEAR
-        );
+    /// The letter of the face in the facelet representation of a cube
+    fn to_facelet(&self) -> char {
+        match self {
+            Face::Left => 'L',
+            Face::Right => 'R',
+            Face::Top => 'U',
+            Face::Bottom => 'D',
+            Face::Front => 'F',
+            Face::Back => 'B',
+        }
+    }
+
+    fn from_facelet(facelet: char) -> Option<Self> {
+        match facelet {
+            'L' => Some(Face::Left),
+            'R' => Some(Face::Right),
+            'U' => Some(Face::Top),
+            'D' => Some(Face::Bottom),
+            'F' => Some(Face::Front),
+            'B' => Some(Face::Back),
+            _ => None,
+        }
     }
 
+    #[cfg(test)]
     fn to_console_color(&self) -> &str {
         match &self {
             Face::Left => console::COLOR_ORANGE,
@@ -48,6 +67,7 @@ impl Face {
 
 /// The indices of a folded out 3x3 cube would be as follows:
 ///
+/// ```text
 ///             0 1 2
 ///             3 4 5                       --> top
 ///             6 7 8
@@ -59,7 +79,8 @@ impl Face {
 ///             0 1 2
 ///             3 4 5                       --> bottom
 ///             6 7 8
-#[derive(Clone)]
+/// ```
+#[derive(Clone, PartialEq)]
 struct FaceStates {
     left: FaceState,
     right: FaceState,
@@ -126,6 +147,70 @@ impl CubeState {
         result
     }
 
+    /// For example, 3 for 3x3
+    pub fn size(&self) -> usize {
+        self.cube_size
+    }
+
+    /// The facelet representation of the cube: the faces in the order top, right, front, bottom, left, back (URFDLB),
+    /// with the stickers of each face in the order of the indices in the documentation of `FaceStates`. Each sticker
+    /// is represented by the letter of the face it belongs to in a solved cube.
+    pub fn to_facelets(&self) -> String {
+        self.face_states
+            .in_facelet_order()
+            .iter()
+            .flat_map(|face_state| face_state.0.iter().map(Face::to_facelet))
+            .collect()
+    }
+
+    /// Creates the cube state from its facelet representation, see `to_facelets`. This does not check whether the
+    /// cube can be solved.
+    pub fn from_facelets(cube_size: usize, facelets: &str) -> Result<Self, String> {
+        let faces_per_side = cube_size * cube_size;
+        let facelets: Vec<char> = facelets.chars().collect();
+
+        if facelets.len() != faces_per_side * 6 {
+            return Err(format!(
+                "expected {} facelets for a {cube_size}x{cube_size} cube, got {}",
+                faces_per_side * 6,
+                facelets.len()
+            ));
+        }
+
+        let mut result = Self::new(cube_size);
+
+        for (face_state, facelets) in result
+            .face_states
+            .in_facelet_order_mut()
+            .into_iter()
+            .zip(facelets.chunks(faces_per_side))
+        {
+            for (i, facelet) in facelets.iter().enumerate() {
+                face_state.0[i] = Face::from_facelet(*facelet)
+                    .ok_or_else(|| format!("invalid facelet '{facelet}'"))?;
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Like `from_facelets`, but the cube size follows from the number of facelets
+    pub fn parse_facelets(facelets: &str) -> Result<Self, String> {
+        let number_of_facelets = facelets.chars().count();
+        let cube_size = (1..)
+            .take_while(|cube_size| cube_size * cube_size * 6 <= number_of_facelets)
+            .last()
+            .unwrap_or(0);
+
+        if cube_size == 0 || cube_size * cube_size * 6 != number_of_facelets {
+            return Err(format!(
+                "{number_of_facelets} facelets do not make up a cube"
+            ));
+        }
+
+        Self::from_facelets(cube_size, facelets)
+    }
+
     pub fn is_solved(&self) -> bool {
         return self.face_states.left.is_solved()
             && self.face_states.right.is_solved()
@@ -135,303 +220,269 @@ impl CubeState {
             && self.face_states.back.is_solved();
     }
 
+    /// The face rotation of all slices on the axis of the cube rotation
+    fn all_slices(&self, cube_rotation: &CubeRotation) -> FaceRotation {
+        let cube_size = CubeSize(self.cube_size as i32);
+        let slices: Vec<i32> = (cube_size.lowest_piece_index()..=cube_size.highest_piece_index())
+            // even sized cubes do not have a slice 0
+            .filter(|slice| self.cube_size % 2 == 1 || *slice != 0)
+            .collect();
+
+        match cube_rotation {
+            CubeRotation::X => FaceRotation::X(slices),
+            CubeRotation::Y => FaceRotation::Y(slices),
+            CubeRotation::Z => FaceRotation::Z(slices),
+        }
+    }
+
     fn can_rotate_slice(&self, slice: &i32) -> bool {
         return slice_to_column_index(*slice, self.cube_size) < self.cube_size as usize;
     }
 
     pub fn handle_rotate_event(&mut self, event: &CubeRotationEvent) {
+        let face_rotation = match &event.rotation {
+            super::rotation::Rotation::Face(face_rotation) => face_rotation.clone(),
+            // turning the whole cube is the same as turning all of its slices
+            super::rotation::Rotation::Cube(cube_rotation) => self.all_slices(cube_rotation),
+        };
+
         let iterations = if event.twice { 2 } else { 1 };
 
         for _ in 0..iterations {
-            match &event.rotation {
-                super::rotation::Rotation::Face(face_rotation) => match face_rotation {
-                    super::rotation::FaceRotation::X(slices) => {
-                        for slice in slices {
-                            if !self.can_rotate_slice(slice) {
-                                log::warn!("Can not rotate slice {} on cube size {}. Skipping this rotation event.", *slice, self.cube_size);
-                                continue;
-                            }
+            match &face_rotation {
+                super::rotation::FaceRotation::X(slices) => {
+                    for slice in slices {
+                        if !self.can_rotate_slice(slice) {
+                            tracing::warn!("Can not rotate slice {} on cube size {}. Skipping this rotation event.", *slice, self.cube_size);
+                            continue;
+                        }
 
-                            let mut new_face_states = self.face_states.clone();
+                        let mut new_face_states = self.face_states.clone();
 
-                            for i in 0..self.cube_size {
-                                let face_index_top = slice_to_column_index(*slice, self.cube_size)
-                                    + i * self.cube_size;
+                        for i in 0..self.cube_size {
+                            let face_index_top =
+                                slice_to_column_index(*slice, self.cube_size) + i * self.cube_size;
 
-                                let face_index_back = self.cube_size
-                                    - slice_to_column_index(*slice, self.cube_size)
-                                    - 1
+                            let face_index_back =
+                                self.cube_size - slice_to_column_index(*slice, self.cube_size) - 1
                                     + (self.cube_size - i - 1) * self.cube_size;
 
-                                let face_index_bottom =
-                                    slice_to_column_index(*slice, self.cube_size)
-                                        + i * self.cube_size;
-
-                                let face_index_front =
-                                    slice_to_column_index(*slice, self.cube_size)
-                                        + i * self.cube_size;
-
-                                if event.negative_direction {
-                                    // front to top
-                                    new_face_states.top.0[face_index_top] =
-                                        self.face_states.front.0[face_index_front].clone();
-
-                                    // bottom to front
-                                    new_face_states.front.0[face_index_front] =
-                                        self.face_states.bottom.0[face_index_bottom].clone();
-
-                                    // back to bottom
-                                    new_face_states.bottom.0[face_index_bottom] =
-                                        self.face_states.back.0[face_index_back].clone();
-
-                                    // top to back
-                                    new_face_states.back.0[face_index_back] =
-                                        self.face_states.top.0[face_index_top].clone();
-                                } else {
-                                    // top to front
-                                    new_face_states.front.0[face_index_front] =
-                                        self.face_states.top.0[face_index_top].clone();
-
-                                    // front to bottom
-                                    new_face_states.bottom.0[face_index_bottom] =
-                                        self.face_states.front.0[face_index_front].clone();
-
-                                    // bottom to back
-                                    new_face_states.back.0[face_index_back] =
-                                        self.face_states.bottom.0[face_index_bottom].clone();
-
-                                    // back to top
-                                    new_face_states.top.0[face_index_top] =
-                                        self.face_states.back.0[face_index_back].clone();
-                                }
-                            }
+                            let face_index_bottom =
+                                slice_to_column_index(*slice, self.cube_size) + i * self.cube_size;
+
+                            let face_index_front =
+                                slice_to_column_index(*slice, self.cube_size) + i * self.cube_size;
 
                             if event.negative_direction {
-                                if has_edge_on_positive_side(slice, self.cube_size) {
-                                    new_face_states.right.rotate_right(self.cube_size);
-                                } else if has_edge_on_negative_side(slice, self.cube_size) {
-                                    new_face_states.left.rotate_left(self.cube_size);
-                                }
+                                // front to top
+                                new_face_states.top.0[face_index_top] =
+                                    self.face_states.front.0[face_index_front].clone();
+
+                                // bottom to front
+                                new_face_states.front.0[face_index_front] =
+                                    self.face_states.bottom.0[face_index_bottom].clone();
+
+                                // back to bottom
+                                new_face_states.bottom.0[face_index_bottom] =
+                                    self.face_states.back.0[face_index_back].clone();
+
+                                // top to back
+                                new_face_states.back.0[face_index_back] =
+                                    self.face_states.top.0[face_index_top].clone();
                             } else {
-                                if has_edge_on_positive_side(slice, self.cube_size) {
-                                    new_face_states.right.rotate_left(self.cube_size);
-                                } else if has_edge_on_negative_side(slice, self.cube_size) {
-                                    new_face_states.left.rotate_right(self.cube_size);
-                                }
+                                // top to front
+                                new_face_states.front.0[face_index_front] =
+                                    self.face_states.top.0[face_index_top].clone();
+
+                                // front to bottom
+                                new_face_states.bottom.0[face_index_bottom] =
+                                    self.face_states.front.0[face_index_front].clone();
+
+                                // bottom to back
+                                new_face_states.back.0[face_index_back] =
+                                    self.face_states.bottom.0[face_index_bottom].clone();
+
+                                // back to top
+                                new_face_states.top.0[face_index_top] =
+                                    self.face_states.back.0[face_index_back].clone();
                             }
+                        }
 
-                            self.face_states = new_face_states;
+                        if event.negative_direction {
+                            if has_edge_on_positive_side(slice, self.cube_size) {
+                                new_face_states.right.rotate_right(self.cube_size);
+                            } else if has_edge_on_negative_side(slice, self.cube_size) {
+                                new_face_states.left.rotate_left(self.cube_size);
+                            }
+                        } else {
+                            if has_edge_on_positive_side(slice, self.cube_size) {
+                                new_face_states.right.rotate_left(self.cube_size);
+                            } else if has_edge_on_negative_side(slice, self.cube_size) {
+                                new_face_states.left.rotate_right(self.cube_size);
+                            }
                         }
+
+                        self.face_states = new_face_states;
                     }
-                    super::rotation::FaceRotation::Y(slices) => {
-                        for slice in slices {
-                            if !self.can_rotate_slice(slice) {
-                                log::warn!("Can not rotate slice {} on cube size {}. Skipping this rotation event.", *slice, self.cube_size);
-                                continue;
-                            }
+                }
+                super::rotation::FaceRotation::Y(slices) => {
+                    for slice in slices {
+                        if !self.can_rotate_slice(slice) {
+                            tracing::warn!("Can not rotate slice {} on cube size {}. Skipping this rotation event.", *slice, self.cube_size);
+                            continue;
+                        }
 
-                            let mut new_face_states = self.face_states.clone();
+                        let mut new_face_states = self.face_states.clone();
 
-                            for i in 0..self.cube_size {
-                                // index is the same for all sides
-                                let face_index = (self.cube_size
-                                    - slice_to_column_index(*slice, self.cube_size)
-                                    - 1)
-                                    * self.cube_size
-                                    + i;
-
-                                if event.negative_direction {
-                                    // front to left
-                                    new_face_states.left.0[face_index] =
-                                        self.face_states.front.0[face_index].clone();
-
-                                    // left to back
-                                    new_face_states.back.0[face_index] =
-                                        self.face_states.left.0[face_index].clone();
-
-                                    // back to right
-                                    new_face_states.right.0[face_index] =
-                                        self.face_states.back.0[face_index].clone();
-
-                                    // right to front
-                                    new_face_states.front.0[face_index] =
-                                        self.face_states.right.0[face_index].clone();
-                                } else {
-                                    // front to right
-                                    new_face_states.right.0[face_index] =
-                                        self.face_states.front.0[face_index].clone();
-
-                                    // right to back
-                                    new_face_states.back.0[face_index] =
-                                        self.face_states.right.0[face_index].clone();
-
-                                    // back to left
-                                    new_face_states.left.0[face_index] =
-                                        self.face_states.back.0[face_index].clone();
-
-                                    // left to front
-                                    new_face_states.front.0[face_index] =
-                                        self.face_states.left.0[face_index].clone();
-                                }
-                            }
+                        for i in 0..self.cube_size {
+                            // index is the same for all sides
+                            let face_index = (self.cube_size
+                                - slice_to_column_index(*slice, self.cube_size)
+                                - 1)
+                                * self.cube_size
+                                + i;
 
                             if event.negative_direction {
-                                if has_edge_on_positive_side(slice, self.cube_size) {
-                                    new_face_states.top.rotate_right(self.cube_size);
-                                } else if has_edge_on_negative_side(slice, self.cube_size) {
-                                    new_face_states.bottom.rotate_left(self.cube_size);
-                                }
+                                // front to left
+                                new_face_states.left.0[face_index] =
+                                    self.face_states.front.0[face_index].clone();
+
+                                // left to back
+                                new_face_states.back.0[face_index] =
+                                    self.face_states.left.0[face_index].clone();
+
+                                // back to right
+                                new_face_states.right.0[face_index] =
+                                    self.face_states.back.0[face_index].clone();
+
+                                // right to front
+                                new_face_states.front.0[face_index] =
+                                    self.face_states.right.0[face_index].clone();
                             } else {
-                                if has_edge_on_positive_side(slice, self.cube_size) {
-                                    new_face_states.top.rotate_left(self.cube_size);
-                                } else if has_edge_on_negative_side(slice, self.cube_size) {
-                                    new_face_states.bottom.rotate_right(self.cube_size);
-                                }
+                                // front to right
+                                new_face_states.right.0[face_index] =
+                                    self.face_states.front.0[face_index].clone();
+
+                                // right to back
+                                new_face_states.back.0[face_index] =
+                                    self.face_states.right.0[face_index].clone();
+
+                                // back to left
+                                new_face_states.left.0[face_index] =
+                                    self.face_states.back.0[face_index].clone();
+
+                                // left to front
+                                new_face_states.front.0[face_index] =
+                                    self.face_states.left.0[face_index].clone();
                             }
+                        }
 
-                            self.face_states = new_face_states;
+                        if event.negative_direction {
+                            if has_edge_on_positive_side(slice, self.cube_size) {
+                                new_face_states.top.rotate_right(self.cube_size);
+                            } else if has_edge_on_negative_side(slice, self.cube_size) {
+                                new_face_states.bottom.rotate_left(self.cube_size);
+                            }
+                        } else {
+                            if has_edge_on_positive_side(slice, self.cube_size) {
+                                new_face_states.top.rotate_left(self.cube_size);
+                            } else if has_edge_on_negative_side(slice, self.cube_size) {
+                                new_face_states.bottom.rotate_right(self.cube_size);
+                            }
                         }
+
+                        self.face_states = new_face_states;
                     }
-                    super::rotation::FaceRotation::Z(slices) => {
-                        for slice in slices {
-                            if !self.can_rotate_slice(slice) {
-                                log::warn!("Can not rotate slice {} on cube size {}. Skipping this rotation event.", *slice, self.cube_size);
-                                continue;
-                            }
+                }
+                super::rotation::FaceRotation::Z(slices) => {
+                    for slice in slices {
+                        if !self.can_rotate_slice(slice) {
+                            tracing::warn!("Can not rotate slice {} on cube size {}. Skipping this rotation event.", *slice, self.cube_size);
+                            continue;
+                        }
 
-                            let mut new_face_states = self.face_states.clone();
+                        let mut new_face_states = self.face_states.clone();
 
-                            for i in 0..self.cube_size {
-                                let face_index_top = slice_to_column_index(*slice, self.cube_size)
-                                    * self.cube_size
-                                    + i;
+                        for i in 0..self.cube_size {
+                            let face_index_top =
+                                slice_to_column_index(*slice, self.cube_size) * self.cube_size + i;
 
-                                let face_index_right = self.cube_size
-                                    - slice_to_column_index(*slice, self.cube_size)
-                                    - 1
+                            let face_index_right =
+                                self.cube_size - slice_to_column_index(*slice, self.cube_size) - 1
                                     + i * self.cube_size;
 
-                                let face_index_bottom = invert_face_index_x(
-                                    (self.cube_size
-                                        - slice_to_column_index(*slice, self.cube_size)
-                                        - 1)
-                                        * self.cube_size
-                                        + i,
-                                    self.cube_size,
-                                );
-
-                                let face_index_left = invert_face_index_y(
-                                    slice_to_column_index(*slice, self.cube_size)
-                                        + i * self.cube_size,
-                                    self.cube_size,
-                                );
-
-                                if event.negative_direction {
-                                    // top to right
-                                    new_face_states.right.0[face_index_right] =
-                                        self.face_states.top.0[face_index_top].clone();
-
-                                    // right to bottom
-                                    new_face_states.bottom.0[face_index_bottom] =
-                                        self.face_states.right.0[face_index_right].clone();
-
-                                    // bottom to left
-                                    new_face_states.left.0[face_index_left] =
-                                        self.face_states.bottom.0[face_index_bottom].clone();
-
-                                    // left to top
-                                    new_face_states.top.0[face_index_top] =
-                                        self.face_states.left.0[face_index_left].clone();
-                                } else {
-                                    // top to left
-                                    new_face_states.left.0[face_index_left] =
-                                        self.face_states.top.0[face_index_top].clone();
-
-                                    // left to bottom
-                                    new_face_states.bottom.0[face_index_bottom] =
-                                        self.face_states.left.0[face_index_left].clone();
-
-                                    // bottom to right
-                                    new_face_states.right.0[face_index_right] =
-                                        self.face_states.bottom.0[face_index_bottom].clone();
-
-                                    // right to top
-                                    new_face_states.top.0[face_index_top] =
-                                        self.face_states.right.0[face_index_right].clone();
-                                }
-                            }
+                            let face_index_bottom = invert_face_index_x(
+                                (self.cube_size
+                                    - slice_to_column_index(*slice, self.cube_size)
+                                    - 1)
+                                    * self.cube_size
+                                    + i,
+                                self.cube_size,
+                            );
+
+                            let face_index_left = invert_face_index_y(
+                                slice_to_column_index(*slice, self.cube_size) + i * self.cube_size,
+                                self.cube_size,
+                            );
 
                             if event.negative_direction {
-                                if has_edge_on_positive_side(slice, self.cube_size) {
-                                    new_face_states.front.rotate_right(self.cube_size);
-                                } else if has_edge_on_negative_side(slice, self.cube_size) {
-                                    new_face_states.back.rotate_left(self.cube_size);
-                                }
-                            } else {
-                                if has_edge_on_positive_side(slice, self.cube_size) {
-                                    new_face_states.front.rotate_left(self.cube_size);
-                                } else if has_edge_on_negative_side(slice, self.cube_size) {
-                                    new_face_states.back.rotate_right(self.cube_size);
-                                }
-                            }
+                                // top to right
+                                new_face_states.right.0[face_index_right] =
+                                    self.face_states.top.0[face_index_top].clone();
 
-                            self.face_states = new_face_states;
-                        }
-                    }
-                },
-                super::rotation::Rotation::Cube(cube_rotation) => {
-                    let mut new_face_states = self.face_states.clone();
+                                // right to bottom
+                                new_face_states.bottom.0[face_index_bottom] =
+                                    self.face_states.right.0[face_index_right].clone();
 
-                    match cube_rotation {
-                        super::rotation::CubeRotation::X => {
-                            if event.negative_direction {
-                                new_face_states.top = self.face_states.front.clone();
-                                new_face_states.bottom = self.face_states.back.clone();
-                                new_face_states.front = self.face_states.bottom.clone();
-                                new_face_states.back = self.face_states.top.clone();
+                                // bottom to left
+                                new_face_states.left.0[face_index_left] =
+                                    self.face_states.bottom.0[face_index_bottom].clone();
+
+                                // left to top
+                                new_face_states.top.0[face_index_top] =
+                                    self.face_states.left.0[face_index_left].clone();
                             } else {
-                                new_face_states.top = self.face_states.back.clone();
-                                new_face_states.bottom = self.face_states.front.clone();
-                                new_face_states.front = self.face_states.top.clone();
-                                new_face_states.back = self.face_states.bottom.clone();
+                                // top to left
+                                new_face_states.left.0[face_index_left] =
+                                    self.face_states.top.0[face_index_top].clone();
+
+                                // left to bottom
+                                new_face_states.bottom.0[face_index_bottom] =
+                                    self.face_states.left.0[face_index_left].clone();
+
+                                // bottom to right
+                                new_face_states.right.0[face_index_right] =
+                                    self.face_states.bottom.0[face_index_bottom].clone();
+
+                                // right to top
+                                new_face_states.top.0[face_index_top] =
+                                    self.face_states.right.0[face_index_right].clone();
                             }
                         }
-                        super::rotation::CubeRotation::Y => {
-                            if event.negative_direction {
-                                new_face_states.front = self.face_states.left.clone();
-                                new_face_states.back = self.face_states.right.clone();
-                                new_face_states.left = self.face_states.back.clone();
-                                new_face_states.right = self.face_states.front.clone();
-                            } else {
-                                new_face_states.front = self.face_states.right.clone();
-                                new_face_states.back = self.face_states.left.clone();
-                                new_face_states.left = self.face_states.front.clone();
-                                new_face_states.right = self.face_states.back.clone();
+
+                        if event.negative_direction {
+                            if has_edge_on_positive_side(slice, self.cube_size) {
+                                new_face_states.front.rotate_right(self.cube_size);
+                            } else if has_edge_on_negative_side(slice, self.cube_size) {
+                                new_face_states.back.rotate_left(self.cube_size);
                             }
-                        }
-                        super::rotation::CubeRotation::Z => {
-                            if event.negative_direction {
-                                new_face_states.top = self.face_states.left.clone();
-                                new_face_states.bottom = self.face_states.right.clone();
-                                new_face_states.left = self.face_states.bottom.clone();
-                                new_face_states.right = self.face_states.top.clone();
-                            } else {
-                                new_face_states.top = self.face_states.right.clone();
-                                new_face_states.bottom = self.face_states.left.clone();
-                                new_face_states.left = self.face_states.top.clone();
-                                new_face_states.right = self.face_states.bottom.clone();
+                        } else {
+                            if has_edge_on_positive_side(slice, self.cube_size) {
+                                new_face_states.front.rotate_left(self.cube_size);
+                            } else if has_edge_on_negative_side(slice, self.cube_size) {
+                                new_face_states.back.rotate_right(self.cube_size);
                             }
                         }
-                    }
 
-                    self.face_states = new_face_states;
+                        self.face_states = new_face_states;
+                    }
                 }
             }
         }
     }
 
     /// Prints the current state in an unfolded format for debugging. The formatting does not work well for cubes 4x4 cubes or bigger.
-    #[allow(dead_code)]
+    #[cfg(test)]
     fn print_indices(&self) {
         // top
         for y in 0..self.cube_size {
@@ -508,6 +559,28 @@ impl CubeState {
 }
 
 impl FaceStates {
+    fn in_facelet_order(&self) -> [&FaceState; 6] {
+        [
+            &self.top,
+            &self.right,
+            &self.front,
+            &self.bottom,
+            &self.left,
+            &self.back,
+        ]
+    }
+
+    fn in_facelet_order_mut(&mut self) -> [&mut FaceState; 6] {
+        [
+            &mut self.top,
+            &mut self.right,
+            &mut self.front,
+            &mut self.bottom,
+            &mut self.left,
+            &mut self.back,
+        ]
+    }
+
     pub fn new(cube_size: usize) -> Self {
         let faces_per_side = cube_size * cube_size;
 
@@ -551,14 +624,14 @@ fn invert_face_index_y(face_index: usize, cube_size: usize) -> usize {
 
 #[cfg(test)]
 mod tests {
-    use crate::cube::{
+    use crate::model::{
         algorithms, create_scramble_sequence_from_algorithm,
         cube_state::{
             has_edge_on_negative_side, has_edge_on_positive_side, invert_face_index_x,
             invert_face_index_y, Face,
         },
         rotation::{CubeRotation, FaceRotation, Rotation},
-        CubeRotationEvent,
+        Algorithm, CubeRotationEvent,
     };
 
     use super::{CubeState, FaceState};
@@ -786,6 +859,54 @@ mod tests {
         test_4_cube_rotations(4, CubeRotation::Z, false);
     }
 
+    #[test]
+    fn test_cube_rotations_turn_all_slices() {
+        // a whole cube rotation must turn the stickers exactly like turning every slice in the same direction
+        let assert_same_state = |cube_size: usize, cube_rotations: &str, slice_rotations: &str| {
+            let apply = |notation: &str| {
+                let mut cube_state = CubeState::new(cube_size);
+                // scramble first, so that the rotations are not hidden by a uniformly colored face
+                for rotation_event in Algorithm::from_notation("R U' F2 L D B'", cube_size)
+                    .unwrap()
+                    .moves
+                    .iter()
+                    .chain(&Algorithm::from_notation(notation, cube_size).unwrap().moves)
+                {
+                    cube_state.handle_rotate_event(rotation_event);
+                }
+                cube_state
+            };
+
+            assert!(
+                apply(cube_rotations) == apply(slice_rotations),
+                "{cube_rotations} on a {cube_size}x{cube_size}"
+            );
+        };
+
+        assert_same_state(3, "x", "R M' L'");
+        assert_same_state(3, "y", "U E' D'");
+        assert_same_state(3, "z", "F S B'");
+        assert_same_state(3, "x' y2 z", "R' M L y2 F S B'");
+        assert_same_state(2, "x", "R L'");
+        assert_same_state(2, "y'", "U' D");
+    }
+
+    #[test]
+    fn test_cube_rotation_y_direction() {
+        // y turns the whole cube like U, so the front face ends up at the left side
+        let mut cube_state = CubeState::new(2);
+        for rotation_event in Algorithm::from_notation("y", 2).unwrap().moves.iter() {
+            cube_state.handle_rotate_event(rotation_event);
+        }
+        assert_eq!("UUUUBBBBRRRRDDDDFFFFLLLL", cube_state.to_facelets());
+
+        let mut cube_state = CubeState::new(2);
+        for rotation_event in Algorithm::from_notation("y'", 2).unwrap().moves.iter() {
+            cube_state.handle_rotate_event(rotation_event);
+        }
+        assert_eq!("UUUUFFFFLLLLDDDDBBBBRRRR", cube_state.to_facelets());
+    }
+
     #[test]
     fn test_has_edge_on_positive_side() {
         // 3x3
@@ -867,4 +988,30 @@ mod tests {
         assert_eq!(5, invert_face_index_y(9, 4));
         assert_eq!(1, invert_face_index_y(13, 4));
     }
+
+    #[test]
+    fn test_facelets_round_trip() {
+        let mut cube_state = CubeState::new(2);
+        assert_eq!("UUUURRRRFFFFDDDDLLLLBBBB", cube_state.to_facelets());
+
+        cube_state.handle_rotate_event(&CubeRotationEvent {
+            rotation: Rotation::face_x(1),
+            negative_direction: true,
+            twice: false,
+            animation: None,
+        });
+        let facelets = cube_state.to_facelets();
+        assert_ne!("UUUURRRRFFFFDDDDLLLLBBBB", facelets);
+
+        let loaded_cube_state = CubeState::from_facelets(2, &facelets).unwrap();
+        assert_eq!(facelets, loaded_cube_state.to_facelets());
+
+        assert_eq!(
+            facelets,
+            CubeState::parse_facelets(&facelets).unwrap().to_facelets()
+        );
+        assert!(CubeState::parse_facelets(&facelets[1..]).is_err());
+        assert!(CubeState::from_facelets(3, &facelets).is_err());
+        assert!(CubeState::from_facelets(2, &facelets.replace('U', "X")).is_err());
+    }
 }
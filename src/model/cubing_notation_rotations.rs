@@ -97,7 +97,7 @@ impl Into<CubeRotationEvent> for CubeRotation {
             },
             CubeRotation::Y => CubeRotationEvent {
                 rotation: Rotation::cube_y(),
-                negative_direction: false,
+                negative_direction: true,
                 twice: false,
                 animation: None,
             },
@@ -109,7 +109,7 @@ impl Into<CubeRotationEvent> for CubeRotation {
             },
             CubeRotation::YPrime => CubeRotationEvent {
                 rotation: Rotation::cube_y(),
-                negative_direction: true,
+                negative_direction: false,
                 twice: false,
                 animation: None,
             },
@@ -0,0 +1,46 @@
+mod axis;
+pub use axis::Axis;
+
+#[cfg(test)]
+mod console;
+
+mod cube_size;
+pub use cube_size::CubeSize;
+pub use cube_size::MAX_CUBE_SIZE;
+
+mod cube_state;
+pub use cube_state::CubeState;
+
+mod cubing_notation_rotations;
+pub use cubing_notation_rotations::*;
+
+mod notation;
+pub use notation::algorithm_from_notation;
+pub use notation::algorithm_to_notation;
+pub use notation::Algorithm;
+
+pub mod rotation;
+pub use rotation::rotations_conflict;
+pub use rotation::CubeRotationEvent;
+pub use rotation::EaseFunction;
+pub use rotation::FaceRotation;
+pub use rotation::Rotation;
+pub use rotation::RotationAnimation;
+
+mod scramble;
+pub use scramble::create_random_scramble_sequence;
+pub use scramble::create_scramble_sequence_from_algorithm;
+pub use scramble::create_seeded_scramble_sequence;
+pub use scramble::scramble_length;
+
+#[cfg(feature = "serde")]
+mod serialization;
+
+pub mod slice;
+
+mod solvability;
+pub use solvability::check_solvable;
+
+pub mod solver;
+
+pub mod algorithms;
@@ -0,0 +1,518 @@
+// This file converts rotation events to cubing notation so they can be displayed to the user.
+//
+// Outer layers use the regular face letters (R, L, U, D, F, B). Inner layers use the layer number
+// counted from the nearest face (e.g. `2R`), except for the middle slice of odd sized cubes, which
+// uses M, E and S. Multiple slices that start at an outer layer are written as wide moves (e.g. `Rw`, `3Rw`).
+
+use super::{
+    axis::Axis,
+    cube_size::CubeSize,
+    rotation::{CubeRotation, FaceRotation, Rotation},
+    slice::column_index_to_slice,
+    CubeRotationEvent,
+};
+
+/// The letters to use for the slices of an axis, together with the value of `negative_direction`
+/// that matches the regular (clockwise) direction of that letter.
+struct AxisNotation {
+    positive_side: (&'static str, bool),
+    negative_side: (&'static str, bool),
+    middle: (&'static str, bool),
+}
+
+const AXIS_NOTATION_X: AxisNotation = AxisNotation {
+    positive_side: ("R", true),
+    negative_side: ("L", false),
+    middle: ("M", false),
+};
+
+const AXIS_NOTATION_Y: AxisNotation = AxisNotation {
+    positive_side: ("U", true),
+    negative_side: ("D", false),
+    middle: ("E", false),
+};
+
+const AXIS_NOTATION_Z: AxisNotation = AxisNotation {
+    positive_side: ("F", true),
+    negative_side: ("B", false),
+    middle: ("S", true),
+};
+
+impl CubeRotationEvent {
+    /// Parse cubing notation, for example `R'`, `2U2`, `3Rw`, `r` or `x`. This is the inverse of `to_notation`.
+    /// The returned event is not animated.
+    pub fn from_notation(notation: &str, cube_size: &CubeSize) -> Result<Self, String> {
+        let mut base = notation.trim();
+        let mut twice = false;
+        let mut prime = false;
+
+        // the suffixes can be in either order, for example `R2'` and `R'2`
+        for _ in 0..2 {
+            if let Some(rest) = base.strip_suffix('\'').filter(|_| !prime) {
+                prime = true;
+                base = rest;
+            } else if let Some(rest) = base.strip_suffix('2').filter(|_| !twice) {
+                twice = true;
+                base = rest;
+            }
+        }
+
+        let digits_len = base.chars().take_while(|c| c.is_ascii_digit()).count();
+        let (digits, rest) = base.split_at(digits_len);
+        let layer: Option<i32> = match digits {
+            "" => None,
+            digits => Some(
+                digits
+                    .parse()
+                    .map_err(|_| format!("invalid layer in \"{notation}\""))?,
+            ),
+        };
+
+        let mut chars = rest.chars();
+        let Some(letter) = chars.next() else {
+            return Err(format!("missing move in \"{notation}\""));
+        };
+        let is_wide = match chars.as_str() {
+            "" => letter.is_ascii_lowercase(),
+            "w" => true,
+            _ => return Err(format!("unexpected characters in \"{notation}\"")),
+        };
+
+        let (rotation, clockwise_direction) = match letter {
+            'x' | 'y' | 'z' => {
+                if layer.is_some() || chars.as_str() == "w" {
+                    return Err(format!(
+                        "cube rotations can not have layers: \"{notation}\""
+                    ));
+                }
+
+                let cube_rotation = match letter {
+                    'x' => CubeRotation::X,
+                    'y' => CubeRotation::Y,
+                    _ => CubeRotation::Z,
+                };
+                (Rotation::Cube(cube_rotation), true)
+            }
+            'M' | 'E' | 'S' => {
+                if layer.is_some() || is_wide {
+                    return Err(format!("middle slices can not have layers: \"{notation}\""));
+                }
+                if cube_size.0 % 2 == 0 {
+                    return Err(format!(
+                        "even sized cubes do not have a middle slice: \"{notation}\""
+                    ));
+                }
+
+                let (axis, axis_notation) = match letter {
+                    'M' => (Axis::X, &AXIS_NOTATION_X),
+                    'E' => (Axis::Y, &AXIS_NOTATION_Y),
+                    _ => (Axis::Z, &AXIS_NOTATION_Z),
+                };
+                (face_rotation(axis, vec![0]), axis_notation.middle.1)
+            }
+            _ => {
+                let Some((axis, (_, clockwise_direction), is_positive_side)) =
+                    face_letter(letter.to_ascii_uppercase())
+                else {
+                    return Err(format!("unknown move \"{notation}\""));
+                };
+
+                let layers = if is_wide {
+                    1..=layer.unwrap_or(2)
+                } else {
+                    let layer = layer.unwrap_or(1);
+                    layer..=layer
+                };
+
+                if *layers.start() < 1 || *layers.end() > cube_size.0 {
+                    return Err(format!(
+                        "\"{notation}\" does not exist on a {0}x{0} cube",
+                        cube_size.0
+                    ));
+                }
+
+                let slices = layers
+                    .map(|layer| {
+                        let column_index = if is_positive_side {
+                            cube_size.0 - layer
+                        } else {
+                            layer - 1
+                        };
+                        column_index_to_slice(column_index, cube_size.0 as usize)
+                    })
+                    .collect();
+
+                (face_rotation(axis, slices), clockwise_direction)
+            }
+        };
+
+        Ok(Self {
+            rotation,
+            negative_direction: if prime {
+                !clockwise_direction
+            } else {
+                clockwise_direction
+            },
+            twice,
+            animation: None,
+        })
+    }
+
+    /// Get the cubing notation of this event, for example `R'` or `2U2`.
+    pub fn to_notation(&self, cube_size: &CubeSize) -> String {
+        let (base, clockwise_direction) = match &self.rotation {
+            Rotation::Face(face_rotation) => {
+                let (axis_notation, slices) = match face_rotation {
+                    FaceRotation::X(slices) => (&AXIS_NOTATION_X, slices),
+                    FaceRotation::Y(slices) => (&AXIS_NOTATION_Y, slices),
+                    FaceRotation::Z(slices) => (&AXIS_NOTATION_Z, slices),
+                };
+
+                face_rotation_notation(axis_notation, slices, cube_size)
+            }
+            Rotation::Cube(cube_rotation) => match cube_rotation {
+                CubeRotation::X => ("x".to_string(), true),
+                CubeRotation::Y => ("y".to_string(), true),
+                CubeRotation::Z => ("z".to_string(), true),
+            },
+        };
+
+        if self.twice {
+            format!("{base}2")
+        } else if self.negative_direction != clockwise_direction {
+            format!("{base}'")
+        } else {
+            base
+        }
+    }
+}
+
+/// Parse a sequence of moves that are separated by whitespace, for example `R U R' U'`
+pub fn algorithm_from_notation(
+    algorithm: &str,
+    cube_size: &CubeSize,
+) -> Result<Vec<CubeRotationEvent>, String> {
+    algorithm
+        .split_whitespace()
+        .map(|notation| CubeRotationEvent::from_notation(notation, cube_size))
+        .collect()
+}
+
+/// The notation of a sequence of moves, separated by spaces. This is the inverse of `algorithm_from_notation`.
+pub fn algorithm_to_notation(
+    rotation_events: &[CubeRotationEvent],
+    cube_size: &CubeSize,
+) -> String {
+    rotation_events
+        .iter()
+        .map(|rotation_event| rotation_event.to_notation(cube_size))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A sequence of moves for a cube of the given size. The size is needed to write the moves in cubing notation.
+#[derive(Clone, Debug)]
+pub struct Algorithm {
+    pub cube_size: usize,
+    pub moves: Vec<CubeRotationEvent>,
+}
+
+impl Algorithm {
+    pub fn from_notation(algorithm: &str, cube_size: usize) -> Result<Self, String> {
+        Ok(Self {
+            cube_size,
+            moves: algorithm_from_notation(algorithm, &CubeSize(cube_size as i32))?,
+        })
+    }
+
+    pub fn to_notation(&self) -> String {
+        algorithm_to_notation(&self.moves, &CubeSize(self.cube_size as i32))
+    }
+}
+
+/// Returns the axis, the letter with its clockwise direction, and whether the letter is on the positive side of the axis
+fn face_letter(letter: char) -> Option<(Axis, (&'static str, bool), bool)> {
+    match letter {
+        'R' => Some((Axis::X, AXIS_NOTATION_X.positive_side, true)),
+        'L' => Some((Axis::X, AXIS_NOTATION_X.negative_side, false)),
+        'U' => Some((Axis::Y, AXIS_NOTATION_Y.positive_side, true)),
+        'D' => Some((Axis::Y, AXIS_NOTATION_Y.negative_side, false)),
+        'F' => Some((Axis::Z, AXIS_NOTATION_Z.positive_side, true)),
+        'B' => Some((Axis::Z, AXIS_NOTATION_Z.negative_side, false)),
+        _ => None,
+    }
+}
+
+fn face_rotation(axis: Axis, slices: Vec<i32>) -> Rotation {
+    match axis {
+        Axis::X => Rotation::Face(FaceRotation::X(slices)),
+        Axis::Y => Rotation::Face(FaceRotation::Y(slices)),
+        Axis::Z => Rotation::Face(FaceRotation::Z(slices)),
+    }
+}
+
+fn face_rotation_notation(
+    axis_notation: &AxisNotation,
+    slices: &[i32],
+    cube_size: &CubeSize,
+) -> (String, bool) {
+    let mut sorted_slices = slices.to_vec();
+    sorted_slices.sort();
+    sorted_slices.dedup();
+
+    if sorted_slices.len() == 1 {
+        return single_slice_notation(axis_notation, sorted_slices[0], cube_size);
+    }
+
+    if let Some(notation) = wide_notation(axis_notation, &sorted_slices, cube_size) {
+        return notation;
+    }
+
+    // Not a move that has a common notation, so we list the individual slices. They all share
+    // the same direction, so we use the direction of the first slice.
+    let mut clockwise_direction = false;
+    let labels: Vec<String> = sorted_slices
+        .iter()
+        .enumerate()
+        .map(|(i, slice)| {
+            let (label, direction) = single_slice_notation(axis_notation, *slice, cube_size);
+            if i == 0 {
+                clockwise_direction = direction;
+            }
+            label
+        })
+        .collect();
+
+    (format!("({})", labels.join(" ")), clockwise_direction)
+}
+
+fn single_slice_notation(
+    axis_notation: &AxisNotation,
+    slice: i32,
+    cube_size: &CubeSize,
+) -> (String, bool) {
+    if slice == 0 {
+        let (label, direction) = axis_notation.middle;
+        return (label.to_string(), direction);
+    }
+
+    let (label, direction, layer) = if slice > 0 {
+        let (label, direction) = axis_notation.positive_side;
+        (
+            label,
+            direction,
+            cube_size.highest_piece_index() - slice + 1,
+        )
+    } else {
+        let (label, direction) = axis_notation.negative_side;
+        (label, direction, slice - cube_size.lowest_piece_index() + 1)
+    };
+
+    if layer == 1 {
+        (label.to_string(), direction)
+    } else {
+        (format!("{layer}{label}"), direction)
+    }
+}
+
+/// Wide moves are all slices from an outer layer up to an inner layer, on the same side of the cube.
+fn wide_notation(
+    axis_notation: &AxisNotation,
+    sorted_slices: &[i32],
+    cube_size: &CubeSize,
+) -> Option<(String, bool)> {
+    let depth = sorted_slices.len() as i32;
+    let is_contiguous = sorted_slices.windows(2).all(|pair| {
+        pair[1] - pair[0] == 1 || (pair[0] == -1 && pair[1] == 1 && cube_size.0 % 2 == 0)
+    });
+
+    if !is_contiguous {
+        return None;
+    }
+
+    let (label, direction) = if *sorted_slices.last().unwrap() == cube_size.highest_piece_index() {
+        axis_notation.positive_side
+    } else if *sorted_slices.first().unwrap() == cube_size.lowest_piece_index() {
+        axis_notation.negative_side
+    } else {
+        return None;
+    };
+
+    if depth == 2 {
+        Some((format!("{label}w"), direction))
+    } else {
+        Some((format!("{depth}{label}w"), direction))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{
+        cube_size::CubeSize,
+        rotation::{FaceRotation, Rotation},
+        CubeRotationEvent, Rotation3x3,
+    };
+
+    use super::{algorithm_from_notation, algorithm_to_notation};
+
+    fn event(rotation: Rotation, negative_direction: bool, twice: bool) -> CubeRotationEvent {
+        CubeRotationEvent {
+            rotation,
+            negative_direction,
+            twice,
+            animation: None,
+        }
+    }
+
+    #[test]
+    fn test_to_notation_3x3() {
+        let cube_size = CubeSize(3);
+
+        let to_notation = |rotation: Rotation3x3| {
+            let event: CubeRotationEvent = rotation.into();
+            event.to_notation(&cube_size)
+        };
+
+        assert_eq!("R", to_notation(Rotation3x3::R));
+        assert_eq!("R'", to_notation(Rotation3x3::RPrime));
+        assert_eq!("R2", to_notation(Rotation3x3::R2));
+        assert_eq!("L", to_notation(Rotation3x3::L));
+        assert_eq!("L'", to_notation(Rotation3x3::LPrime));
+        assert_eq!("U", to_notation(Rotation3x3::U));
+        assert_eq!("D'", to_notation(Rotation3x3::DPrime));
+        assert_eq!("F", to_notation(Rotation3x3::F));
+        assert_eq!("B2", to_notation(Rotation3x3::B2));
+
+        assert_eq!(
+            "M",
+            event(Rotation::face_x(0), false, false).to_notation(&cube_size)
+        );
+        assert_eq!(
+            "E'",
+            event(Rotation::face_y(0), true, false).to_notation(&cube_size)
+        );
+        assert_eq!(
+            "S",
+            event(Rotation::face_z(0), true, false).to_notation(&cube_size)
+        );
+        assert_eq!(
+            "y'",
+            event(Rotation::cube_y(), false, false).to_notation(&cube_size)
+        );
+    }
+
+    #[test]
+    fn test_to_notation_inner_and_wide_slices() {
+        let cube_size = CubeSize(5);
+
+        assert_eq!(
+            "2R",
+            event(Rotation::face_x(1), true, false).to_notation(&cube_size)
+        );
+        assert_eq!(
+            "2L'",
+            event(Rotation::face_x(-1), true, false).to_notation(&cube_size)
+        );
+        assert_eq!(
+            "Uw",
+            event(Rotation::Face(FaceRotation::Y(vec![2, 1])), true, false).to_notation(&cube_size)
+        );
+        assert_eq!(
+            "3Bw2",
+            event(
+                Rotation::Face(FaceRotation::Z(vec![-2, -1, 0])),
+                false,
+                true
+            )
+            .to_notation(&cube_size)
+        );
+
+        let cube_size = CubeSize(4);
+
+        assert_eq!(
+            "2F",
+            event(Rotation::face_z(1), true, false).to_notation(&cube_size)
+        );
+        assert_eq!(
+            "3Rw",
+            event(Rotation::Face(FaceRotation::X(vec![-1, 1, 2])), true, false)
+                .to_notation(&cube_size)
+        );
+    }
+
+    #[test]
+    fn test_from_notation_round_trip() {
+        let cases = [
+            (
+                3,
+                vec![
+                    "R", "L'", "U2", "D", "F'", "B2", "M", "E'", "S2", "x", "y'", "z2",
+                ],
+            ),
+            (4, vec!["2R", "2L'", "Rw", "3Uw2", "4Fw'", "Bw"]),
+            (5, vec!["2R'", "M", "Lw", "3Dw'"]),
+            (7, vec!["3R", "3Lw'", "4Bw"]),
+        ];
+
+        for (cube_size, notations) in cases {
+            let cube_size = CubeSize(cube_size);
+            for notation in notations {
+                let event = CubeRotationEvent::from_notation(notation, &cube_size).unwrap();
+                assert_eq!(notation, event.to_notation(&cube_size));
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_notation_variants() {
+        let cube_size = CubeSize(3);
+        let to_notation = |notation: &str| {
+            CubeRotationEvent::from_notation(notation, &cube_size)
+                .unwrap()
+                .to_notation(&cube_size)
+        };
+
+        // lowercase face letters are wide moves
+        assert_eq!("Rw", to_notation("r"));
+        assert_eq!("Uw'", to_notation("u'"));
+        assert_eq!("R2", to_notation("R2'"));
+        assert_eq!("R2", to_notation(" R'2 "));
+        assert_eq!("R", to_notation("1R"));
+
+        // the middle layer of odd cubes is always written as the middle slice
+        let cube_size = CubeSize(5);
+        assert_eq!(
+            "M'",
+            CubeRotationEvent::from_notation("3R", &cube_size)
+                .unwrap()
+                .to_notation(&cube_size)
+        );
+    }
+
+    #[test]
+    fn test_algorithm_notation() {
+        let cube_size = CubeSize(3);
+
+        let algorithm = algorithm_from_notation(" R U  R' U'\n", &cube_size).unwrap();
+        assert_eq!(4, algorithm.len());
+        assert_eq!("R U R' U'", algorithm_to_notation(&algorithm, &cube_size));
+
+        assert!(algorithm_from_notation("", &cube_size).unwrap().is_empty());
+        assert_eq!(
+            Err("unexpected characters in \"Rq\"".to_string()),
+            algorithm_from_notation("R Rq U", &cube_size).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_from_notation_errors() {
+        let cube_size = CubeSize(4);
+
+        for notation in ["", "'", "Q", "R3", "Rx", "5R", "0R", "M", "2x", "R''"] {
+            assert!(
+                CubeRotationEvent::from_notation(notation, &cube_size).is_err(),
+                "expected \"{notation}\" to be invalid"
+            );
+        }
+    }
+}
@@ -0,0 +1,308 @@
+use rand::Rng;
+
+use super::{axis::Axis, cube_size::CubeSize};
+
+/// How the speed of a rotation animation changes over time. These are the easing functions of Bevy that are used by
+/// the game, so that the model does not depend on Bevy.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EaseFunction {
+    Linear,
+    QuadraticOut,
+    CubicOut,
+    SineInOut,
+    BackOut,
+}
+
+#[cfg(feature = "bevy")]
+impl From<EaseFunction> for bevy::math::curve::EaseFunction {
+    fn from(ease_function: EaseFunction) -> Self {
+        match ease_function {
+            EaseFunction::Linear => Self::Linear,
+            EaseFunction::QuadraticOut => Self::QuadraticOut,
+            EaseFunction::CubicOut => Self::CubicOut,
+            EaseFunction::SineInOut => Self::SineInOut,
+            EaseFunction::BackOut => Self::BackOut,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RotationAnimation {
+    pub duration_in_seconds: f32,
+    pub ease_function: Option<EaseFunction>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "bevy", derive(bevy::prelude::Event))]
+pub struct CubeRotationEvent {
+    pub rotation: Rotation,
+    pub negative_direction: bool,
+    pub twice: bool,
+    pub animation: Option<RotationAnimation>,
+}
+
+impl CubeRotationEvent {
+    /// Two events can be animated concurrently when they rotate different slices of the same axis.
+    pub fn can_animate_concurrently(&self, other: &CubeRotationEvent) -> bool {
+        !rotations_conflict(&self.rotation, &other.rotation)
+    }
+
+    pub fn random_face_rotation(cube_size: &CubeSize) -> Self {
+        Self::random_face_rotation_with_rng(cube_size, &mut rand::thread_rng())
+    }
+
+    /// Like `random_face_rotation`, but with the given random number generator so that the result can be reproduced
+    pub fn random_face_rotation_with_rng(cube_size: &CubeSize, rng: &mut impl Rng) -> Self {
+        let face_rotation = FaceRotation::random_with_rng(cube_size, rng);
+
+        let direction = if rng.gen_range(0..=1) == 0 {
+            true
+        } else {
+            false
+        };
+
+        CubeRotationEvent {
+            rotation: Rotation::Face(face_rotation),
+            negative_direction: direction,
+            twice: false,
+            animation: None,
+        }
+    }
+
+    /// check wether the given event negates (undoes) self
+    pub fn negates(&self, event: &Self) -> bool {
+        if self.rotation != event.rotation {
+            return false;
+        }
+
+        if self.twice != event.twice {
+            return false;
+        }
+
+        if self.negative_direction != event.negative_direction {
+            return true;
+        }
+
+        return false;
+    }
+
+    pub fn equals(&self, comparison: &Self) -> bool {
+        return self.rotation == comparison.rotation
+            && self.negative_direction == comparison.negative_direction
+            && self.twice == comparison.twice;
+    }
+
+    /// Get the event that undoes (negates) self
+    pub fn inverted(&self) -> Self {
+        let mut result = self.clone();
+
+        if !self.twice {
+            result.negative_direction = !self.negative_direction;
+        }
+
+        result
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Rotation {
+    Face(FaceRotation),
+    Cube(CubeRotation),
+}
+
+impl Rotation {
+    pub fn face_x(slice: i32) -> Self {
+        Self::Face(FaceRotation::x(slice))
+    }
+    pub fn face_y(slice: i32) -> Self {
+        Self::Face(FaceRotation::y(slice))
+    }
+    pub fn face_z(slice: i32) -> Self {
+        Self::Face(FaceRotation::z(slice))
+    }
+    pub fn face(axis: Axis, slice: i32) -> Self {
+        match axis {
+            Axis::X => Self::face_x(slice),
+            Axis::Y => Self::face_y(slice),
+            Axis::Z => Self::face_z(slice),
+        }
+    }
+    pub fn cube_x() -> Self {
+        Self::Cube(CubeRotation::X)
+    }
+    pub fn cube_y() -> Self {
+        Self::Cube(CubeRotation::Y)
+    }
+    pub fn cube_z() -> Self {
+        Self::Cube(CubeRotation::Z)
+    }
+    pub fn cube(axis: Axis) -> Self {
+        match axis {
+            Axis::X => Self::cube_x(),
+            Axis::Y => Self::cube_y(),
+            Axis::Z => Self::cube_z(),
+        }
+    }
+}
+
+/// Rotate the given faces (e.g. slices) of the cube on a given axis. This is relative to the current cube rotation.
+/// For even sized cubes (2x2, 4x4) there is no slice 0.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FaceRotation {
+    /// Rotate the given slices on the x axis.
+    /// For a rotation in the default direction, when looking at the front of the cube, the front row ends up at the bottom.
+    /// The positive slice indices are on the right of the cube and the negative slice indices are at the left of the cube.
+    X(Vec<i32>),
+    /// Rotate the given slices on the y axis.
+    /// For a rotation in the default direction, when looking at the front of the cube, the front row ends up at the right side.
+    /// The positive slice indices are on the top of the cube and the negative slice indices are at the bottom of the cube.
+    Y(Vec<i32>),
+    /// Rotate the given slices on the z axis.
+    /// For a rotation in the default direction, when looking at the front of the cube, the top row ends up at the left side.
+    /// The positive slice indices are on the front of the cube and the negative slice indices are on the back of the cube.
+    Z(Vec<i32>),
+}
+
+impl FaceRotation {
+    pub fn random(cube_size: &CubeSize) -> Self {
+        Self::random_with_rng(cube_size, &mut rand::thread_rng())
+    }
+
+    pub fn random_with_rng(cube_size: &CubeSize, rng: &mut impl Rng) -> Self {
+        let slice = if cube_size.0 % 2 == 1 {
+            rng.gen_range(cube_size.lowest_piece_index()..=cube_size.highest_piece_index())
+        } else {
+            let mut result = if cube_size.0 == 2 {
+                1
+            } else {
+                rng.gen_range(1..=(cube_size.0 / 2))
+            };
+            let negative = rng.gen_range(0..=1);
+
+            if negative == 0 {
+                result *= -1
+            }
+
+            result
+        };
+        let axis = rng.gen_range(0..=2);
+
+        if axis == 0 {
+            Self::X(vec![slice])
+        } else if axis == 1 {
+            Self::Y(vec![slice])
+        } else {
+            Self::Z(vec![slice])
+        }
+    }
+
+    pub fn x(slice: i32) -> Self {
+        Self::X(vec![slice])
+    }
+    pub fn y(slice: i32) -> Self {
+        Self::Y(vec![slice])
+    }
+    pub fn z(slice: i32) -> Self {
+        Self::Z(vec![slice])
+    }
+}
+
+/// Rotate the whole cube on a given axis. This also changes which faces gets rotated for FaceRotation events.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CubeRotation {
+    /// Move the whole cube on the x axis.
+    /// For the default direction, when looking at the front of the cube, the front face ends up at the bottom.
+    X,
+    /// Move the whole cube on the y axis.
+    /// For the default direction, when looking at the front of the cube, the front face ends up at the right side.
+    Y,
+    /// Move the whole cube on the z axis.
+    /// For the default direction, when looking at the front of the cube, the top row ends up at the left side.
+    Z,
+}
+
+impl Into<Axis> for &CubeRotation {
+    fn into(self) -> Axis {
+        match self {
+            CubeRotation::X => Axis::X,
+            CubeRotation::Y => Axis::Y,
+            CubeRotation::Z => Axis::Z,
+        }
+    }
+}
+
+/// Two rotations conflict when they share pieces. Cube rotations share pieces with every other rotation.
+pub fn rotations_conflict(a: &Rotation, b: &Rotation) -> bool {
+    let (Rotation::Face(a), Rotation::Face(b)) = (a, b) else {
+        return true;
+    };
+
+    match (a, b) {
+        (FaceRotation::X(a_slices), FaceRotation::X(b_slices))
+        | (FaceRotation::Y(a_slices), FaceRotation::Y(b_slices))
+        | (FaceRotation::Z(a_slices), FaceRotation::Z(b_slices)) => {
+            a_slices.iter().any(|slice| b_slices.contains(slice))
+        }
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CubeRotationEvent, Rotation};
+
+    #[test]
+    fn cube_rotation_event_negates() {
+        let rotation_event = CubeRotationEvent {
+            rotation: Rotation::face_x(-1),
+            negative_direction: true,
+            twice: false,
+            animation: None,
+        };
+
+        assert_eq!(rotation_event.negates(&rotation_event), false);
+
+        let negating_event = CubeRotationEvent {
+            rotation: Rotation::face_x(-1),
+            negative_direction: false,
+            twice: false,
+            animation: None,
+        };
+
+        assert!(negating_event.negates(&rotation_event));
+
+        // it does not negate because its on a different axis
+        let non_negating_event = CubeRotationEvent {
+            rotation: Rotation::face_y(-1),
+            negative_direction: false,
+            twice: false,
+            animation: None,
+        };
+
+        assert_eq!(non_negating_event.negates(&rotation_event), false);
+    }
+
+    #[test]
+    fn cube_rotation_event_inverted() {
+        let rotation_event = CubeRotationEvent {
+            rotation: Rotation::face_z(1),
+            negative_direction: true,
+            twice: false,
+            animation: None,
+        };
+
+        let inverted_event = rotation_event.inverted();
+        assert!(inverted_event.negates(&rotation_event));
+        assert!(!inverted_event.negative_direction);
+
+        // rotating twice undoes itself
+        let twice_event = CubeRotationEvent {
+            rotation: Rotation::face_z(1),
+            negative_direction: false,
+            twice: true,
+            animation: None,
+        };
+
+        assert!(twice_event.inverted().equals(&twice_event));
+    }
+}
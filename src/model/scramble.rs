@@ -1,4 +1,11 @@
-use super::{cube::CubeSize, CubeRotationEvent};
+use rand::{rngs::StdRng, SeedableRng};
+
+use super::{cube_size::CubeSize, CubeRotationEvent};
+
+/// The number of turns of a scramble that mixes up a cube of the given size well enough
+pub fn scramble_length(cube_size: &CubeSize) -> usize {
+    (cube_size.0 + 1) as usize * 6
+}
 
 #[allow(dead_code)]
 pub fn create_scramble_sequence_from_algorithm<T>(algorithm: Vec<T>) -> Vec<CubeRotationEvent>
@@ -22,6 +29,19 @@ pub fn create_random_scramble_sequence(
     )
 }
 
+/// Creates the same scramble every time for the same seed
+pub fn create_seeded_scramble_sequence(
+    cube_size: &CubeSize,
+    number_of_rotations: usize,
+    seed: u64,
+) -> Vec<CubeRotationEvent> {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    create_scramble_sequence_with_strategy(cube_size, number_of_rotations, &mut |cube_size| {
+        CubeRotationEvent::random_face_rotation_with_rng(cube_size, &mut rng)
+    })
+}
+
 fn create_scramble_sequence_with_strategy(
     cube_size: &CubeSize,
     number_of_rotations: usize,
@@ -56,11 +76,12 @@ fn create_scramble_sequence_with_strategy(
 
 #[cfg(test)]
 mod tests {
-    use crate::cube::{
-        create_random_scramble_sequence, cube::CubeSize, rotation::Rotation, CubeRotationEvent,
+    use crate::model::{
+        algorithm_to_notation, create_random_scramble_sequence, cube_size::CubeSize,
+        rotation::Rotation, CubeRotationEvent,
     };
 
-    use super::create_scramble_sequence_with_strategy;
+    use super::{create_scramble_sequence_with_strategy, create_seeded_scramble_sequence};
 
     struct FakeRandomEventProvider {
         events: Vec<CubeRotationEvent>,
@@ -160,4 +181,26 @@ mod tests {
         assert!(sequence[1].equals(&event_provider.events[1]));
         assert!(sequence[2].equals(&event_provider.events[3]));
     }
+
+    #[test]
+    fn test_seeded_scramble_sequence() {
+        let cube_size = CubeSize(4);
+        let scramble = create_seeded_scramble_sequence(&cube_size, 30, 42);
+
+        assert_eq!(30, scramble.len());
+        assert_eq!(
+            algorithm_to_notation(&scramble, &cube_size),
+            algorithm_to_notation(
+                &create_seeded_scramble_sequence(&cube_size, 30, 42),
+                &cube_size
+            )
+        );
+        assert_ne!(
+            algorithm_to_notation(&scramble, &cube_size),
+            algorithm_to_notation(
+                &create_seeded_scramble_sequence(&cube_size, 30, 43),
+                &cube_size
+            )
+        );
+    }
 }
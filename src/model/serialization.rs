@@ -0,0 +1,129 @@
+// Serde support for the model, enabled by the `serde` feature. Cube states are written as their facelet string (see
+// `CubeState::to_facelets`) and algorithms as cubing notation, so that saved data stays readable and does not depend
+// on how the model is stored in memory. Single moves have no cube size to write them as notation, so they are only
+// serialized as part of an algorithm.
+
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{Algorithm, CubeState, MAX_CUBE_SIZE};
+
+impl Serialize for CubeState {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_facelets())
+    }
+}
+
+impl<'de> Deserialize<'de> for CubeState {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let facelets = String::deserialize(deserializer)?;
+        let cube_state = CubeState::parse_facelets(&facelets).map_err(D::Error::custom)?;
+
+        if cube_state.size() > MAX_CUBE_SIZE {
+            return Err(D::Error::custom(format!(
+                "the cube size can be at most {MAX_CUBE_SIZE}"
+            )));
+        }
+
+        Ok(cube_state)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct AlgorithmRepresentation {
+    cube_size: usize,
+    moves: String,
+}
+
+impl Serialize for Algorithm {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        AlgorithmRepresentation {
+            cube_size: self.cube_size,
+            moves: self.to_notation(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Algorithm {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let representation = AlgorithmRepresentation::deserialize(deserializer)?;
+
+        if representation.cube_size == 0 || representation.cube_size > MAX_CUBE_SIZE {
+            return Err(D::Error::custom(format!(
+                "cube_size must be between 1 and {MAX_CUBE_SIZE}"
+            )));
+        }
+
+        Algorithm::from_notation(&representation.moves, representation.cube_size)
+            .map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{
+        create_seeded_scramble_sequence, scramble_length, Algorithm, CubeSize, CubeState,
+        MAX_CUBE_SIZE,
+    };
+
+    #[test]
+    fn test_round_trip_for_every_cube_size() {
+        for cube_size in 1..=10 {
+            let moves = create_seeded_scramble_sequence(
+                &CubeSize(cube_size as i32),
+                scramble_length(&CubeSize(cube_size as i32)),
+                cube_size as u64,
+            );
+            let mut cube_state = CubeState::new(cube_size);
+            for rotation_event in &moves {
+                cube_state.handle_rotate_event(rotation_event);
+            }
+
+            let json = serde_json::to_string(&cube_state).unwrap();
+            assert_eq!(format!("\"{}\"", cube_state.to_facelets()), json);
+            let loaded_cube_state: CubeState = serde_json::from_str(&json).unwrap();
+            assert!(cube_state == loaded_cube_state, "cube size {cube_size}");
+
+            let algorithm = Algorithm { cube_size, moves };
+            let json = serde_json::to_string(&algorithm).unwrap();
+            let loaded_algorithm: Algorithm = serde_json::from_str(&json).unwrap();
+            assert_eq!(algorithm.to_notation(), loaded_algorithm.to_notation());
+
+            let mut loaded_cube_state = CubeState::new(cube_size);
+            for (rotation_event, loaded_rotation_event) in
+                algorithm.moves.iter().zip(&loaded_algorithm.moves)
+            {
+                assert!(rotation_event.equals(loaded_rotation_event));
+                loaded_cube_state.handle_rotate_event(loaded_rotation_event);
+            }
+            assert_eq!(algorithm.moves.len(), loaded_algorithm.moves.len());
+            assert!(cube_state == loaded_cube_state, "cube size {cube_size}");
+        }
+    }
+
+    #[test]
+    fn test_human_readable_representations() {
+        let algorithm = Algorithm::from_notation("R U2 R'", 3).unwrap();
+        assert_eq!(
+            r#"{"cube_size":3,"moves":"R U2 R'"}"#,
+            serde_json::to_string(&algorithm).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert!(serde_json::from_str::<CubeState>(r#""UUUURRRR""#).is_err());
+        assert!(serde_json::from_str::<Algorithm>(r#"{"cube_size":3,"moves":"Q"}"#).is_err());
+        assert!(serde_json::from_str::<Algorithm>(r#"{"cube_size":0,"moves":""}"#).is_err());
+        assert!(serde_json::from_str::<Algorithm>(&format!(
+            r#"{{"cube_size":{},"moves":""}}"#,
+            MAX_CUBE_SIZE + 1
+        ))
+        .is_err());
+
+        let too_big = CubeState::new(MAX_CUBE_SIZE + 1).to_facelets();
+        assert!(serde_json::from_str::<CubeState>(&format!(r#""{too_big}""#)).is_err());
+        let biggest = CubeState::new(MAX_CUBE_SIZE).to_facelets();
+        assert!(serde_json::from_str::<CubeState>(&format!(r#""{biggest}""#)).is_ok());
+    }
+}
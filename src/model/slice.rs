@@ -34,9 +34,21 @@ pub fn column_index_to_slice(column_index: i32, cube_size: usize) -> i32 {
     }
 }
 
+/// Get the slice at the given position along an axis of the cube. The position is relative to the center of
+/// the cube and `face_size` is the size of a face of the cube, in the same unit.
+pub fn position_to_slice(position: f32, face_size: f32, cube_size: usize) -> i32 {
+    let piece_size = face_size / cube_size as f32;
+    let column_index = ((position + face_size / 2.0) / piece_size).floor() as i32;
+
+    // positions on the very edge of the cube will result in the index being 1 too high or too low.
+    let column_index = column_index.clamp(0, cube_size as i32 - 1);
+
+    column_index_to_slice(column_index, cube_size)
+}
+
 #[cfg(test)]
 mod test {
-    use crate::cube::slice::{column_index_to_slice, slice_to_column_index};
+    use crate::model::slice::{column_index_to_slice, position_to_slice, slice_to_column_index};
 
     #[test]
     fn test_slice_to_column_index() {
@@ -65,4 +77,24 @@ mod test {
         assert_eq!(1, column_index_to_slice(2, 4));
         assert_eq!(2, column_index_to_slice(3, 4));
     }
+
+    #[test]
+    fn test_position_to_slice() {
+        // 3x3
+        assert_eq!(-1, position_to_slice(-1.2, 3.0, 3));
+        assert_eq!(0, position_to_slice(-0.4, 3.0, 3));
+        assert_eq!(0, position_to_slice(0.4, 3.0, 3));
+        assert_eq!(1, position_to_slice(0.6, 3.0, 3));
+
+        // 4x4
+        assert_eq!(-2, position_to_slice(-1.0, 3.0, 4));
+        assert_eq!(-1, position_to_slice(-0.1, 3.0, 4));
+        assert_eq!(1, position_to_slice(0.1, 3.0, 4));
+        assert_eq!(2, position_to_slice(1.0, 3.0, 4));
+
+        // the edges of the cube
+        assert_eq!(-2, position_to_slice(-1.5, 3.0, 4));
+        assert_eq!(2, position_to_slice(1.5, 3.0, 4));
+        assert_eq!(2, position_to_slice(1.50001, 3.0, 4));
+    }
 }
@@ -0,0 +1,483 @@
+// Checks whether a cube state can be reached by turning a solved cube. States that are entered sticker by sticker,
+// for example from a physical cube, can describe a cube that no sequence of moves solves: a sticker might have been
+// entered wrong, or the physical cube might have been taken apart and put together with a twisted corner.
+//
+// The checks follow the pieces that can be told apart on every cube size: the corners, and on odd sized cubes the
+// middle edges and the centers. The other edges and centers of bigger cubes are only checked by counting colors.
+
+use std::collections::BTreeMap;
+
+use super::{algorithm_from_notation, CubeSize, CubeState};
+
+/// The facelets in the order of `CubeState::to_facelets`, with the axis and the direction of their face
+const FACES: [(char, usize, i32); 6] = [
+    ('U', 1, 1),
+    ('R', 0, 1),
+    ('F', 2, 1),
+    ('D', 1, -1),
+    ('L', 0, -1),
+    ('B', 2, -1),
+];
+
+/// Rotations that bring each of the 24 orientations of a cube back to the orientation of a solved cube
+const ORIENTATIONS: [&str; 6] = ["", "x", "x2", "x'", "z", "z'"];
+const TURNS_AROUND_Y: [&str; 4] = ["", "y", "y2", "y'"];
+
+/// Checks that the cube state can be solved, and tells what is wrong if it can not
+pub fn check_solvable(cube_state: &CubeState) -> Result<(), String> {
+    let cube_size = cube_state.size();
+
+    let facelets = cube_state.to_facelets();
+    for (facelet, _, _) in FACES {
+        let count = facelets.chars().filter(|f| *f == facelet).count();
+        if count != cube_size * cube_size {
+            return Err(format!(
+                "there are {count} {} stickers instead of {}",
+                color_name(facelet),
+                cube_size * cube_size
+            ));
+        }
+    }
+
+    check_corners(&pieces(cube_state))?;
+
+    // The twist, flip and parity of the pieces are only kept by turns when the cube is held the same way
+    let cube_state = orient(cube_state)?;
+    let pieces = pieces(&cube_state);
+
+    let corners: Vec<&Piece> = pieces.iter().filter(|piece| piece.is_corner()).collect();
+    let twist: usize = corners.iter().map(|corner| corner.twist()).sum();
+    if !twist.is_multiple_of(3) {
+        return Err("a corner is twisted".to_string());
+    }
+
+    if cube_size < 3 || cube_size.is_multiple_of(2) {
+        return Ok(());
+    }
+
+    let edges: Vec<&Piece> = pieces
+        .iter()
+        .filter(|piece| piece.is_middle_edge())
+        .collect();
+    for edge in &edges {
+        let [first, second] = [edge.stickers[0].color, edge.stickers[1].color];
+        if first.axis == second.axis {
+            return Err(format!(
+                "an edge has a {} and a {} sticker",
+                color_name(first.facelet),
+                color_name(second.facelet)
+            ));
+        }
+    }
+    check_unique_pieces(&edges, "edges")?;
+
+    let flip: usize = edges.iter().map(|edge| edge.flip()).sum();
+    if !flip.is_multiple_of(2) {
+        return Err("an edge is flipped".to_string());
+    }
+
+    if permutation_is_odd(&corners) != permutation_is_odd(&edges) {
+        return Err("two pieces are swapped".to_string());
+    }
+
+    Ok(())
+}
+
+fn color_name(facelet: char) -> &'static str {
+    match facelet {
+        'U' => "white",
+        'R' => "red",
+        'F' => "green",
+        'D' => "yellow",
+        'L' => "orange",
+        'B' => "blue",
+        _ => "unknown",
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Color {
+    facelet: char,
+    /// The axis of the face this color belongs to on a solved cube
+    axis: usize,
+    /// The direction of the face this color belongs to on a solved cube
+    direction: i32,
+}
+
+impl Color {
+    fn new(facelet: char) -> Self {
+        let (_, axis, direction) = FACES
+            .iter()
+            .find(|(face, _, _)| *face == facelet)
+            .copied()
+            .unwrap();
+
+        Self {
+            facelet,
+            axis,
+            direction,
+        }
+    }
+}
+
+struct Sticker {
+    /// The axis of the face the sticker is on
+    axis: usize,
+    color: Color,
+}
+
+/// The stickers of a piece. Positions are doubled, so that they are whole numbers on every cube size: they go from
+/// -(n - 1) to n - 1 in steps of 2.
+struct Piece {
+    position: [i32; 3],
+    stickers: Vec<Sticker>,
+    outer: i32,
+}
+
+impl Piece {
+    fn is_corner(&self) -> bool {
+        self.stickers.len() == 3
+    }
+
+    fn is_middle_edge(&self) -> bool {
+        self.stickers.len() == 2 && self.position.contains(&0)
+    }
+
+    /// The position where the piece is on a solved cube
+    fn solved_position(&self) -> [i32; 3] {
+        let mut position = [0; 3];
+        for sticker in &self.stickers {
+            position[sticker.color.axis] = sticker.color.direction * self.outer;
+        }
+        position
+    }
+
+    fn color_names(&self) -> String {
+        let names: Vec<&str> = self
+            .stickers
+            .iter()
+            .map(|sticker| color_name(sticker.color.facelet))
+            .collect();
+        names.join(", ")
+    }
+
+    /// How often the corner is turned clockwise, from where its white or yellow sticker is on the white or yellow
+    /// face
+    fn twist(&self) -> usize {
+        let clockwise_axes = if self.position.iter().product::<i32>() > 0 {
+            [1, 0, 2]
+        } else {
+            [1, 2, 0]
+        };
+
+        clockwise_axes
+            .iter()
+            .position(|axis| {
+                self.stickers
+                    .iter()
+                    .any(|sticker| sticker.axis == *axis && sticker.color.axis == 1)
+            })
+            .unwrap()
+    }
+
+    /// Whether the edge is flipped. The white or yellow sticker of an edge, or else its green or blue sticker, should
+    /// be on the white or yellow face if the edge is next to it, or else on the green or blue face.
+    fn flip(&self) -> usize {
+        let reference_axis = |axes: [usize; 2]| if axes.contains(&1) { 1 } else { 2 };
+        let [first, second] = [&self.stickers[0], &self.stickers[1]];
+        let position_axis = reference_axis([first.axis, second.axis]);
+        let color_axis = reference_axis([first.color.axis, second.color.axis]);
+
+        let sticker = self
+            .stickers
+            .iter()
+            .find(|sticker| sticker.axis == position_axis)
+            .unwrap();
+        (sticker.color.axis != color_axis) as usize
+    }
+}
+
+fn pieces(cube_state: &CubeState) -> Vec<Piece> {
+    let cube_size = cube_state.size();
+    let outer = cube_size as i32 - 1;
+    let mut pieces: BTreeMap<[i32; 3], Vec<Sticker>> = BTreeMap::new();
+
+    for (i, facelet) in cube_state.to_facelets().chars().enumerate() {
+        let (_, axis, direction) = FACES[i / (cube_size * cube_size)];
+        let row = 2 * ((i / cube_size) % cube_size) as i32 - outer;
+        let column = 2 * (i % cube_size) as i32 - outer;
+
+        // see the layout in the documentation of `FaceStates`
+        let position = match FACES[i / (cube_size * cube_size)].0 {
+            'U' => [column, outer, row],
+            'R' => [outer, -row, -column],
+            'F' => [column, -row, outer],
+            'D' => [column, -outer, -row],
+            'L' => [-outer, -row, column],
+            _ => [-column, -row, -outer],
+        };
+        debug_assert_eq!(direction * outer, position[axis]);
+
+        pieces.entry(position).or_default().push(Sticker {
+            axis,
+            color: Color::new(facelet),
+        });
+    }
+
+    pieces
+        .into_iter()
+        .map(|(position, mut stickers)| {
+            stickers.sort_by_key(|sticker| sticker.axis);
+            Piece {
+                position,
+                stickers,
+                outer,
+            }
+        })
+        .collect()
+}
+
+fn check_corners(pieces: &[Piece]) -> Result<(), String> {
+    let corners: Vec<&Piece> = pieces.iter().filter(|piece| piece.is_corner()).collect();
+
+    for corner in &corners {
+        // The colors of a corner go around it in a fixed order. Turning the corner keeps the order, while swapping
+        // two stickers mirrors it.
+        let mut axes: Vec<usize> = corner
+            .stickers
+            .iter()
+            .map(|sticker| sticker.color.axis)
+            .collect();
+        axes.sort();
+        axes.dedup();
+        if axes.len() != 3 {
+            return Err(format!(
+                "a corner has the colors {}, which do not belong together",
+                corner.color_names()
+            ));
+        }
+
+        let mut sign = corner
+            .stickers
+            .iter()
+            .map(|sticker| corner.position[sticker.axis].signum() * sticker.color.direction)
+            .product::<i32>();
+        let color_axes: Vec<usize> = corner
+            .stickers
+            .iter()
+            .map(|sticker| sticker.color.axis)
+            .collect();
+        for i in 0..3 {
+            for j in i + 1..3 {
+                if color_axes[i] > color_axes[j] {
+                    sign = -sign;
+                }
+            }
+        }
+
+        if sign < 0 {
+            return Err(format!(
+                "the corner with the colors {} is mirrored",
+                corner.color_names()
+            ));
+        }
+    }
+
+    check_unique_pieces(&corners, "corners")
+}
+
+fn check_unique_pieces(pieces: &[&Piece], kind: &str) -> Result<(), String> {
+    let mut solved_positions: Vec<[i32; 3]> = vec![];
+    for piece in pieces {
+        let solved_position = piece.solved_position();
+        if solved_positions.contains(&solved_position) {
+            return Err(format!(
+                "there are two {kind} with the colors {}",
+                piece.color_names()
+            ));
+        }
+        solved_positions.push(solved_position);
+    }
+    Ok(())
+}
+
+/// Turns the whole cube, so that it is held like a solved cube: with the white center on top and the green center
+/// in front, or on even sized cubes, with the yellow, orange and blue corner at the bottom left in the back.
+fn orient(cube_state: &CubeState) -> Result<CubeState, String> {
+    let cube_size = CubeSize(cube_state.size() as i32);
+
+    for orientation in ORIENTATIONS {
+        for turn_around_y in TURNS_AROUND_Y {
+            let mut oriented_cube_state = cube_state.clone();
+            let notation = format!("{orientation} {turn_around_y}");
+            for rotation_event in algorithm_from_notation(&notation, &cube_size)? {
+                oriented_cube_state.handle_rotate_event(&rotation_event);
+            }
+
+            if is_oriented(&oriented_cube_state) {
+                return Ok(oriented_cube_state);
+            }
+        }
+    }
+
+    Err(if cube_size.0 % 2 == 1 {
+        "the centers are not where they are on a cube".to_string()
+    } else {
+        "there is no corner with the colors yellow, orange, blue".to_string()
+    })
+}
+
+fn is_oriented(cube_state: &CubeState) -> bool {
+    let cube_size = cube_state.size();
+    let facelets: Vec<char> = cube_state.to_facelets().chars().collect();
+    let face_length = cube_size * cube_size;
+
+    if cube_size % 2 == 1 {
+        let center = face_length / 2;
+        return FACES
+            .iter()
+            .enumerate()
+            .all(|(i, (face, _, _))| facelets[i * face_length + center] == *face);
+    }
+
+    // the bottom left facelet of D, the bottom left facelet of L and the bottom right facelet of B
+    let d = 3 * face_length + face_length - cube_size;
+    let l = 4 * face_length + face_length - cube_size;
+    let b = 5 * face_length + face_length - 1;
+    facelets[d] == 'D' && facelets[l] == 'L' && facelets[b] == 'B'
+}
+
+/// Whether the pieces get to their solved positions with an odd number of swaps
+fn permutation_is_odd(pieces: &[&Piece]) -> bool {
+    let positions: Vec<[i32; 3]> = pieces.iter().map(|piece| piece.position).collect();
+    let mut permutation: Vec<usize> = pieces
+        .iter()
+        .map(|piece| {
+            positions
+                .iter()
+                .position(|position| *position == piece.solved_position())
+                .unwrap()
+        })
+        .collect();
+
+    let mut swaps = 0;
+    for i in 0..permutation.len() {
+        while permutation[i] != i {
+            let j = permutation[i];
+            permutation.swap(i, j);
+            swaps += 1;
+        }
+    }
+
+    swaps % 2 == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{
+        algorithm_from_notation, create_seeded_scramble_sequence, scramble_length, CubeSize,
+        CubeState,
+    };
+
+    use super::check_solvable;
+
+    fn with_facelets(cube_state: &CubeState, change: impl Fn(&mut Vec<char>)) -> CubeState {
+        let mut facelets: Vec<char> = cube_state.to_facelets().chars().collect();
+        change(&mut facelets);
+        CubeState::parse_facelets(&facelets.into_iter().collect::<String>()).unwrap()
+    }
+
+    #[test]
+    fn test_reachable_states_are_solvable() {
+        for cube_size in 1..=7 {
+            let size = CubeSize(cube_size as i32);
+            let mut cube_state = CubeState::new(cube_size);
+            assert_eq!(Ok(()), check_solvable(&cube_state));
+
+            for rotation_event in
+                create_seeded_scramble_sequence(&size, scramble_length(&size), cube_size as u64)
+                    .iter()
+                    .chain(&algorithm_from_notation("x y2 z'", &size).unwrap())
+            {
+                cube_state.handle_rotate_event(rotation_event);
+            }
+            assert_eq!(
+                Ok(()),
+                check_solvable(&cube_state),
+                "{cube_size}x{cube_size}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_unsolvable_3x3_states() {
+        // facelets of the URF corner, and of the UR and UF edges
+        let (u9, r1, f3) = (8, 9, 20);
+        let (u6, r2) = (5, 10);
+        let (u8, f2) = (7, 19);
+
+        let mut cube_state = CubeState::new(3);
+        for rotation_event in algorithm_from_notation("R U F' D2 L", &CubeSize(3)).unwrap() {
+            cube_state.handle_rotate_event(&rotation_event);
+        }
+        assert_eq!(Ok(()), check_solvable(&cube_state));
+
+        let wrong_count = with_facelets(&cube_state, |facelets| {
+            facelets[0] = if facelets[0] == 'U' { 'D' } else { 'U' }
+        });
+        let twisted_corner = with_facelets(&cube_state, |facelets| {
+            let (a, b, c) = (facelets[u9], facelets[r1], facelets[f3]);
+            (facelets[u9], facelets[r1], facelets[f3]) = (c, a, b);
+        });
+        let mirrored_corner = with_facelets(&cube_state, |facelets| facelets.swap(u9, r1));
+        let flipped_edge = with_facelets(&cube_state, |facelets| facelets.swap(u6, r2));
+        let swapped_edges = with_facelets(&cube_state, |facelets| {
+            facelets.swap(u6, u8);
+            facelets.swap(r2, f2);
+        });
+        let swapped_centers = with_facelets(&CubeState::new(3), |facelets| facelets.swap(4, 31));
+
+        assert!(check_solvable(&wrong_count).is_err());
+        assert_eq!(
+            Err("a corner is twisted".to_string()),
+            check_solvable(&twisted_corner)
+        );
+        assert!(check_solvable(&mirrored_corner)
+            .unwrap_err()
+            .ends_with("is mirrored"));
+        assert_eq!(
+            Err("an edge is flipped".to_string()),
+            check_solvable(&flipped_edge)
+        );
+        assert_eq!(
+            Err("two pieces are swapped".to_string()),
+            check_solvable(&swapped_edges)
+        );
+        assert_eq!(
+            Err("the centers are not where they are on a cube".to_string()),
+            check_solvable(&swapped_centers)
+        );
+    }
+
+    #[test]
+    fn test_unsolvable_4x4_states() {
+        // the URF corner on a 4x4
+        let (u16, r1, f4) = (15, 16, 35);
+
+        let twisted_corner = with_facelets(&CubeState::new(4), |facelets| {
+            let (a, b, c) = (facelets[u16], facelets[r1], facelets[f4]);
+            (facelets[u16], facelets[r1], facelets[f4]) = (c, a, b);
+        });
+        assert_eq!(
+            Err("a corner is twisted".to_string()),
+            check_solvable(&twisted_corner)
+        );
+
+        // a yellow sticker painted white
+        let wrong_count = with_facelets(&CubeState::new(4), |facelets| facelets[50] = 'U');
+        assert_eq!(
+            Err("there are 17 white stickers instead of 16".to_string()),
+            check_solvable(&wrong_count)
+        );
+    }
+}
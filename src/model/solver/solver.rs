@@ -1,6 +1,4 @@
-use bevy::log;
-
-use crate::cube::{CubeRotationEvent, CubeState};
+use crate::model::{CubeRotationEvent, CubeState};
 
 use super::kociemba::get_solve_sequence_with_kociemba;
 
@@ -25,6 +23,6 @@ pub fn get_solve_sequence(
 }
 
 pub fn get_solve_sequence_with_gods_algorithm(_cube_state: &CubeState) -> Vec<CubeRotationEvent> {
-    log::info!("TODO implement get_solve_sequence_with_gods_algorithm");
+    tracing::info!("TODO implement get_solve_sequence_with_gods_algorithm");
     return vec![];
 }
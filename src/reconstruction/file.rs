@@ -0,0 +1,346 @@
+// Saves reconstructions to files and loads them again. The files use the same `key = value` format as the other
+// files in the storage directory, so that they are easy to read and edit by hand. Every file starts with the version
+// of the format, so that files written by older versions of the app can be migrated when the format changes. Version 1
+// is the first version, so there is nothing to migrate yet.
+
+use std::{fs, path::Path};
+
+use crate::{
+    cube::{
+        algorithm_from_notation, algorithm_to_notation, CubeRotationAnimation, CubeRotationEvent,
+        CubeSize, CubeState, EaseFunction,
+    },
+    utils::storage::{format_key_values, parse_key_values},
+};
+
+use super::{Reconstruction, RecordedTurn};
+
+/// The version of the format that gets written. Increase it, and handle the old version in `migrate`, whenever the
+/// format changes in a way that older files can not be read anymore.
+pub const FORMAT_VERSION: u32 = 1;
+
+const FILE_HEADER: &str = "# A reconstruction of a solve of a Rubik's cube.
+# `facelets` is the cube at the start of the solve, with the faces in URFDLB order.
+# `scramble` brings a solved cube to that state.
+# Each turn is written as `turn = <started at> <finished at> <notation>`, in seconds since the start of the solve.
+";
+
+/// How long turns take when a loaded turn has no duration
+const DEFAULT_TURN_SECONDS: f32 = 0.3;
+/// Turn times above a day can not come from a solve, and larger values overflow the timers of the replay
+const MAX_TURN_SECONDS: f32 = 24. * 60. * 60.;
+
+pub fn save(path: &Path, reconstruction: &Reconstruction) -> Result<(), String> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)
+            .map_err(|err| format!("failed to create {}: {err}", dir.display()))?;
+    }
+
+    fs::write(path, to_file_contents(reconstruction))
+        .map_err(|err| format!("failed to write {}: {err}", path.display()))
+}
+
+pub fn load(path: &Path) -> Result<Reconstruction, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+
+    from_file_contents(&contents).map_err(|err| format!("{}: {err}", path.display()))
+}
+
+fn to_file_contents(reconstruction: &Reconstruction) -> String {
+    let cube_size = CubeSize(reconstruction.cube_size as i32);
+
+    let mut key_values = vec![
+        ("version".to_string(), FORMAT_VERSION.to_string()),
+        (
+            "cube_size".to_string(),
+            reconstruction.cube_size.to_string(),
+        ),
+        (
+            "facelets".to_string(),
+            reconstruction.start_state.to_facelets(),
+        ),
+        (
+            "scramble".to_string(),
+            algorithm_to_notation(&reconstruction.scramble, &cube_size),
+        ),
+        (
+            "recorded_at".to_string(),
+            reconstruction.recorded_at.to_string(),
+        ),
+        ("timed".to_string(), reconstruction.is_timed.to_string()),
+    ];
+
+    for turn in &reconstruction.turns {
+        key_values.push((
+            "turn".to_string(),
+            format!(
+                "{:.3} {:.3} {}",
+                turn.started_at,
+                turn.finished_at,
+                turn.rotation_event.to_notation(&cube_size)
+            ),
+        ));
+    }
+
+    format!("{FILE_HEADER}{}", format_key_values(&key_values))
+}
+
+fn from_file_contents(contents: &str) -> Result<Reconstruction, String> {
+    let key_values = parse_key_values(contents);
+
+    let version = match find_value(&key_values, "version") {
+        Some(version) => version
+            .parse()
+            .map_err(|_| format!("invalid version \"{version}\""))?,
+        None => return Err("not a reconstruction file, the version is missing".to_string()),
+    };
+    let key_values = migrate(version, key_values)?;
+
+    let cube_size: usize = parse_value(&key_values, "cube_size")?;
+    if cube_size == 0 {
+        return Err("the cube size must be at least 1".to_string());
+    }
+    let notation_cube_size = CubeSize(cube_size as i32);
+
+    let start_state =
+        CubeState::from_facelets(cube_size, &required_value(&key_values, "facelets")?)
+            .map_err(|err| format!("invalid facelets: {err}"))?;
+
+    let scramble = algorithm_from_notation(
+        &required_value(&key_values, "scramble")?,
+        &notation_cube_size,
+    )
+    .map_err(|err| format!("invalid scramble: {err}"))?;
+
+    let mut turns = vec![];
+    for (key, value) in &key_values {
+        if key == "turn" {
+            let turn = parse_turn(value, &notation_cube_size)
+                .map_err(|err| format!("invalid turn \"{value}\": {err}"))?;
+            turns.push(turn);
+        }
+    }
+
+    Ok(Reconstruction {
+        cube_size,
+        start_state,
+        scramble,
+        turns,
+        is_timed: parse_value(&key_values, "timed")?,
+        recorded_at: parse_value(&key_values, "recorded_at")?,
+        history_start: None,
+    })
+}
+
+/// Brings the contents of a file that was written with an older version of the format up to date. There are no older
+/// versions yet. When the format changes, add a step for the previous version that turns its key values into those
+/// of the next version.
+fn migrate(
+    version: u32,
+    key_values: Vec<(String, String)>,
+) -> Result<Vec<(String, String)>, String> {
+    match version {
+        FORMAT_VERSION => Ok(key_values),
+        version if version > FORMAT_VERSION => Err(format!(
+            "version {version} is not supported, update the app to open this file"
+        )),
+        version => Err(format!(
+            "unknown version {version}, the first version of the format is 1"
+        )),
+    }
+}
+
+fn parse_time(value: &str) -> Result<f32, String> {
+    match value.parse::<f32>() {
+        Ok(time) if time.is_finite() && time <= MAX_TURN_SECONDS => Ok(time),
+        _ => Err(format!("invalid time \"{value}\"")),
+    }
+}
+
+fn parse_turn(value: &str, cube_size: &CubeSize) -> Result<RecordedTurn, String> {
+    let mut parts = value.split_whitespace();
+    let (Some(started_at), Some(finished_at), Some(notation), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err("expected <started at> <finished at> <notation>".to_string());
+    };
+
+    let started_at = parse_time(started_at)?;
+    let finished_at = parse_time(finished_at)?;
+    if started_at < 0.0 || finished_at < started_at {
+        return Err("a turn can not finish before it started".to_string());
+    }
+
+    let mut rotation_event = CubeRotationEvent::from_notation(notation, cube_size)?;
+    rotation_event.animation = Some(CubeRotationAnimation {
+        duration_in_seconds: match finished_at - started_at {
+            duration if duration > 0.0 => duration,
+            _ => DEFAULT_TURN_SECONDS,
+        },
+        ease_function: Some(EaseFunction::CubicOut),
+    });
+
+    Ok(RecordedTurn {
+        rotation_event,
+        started_at,
+        finished_at,
+    })
+}
+
+fn find_value<'a>(key_values: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    key_values
+        .iter()
+        .find(|(value_key, _)| value_key == key)
+        .map(|(_, value)| value.as_str())
+}
+
+fn required_value(key_values: &[(String, String)], key: &str) -> Result<String, String> {
+    find_value(key_values, key)
+        .map(str::to_string)
+        .ok_or_else(|| format!("{key} is missing"))
+}
+
+fn parse_value<T: std::str::FromStr>(
+    key_values: &[(String, String)],
+    key: &str,
+) -> Result<T, String> {
+    let value = required_value(key_values, key)?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid {key} \"{value}\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        cube::{CubeRotationEvent, CubeSize, CubeState},
+        reconstruction::{Reconstruction, RecordedTurn},
+    };
+
+    use super::{from_file_contents, to_file_contents, FORMAT_VERSION};
+
+    fn reconstruction() -> Reconstruction {
+        let cube_size = CubeSize(3);
+        let scramble: Vec<CubeRotationEvent> = ["R", "U'", "F2"]
+            .iter()
+            .map(|notation| CubeRotationEvent::from_notation(notation, &cube_size).unwrap())
+            .collect();
+
+        let mut start_state = CubeState::new(3);
+        for rotation_event in &scramble {
+            start_state.handle_rotate_event(rotation_event);
+        }
+
+        let turns = ["F2", "U", "R'"]
+            .iter()
+            .enumerate()
+            .map(|(i, notation)| RecordedTurn {
+                rotation_event: CubeRotationEvent::from_notation(notation, &cube_size).unwrap(),
+                started_at: i as f32,
+                finished_at: i as f32 + 0.25,
+            })
+            .collect();
+
+        Reconstruction {
+            cube_size: 3,
+            start_state,
+            scramble,
+            turns,
+            is_timed: true,
+            recorded_at: 1_700_000_000,
+            history_start: Some(3),
+        }
+    }
+
+    #[test]
+    fn test_file_round_trip() {
+        let reconstruction = reconstruction();
+        let loaded = from_file_contents(&to_file_contents(&reconstruction)).unwrap();
+
+        let cube_size = CubeSize(3);
+        assert_eq!(3, loaded.cube_size);
+        assert!(loaded.start_state == reconstruction.start_state);
+        assert_eq!(
+            vec!["R", "U'", "F2"],
+            loaded
+                .scramble
+                .iter()
+                .map(|rotation_event| rotation_event.to_notation(&cube_size))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(3, loaded.turns.len());
+        assert_eq!("R'", loaded.turns[2].rotation_event.to_notation(&cube_size));
+        assert_eq!(2.0, loaded.turns[2].started_at);
+        assert_eq!(2.25, loaded.turns[2].finished_at);
+        assert!(loaded.is_timed);
+        assert_eq!(1_700_000_000, loaded.recorded_at);
+        assert_eq!(None, loaded.history_start);
+    }
+
+    #[test]
+    fn test_unsupported_versions() {
+        let contents = to_file_contents(&reconstruction());
+
+        let without_version = contents.replace(&format!("version = {FORMAT_VERSION}\n"), "");
+        assert_eq!(
+            Err("not a reconstruction file, the version is missing".to_string()),
+            from_file_contents(&without_version).map(|_| ())
+        );
+
+        let newer_version = contents.replace(
+            &format!("version = {FORMAT_VERSION}"),
+            &format!("version = {}", FORMAT_VERSION + 1),
+        );
+        assert!(from_file_contents(&newer_version)
+            .err()
+            .unwrap()
+            .contains("not supported"));
+
+        let version_0 = contents.replace(&format!("version = {FORMAT_VERSION}"), "version = 0");
+        assert_eq!(
+            Err("unknown version 0, the first version of the format is 1".to_string()),
+            from_file_contents(&version_0).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_corrupt_files() {
+        let contents = to_file_contents(&reconstruction());
+
+        let missing_facelets: String = contents
+            .lines()
+            .filter(|line| !line.starts_with("facelets"))
+            .map(|line| format!("{line}\n"))
+            .collect();
+        assert_eq!(
+            Err("facelets is missing".to_string()),
+            from_file_contents(&missing_facelets).map(|_| ())
+        );
+
+        let corrupt_turn = contents.replace("turn = 1.000 1.250 U", "turn = 1.000 U");
+        assert_eq!(
+            Err(
+                "invalid turn \"1.000 U\": expected <started at> <finished at> <notation>"
+                    .to_string()
+            ),
+            from_file_contents(&corrupt_turn).map(|_| ())
+        );
+
+        for (times, invalid_time) in [("0 inf", "inf"), ("0 1e30", "1e30"), ("NaN 1.250", "NaN")] {
+            let corrupt_time = contents.replace("1.000 1.250 U", &format!("{times} U"));
+            assert_eq!(
+                Err(format!(
+                    "invalid turn \"{times} U\": invalid time \"{invalid_time}\""
+                )),
+                from_file_contents(&corrupt_time).map(|_| ())
+            );
+        }
+
+        let wrong_size = contents.replace("cube_size = 3", "cube_size = 2");
+        assert!(from_file_contents(&wrong_size)
+            .err()
+            .unwrap()
+            .starts_with("invalid facelets"));
+    }
+}
@@ -0,0 +1,7 @@
+mod reconstruction;
+pub use reconstruction::Reconstruction;
+pub use reconstruction::ReconstructionPlugin;
+pub use reconstruction::ReconstructionResource;
+pub use reconstruction::RecordedTurn;
+
+pub mod file;
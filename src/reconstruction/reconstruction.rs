@@ -0,0 +1,544 @@
+// Records the turns of a solve together with the moment they were made, so that the solve can be reviewed afterwards.
+// Timed solves are recorded from the start of inspection. Other solves are recorded from the first turn that is made
+// by the user on a scrambled cube. A recording ends once the cube is solved. Solves of a cube whose state was set
+// directly are not recorded, because there is no known scramble for them.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::{
+    cube::{
+        Cube, CubeRotationAnimation, CubeRotationEvent, CubeRotationEventFinished, CubeSize,
+        CubeState, Rotation, SequenceResource, SetCubeStateEvent,
+    },
+    schedules::CubeScheduleSet,
+    timer::{format_time, SolveTimerResource, TimerState},
+};
+
+pub struct ReconstructionPlugin;
+
+impl Plugin for ReconstructionPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ReconstructionResource::default())
+            .add_systems(
+                Update,
+                (handle_set_cube_state_events, record_turns)
+                    .chain()
+                    .in_set(CubeScheduleSet::UpdateAnimations),
+            );
+    }
+}
+
+/// Turns that are made within this time after each other are shown on the same line when exporting a reconstruction
+const PAUSE_SECONDS: f32 = 0.5;
+
+#[derive(Clone, Debug)]
+pub struct RecordedTurn {
+    pub rotation_event: CubeRotationEvent,
+    /// Seconds since the start of the recording
+    pub started_at: f32,
+    /// Seconds since the start of the recording
+    pub finished_at: f32,
+}
+
+#[derive(Clone)]
+pub struct Reconstruction {
+    pub cube_size: usize,
+    pub start_state: CubeState,
+    /// Turns that bring a solved cube to `start_state`
+    pub scramble: Vec<CubeRotationEvent>,
+    pub turns: Vec<RecordedTurn>,
+    /// Whether this was a timed solve, which includes inspection
+    pub is_timed: bool,
+    /// Seconds since the unix epoch
+    pub recorded_at: u64,
+    /// Where the reconstruction starts in the turn history of the cube. None if the cube got replaced since, or
+    /// if the reconstruction was loaded from a file.
+    pub(super) history_start: Option<usize>,
+}
+
+struct Recording {
+    reconstruction: Reconstruction,
+    /// `Time::elapsed_secs` at the start of the recording
+    started_at: f32,
+    /// Indices of turns that are still being animated, oldest first
+    animating_turns: VecDeque<usize>,
+}
+
+#[derive(Resource, Default)]
+pub struct ReconstructionResource {
+    /// Every turn since the cube got spawned, used to return the cube to the start of a reconstruction
+    history: Vec<CubeRotationEvent>,
+    /// The state that `history` brings a solved cube to. Solves can only be recorded from this state, so that
+    /// `history` is their scramble.
+    history_state: Option<CubeState>,
+    recording: Option<Recording>,
+    last: Option<Reconstruction>,
+}
+
+impl ReconstructionResource {
+    /// The last solve that was recorded
+    pub fn last(&self) -> Option<&Reconstruction> {
+        self.last.as_ref()
+    }
+
+    /// Make the reconstruction the one that gets replayed, for example after loading it from a file
+    pub fn set_last(&mut self, reconstruction: Reconstruction) {
+        self.last = Some(reconstruction);
+    }
+
+    /// The steps and pauses to set on the `SequenceResource` to replay the last reconstruction. The cube first
+    /// goes to the start of the reconstruction without animating. A higher speed multiplier results in a faster
+    /// replay.
+    ///
+    /// Returns None if there is no reconstruction, or if the cube can not be brought to the start of it. That is
+    /// only possible if the cube is solved, or if the cube has not been replaced since the reconstruction was
+    /// recorded.
+    pub fn replay_sequence(
+        &self,
+        cube_state: &CubeState,
+        speed_multiplier: f32,
+    ) -> Option<(Vec<CubeRotationEvent>, Vec<f32>)> {
+        let reconstruction = self.last.as_ref()?;
+
+        let mut steps = self.steps_to_start(reconstruction, cube_state)?;
+        let mut step_pauses = vec![0.0; steps.len()];
+
+        let (replay_steps, replay_pauses) = replay_turns(&reconstruction.turns, speed_multiplier);
+        steps.extend(replay_steps);
+        step_pauses.extend(replay_pauses);
+
+        Some((steps, step_pauses))
+    }
+
+    /// Steps without animation that bring the cube to the start of the reconstruction
+    fn steps_to_start(
+        &self,
+        reconstruction: &Reconstruction,
+        cube_state: &CubeState,
+    ) -> Option<Vec<CubeRotationEvent>> {
+        if cube_state.size() != reconstruction.cube_size {
+            return None;
+        }
+
+        let mut candidates = vec![];
+
+        if let Some(history_start) = reconstruction.history_start {
+            candidates.push(
+                self.history[history_start..]
+                    .iter()
+                    .rev()
+                    .map(|rotation_event| rotation_event.inverted())
+                    .collect::<Vec<_>>(),
+            );
+        }
+
+        if cube_state.is_solved() {
+            candidates.push(reconstruction.scramble.clone());
+        }
+
+        candidates.into_iter().find_map(|mut steps| {
+            let mut state = cube_state.clone();
+            for step in &mut steps {
+                step.animation = None;
+                state.handle_rotate_event(step);
+            }
+
+            (state == reconstruction.start_state).then_some(steps)
+        })
+    }
+
+    fn start_recording(&mut self, start_state: CubeState, now: f32, is_timed: bool) {
+        let recorded_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+
+        self.recording = Some(Recording {
+            reconstruction: Reconstruction {
+                cube_size: start_state.size(),
+                start_state,
+                // the cube is solved when it gets spawned
+                scramble: self.history.clone(),
+                turns: vec![],
+                is_timed,
+                recorded_at,
+                history_start: Some(self.history.len()),
+            },
+            started_at: now,
+            animating_turns: VecDeque::new(),
+        });
+    }
+
+    /// Forgets the turns that were made so far, for example because the cube got replaced
+    fn reset_history(&mut self, cube_size: usize) {
+        self.history.clear();
+        self.history_state = Some(CubeState::new(cube_size));
+        self.recording = None;
+        if let Some(reconstruction) = &mut self.last {
+            reconstruction.history_start = None;
+        }
+    }
+
+    fn finish_recording(&mut self) {
+        if let Some(recording) = self.recording.take() {
+            info!(
+                "recorded a solve of {} moves",
+                recording.reconstruction.move_count()
+            );
+            self.last = Some(recording.reconstruction);
+        }
+    }
+}
+
+/// The steps and the pauses after them to replay the turns with the timing they were made with
+fn replay_turns(
+    turns: &[RecordedTurn],
+    speed_multiplier: f32,
+) -> (Vec<CubeRotationEvent>, Vec<f32>) {
+    let mut steps = vec![];
+    let mut step_pauses = vec![];
+
+    for (i, turn) in turns.iter().enumerate() {
+        let mut step = turn.rotation_event.clone();
+
+        let duration_in_seconds = (turn.finished_at - turn.started_at) / speed_multiplier;
+        step.animation = (duration_in_seconds > 0.0).then(|| CubeRotationAnimation {
+            duration_in_seconds,
+            ease_function: step
+                .animation
+                .as_ref()
+                .and_then(|animation| animation.ease_function),
+        });
+
+        let pause = match turns.get(i + 1) {
+            Some(next_turn) => ((next_turn.started_at - turn.started_at) / speed_multiplier
+                - duration_in_seconds)
+                .max(0.0),
+            None => 0.0,
+        };
+
+        steps.push(step);
+        step_pauses.push(pause);
+    }
+
+    (steps, step_pauses)
+}
+
+impl Reconstruction {
+    /// The number of turns, not counting rotations of the whole cube
+    pub fn move_count(&self) -> usize {
+        self.turns
+            .iter()
+            .filter(|turn| !matches!(turn.rotation_event.rotation, Rotation::Cube(_)))
+            .count()
+    }
+
+    /// The notation of all turns, with a new line after every pause. Each line starts with the time at which its
+    /// first turn was made, for example:
+    ///
+    /// ```text
+    /// // 3x3, 4 moves in 2.10 seconds
+    /// [0.00] R U
+    /// [1.60] R' U'
+    /// ```
+    pub fn to_timed_notation(&self) -> String {
+        let cube_size = CubeSize(self.cube_size as i32);
+        let duration = self.turns.last().map_or(0.0, |turn| turn.finished_at);
+
+        let mut result = format!(
+            "// {0}x{0}, {1} moves in {2} seconds\n",
+            self.cube_size,
+            self.move_count(),
+            format_time(duration)
+        );
+
+        let mut previous_started_at: Option<f32> = None;
+        for turn in &self.turns {
+            let notation = turn.rotation_event.to_notation(&cube_size);
+
+            match previous_started_at {
+                Some(started_at) if turn.started_at - started_at < PAUSE_SECONDS => {
+                    result.push_str(&format!(" {notation}"));
+                }
+                Some(_) => {
+                    result.push_str(&format!("\n[{}] {notation}", format_time(turn.started_at)));
+                }
+                None => result.push_str(&format!("[{}] {notation}", format_time(turn.started_at))),
+            }
+
+            previous_started_at = Some(turn.started_at);
+        }
+
+        result.push('\n');
+        result
+    }
+}
+
+/// The turns before a state was set do not lead to it, so they can not be used as a scramble anymore
+fn handle_set_cube_state_events(
+    mut reconstruction_resource: ResMut<ReconstructionResource>,
+    mut set_cube_state_event_reader: EventReader<SetCubeStateEvent>,
+) {
+    if let Some(event) = set_cube_state_event_reader.read().last() {
+        reconstruction_resource.reset_history(event.cube_state.size());
+    }
+}
+
+fn record_turns(
+    mut reconstruction_resource: ResMut<ReconstructionResource>,
+    mut rotation_event_reader: EventReader<CubeRotationEvent>,
+    mut rotation_finished_event_reader: EventReader<CubeRotationEventFinished>,
+    cube_query: Query<(Ref<Cube>, &CubeState)>,
+    sequence_resource: Res<SequenceResource>,
+    solve_timer: Res<SolveTimerResource>,
+    mut last_sequence_version: Local<usize>,
+    time: Res<Time>,
+) {
+    let Ok((cube, cube_state)) = cube_query.get_single() else {
+        return;
+    };
+
+    if cube.is_added() {
+        reconstruction_resource.reset_history(cube_state.size());
+    }
+
+    let rotation_events: Vec<CubeRotationEvent> = rotation_event_reader.read().cloned().collect();
+    let finished_rotations = rotation_finished_event_reader.read().count();
+    let now = time.elapsed_secs();
+
+    // Turns of a sequence are sent while the sequence is not done yet, or all at once in the frame the sequence
+    // was set if they are not animated.
+    let are_user_turns =
+        sequence_resource.is_done() && sequence_resource.version() == *last_sequence_version;
+    *last_sequence_version = sequence_resource.version();
+
+    let resource = reconstruction_resource.as_mut();
+
+    if resource.recording.is_none() {
+        let start_timed_recording = matches!(solve_timer.state(), TimerState::Inspecting { .. });
+        let start_free_recording =
+            !solve_timer.is_timing() && are_user_turns && !rotation_events.is_empty();
+
+        if start_timed_recording || start_free_recording {
+            // the cube state already includes the turns of this frame
+            let mut start_state = cube_state.clone();
+            for rotation_event in rotation_events.iter().rev() {
+                start_state.handle_rotate_event(&rotation_event.inverted());
+            }
+
+            let is_scrambled_from_history = resource.history_state.as_ref() == Some(&start_state);
+
+            if is_scrambled_from_history && (start_timed_recording || !start_state.is_solved()) {
+                resource.start_recording(start_state, now, start_timed_recording);
+            }
+        }
+    }
+
+    if let Some(recording) = &mut resource.recording {
+        if !recording.reconstruction.is_timed && !are_user_turns {
+            // a sequence, like a solve, got started
+            resource.recording = None;
+        }
+    }
+
+    if let Some(recording) = &mut resource.recording {
+        for _ in 0..finished_rotations {
+            if let Some(turn_index) = recording.animating_turns.pop_front() {
+                recording.reconstruction.turns[turn_index].finished_at = now - recording.started_at;
+            }
+        }
+
+        for rotation_event in &rotation_events {
+            let started_at = now - recording.started_at;
+
+            if rotation_event.animation.is_some() {
+                recording
+                    .animating_turns
+                    .push_back(recording.reconstruction.turns.len());
+            }
+
+            recording.reconstruction.turns.push(RecordedTurn {
+                rotation_event: rotation_event.clone(),
+                started_at,
+                finished_at: started_at
+                    + rotation_event
+                        .animation
+                        .as_ref()
+                        .map_or(0.0, |animation| animation.duration_in_seconds),
+            });
+        }
+    }
+
+    if let Some(history_state) = &mut resource.history_state {
+        for rotation_event in &rotation_events {
+            history_state.handle_rotate_event(rotation_event);
+        }
+    }
+    resource.history.extend(rotation_events);
+
+    let Some(recording) = &resource.recording else {
+        return;
+    };
+
+    if recording.reconstruction.is_timed {
+        match solve_timer.state() {
+            TimerState::Finished(_) => resource.finish_recording(),
+            TimerState::Inspecting { .. } | TimerState::Solving { .. } => (),
+            // the timed solve got cancelled
+            _ => resource.recording = None,
+        }
+    } else if cube_state.is_solved() {
+        resource.finish_recording();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate::{
+        cube::{
+            algorithm_from_notation, test_utils::headless_app, CubeRotationAnimation,
+            CubeRotationEvent, CubeSize, CubeState, Rotation, SequenceResource, SetCubeStateEvent,
+        },
+        reconstruction::file,
+        timer::TimerPlugin,
+    };
+
+    use super::{
+        replay_turns, Reconstruction, ReconstructionPlugin, ReconstructionResource, RecordedTurn,
+    };
+
+    fn turn(rotation: Rotation, started_at: f32, finished_at: f32) -> RecordedTurn {
+        RecordedTurn {
+            rotation_event: CubeRotationEvent {
+                rotation,
+                negative_direction: true,
+                twice: false,
+                animation: Some(CubeRotationAnimation {
+                    duration_in_seconds: 0.3,
+                    ease_function: None,
+                }),
+            },
+            started_at,
+            finished_at,
+        }
+    }
+
+    fn reconstruction() -> Reconstruction {
+        Reconstruction {
+            cube_size: 3,
+            start_state: CubeState::new(3),
+            scramble: vec![],
+            turns: vec![
+                turn(Rotation::cube_y(), 0.0, 0.3),
+                turn(Rotation::face_x(1), 1.0, 1.3),
+                turn(Rotation::face_y(1), 1.4, 1.7),
+                turn(Rotation::face_z(1), 2.5, 2.8),
+            ],
+            is_timed: false,
+            recorded_at: 0,
+            history_start: Some(0),
+        }
+    }
+
+    #[test]
+    fn test_to_timed_notation() {
+        assert_eq!(
+            "// 3x3, 3 moves in 2.80 seconds\n[0.00] y\n[1.00] R U\n[2.50] F\n",
+            reconstruction().to_timed_notation()
+        );
+    }
+
+    #[test]
+    fn test_replay_turns_keeps_timing() {
+        let (steps, step_pauses) = replay_turns(&reconstruction().turns, 2.0);
+
+        assert_eq!(4, steps.len());
+        assert_eq!(
+            0.15,
+            steps[0].animation.as_ref().unwrap().duration_in_seconds
+        );
+
+        // each turn starts at the scaled time it was made at
+        let mut started_at = 0.0;
+        for (i, turn) in reconstruction().turns.iter().enumerate() {
+            assert!((turn.started_at / 2.0 - started_at).abs() < 0.0001);
+            started_at += steps[i].animation.as_ref().unwrap().duration_in_seconds + step_pauses[i];
+        }
+    }
+
+    fn moves(notation: &str) -> Vec<CubeRotationEvent> {
+        algorithm_from_notation(notation, &CubeSize(3)).unwrap()
+    }
+
+    fn play_sequence(app: &mut App, notation: &str) {
+        app.world_mut()
+            .resource_mut::<SequenceResource>()
+            .set(moves(notation));
+        for _ in 0..20 {
+            app.update();
+        }
+    }
+
+    fn turn_by_user(app: &mut App, notation: &str) {
+        for rotation_event in moves(notation) {
+            app.world_mut().send_event(rotation_event);
+            app.update();
+        }
+    }
+
+    fn set_cube_state(app: &mut App, cube_state: CubeState) {
+        app.world_mut().send_event(SetCubeStateEvent { cube_state });
+        app.update();
+    }
+
+    #[test]
+    fn test_set_cube_state_resets_the_history() {
+        let mut app = headless_app(3);
+        app.add_plugins((TimerPlugin, ReconstructionPlugin));
+        app.update();
+
+        // a state that was set directly has no known scramble, so its solve is not recorded
+        play_sequence(&mut app, "R U F");
+        let mut set_state = CubeState::new(3);
+        for rotation_event in moves("L' D2 B") {
+            set_state.handle_rotate_event(&rotation_event);
+        }
+        set_cube_state(&mut app, set_state);
+        turn_by_user(&mut app, "B' D2");
+        assert!(app
+            .world()
+            .resource::<ReconstructionResource>()
+            .recording
+            .is_none());
+
+        // after a reset to a solved cube the turns since then are the scramble
+        set_cube_state(&mut app, CubeState::new(3));
+        play_sequence(&mut app, "F2 U' R");
+        turn_by_user(&mut app, "R' U F2");
+
+        let reconstruction = app
+            .world()
+            .resource::<ReconstructionResource>()
+            .last()
+            .unwrap()
+            .clone();
+        let path = std::env::temp_dir().join(format!(
+            "rubiks-cube-test-{}-set-state.txt",
+            std::process::id()
+        ));
+        file::save(&path, &reconstruction).unwrap();
+        let loaded = file::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let mut scrambled = CubeState::new(3);
+        for rotation_event in &loaded.scramble {
+            scrambled.handle_rotate_event(rotation_event);
+        }
+        assert!(scrambled == loaded.start_state);
+        assert_eq!(3, loaded.scramble.len());
+        assert_eq!(3, loaded.turns.len());
+    }
+}
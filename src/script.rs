@@ -0,0 +1,446 @@
+// Runs scripted scenarios, for demos and regression tests. A script is a text file with one step per line, and gets
+// started with the `--script <path>` command line argument:
+//
+//     # lines starting with # are comments
+//     size 5
+//     speed 2
+//     scramble seed=7
+//     assert not solved
+//     wait 2s
+//     solve
+//     exit
+//
+// Steps: `size <n>`, `speed <multiplier>` or `speed instant`, `scramble` with an optional `seed=<n>`,
+// `apply <moves>`, `wait <n>s` or `wait <n>ms`, `solve`, `assert solved`, `assert not solved`,
+// `assert state <facelets>` and `exit`. A failing assert stops the app with an error exit code.
+
+use std::{fs, path::Path, time::Duration};
+
+use bevy::prelude::*;
+use rubiks_cube::model::MAX_CUBE_SIZE;
+
+use crate::{
+    cube::{
+        algorithm_from_notation, create_random_scramble_sequence, create_seeded_scramble_sequence,
+        scramble_length,
+        solver::{self, SolveStrategy},
+        AnimationProfileResource, Cube, CubeCommandsResource, CubeState, CurrentCubeSizeResource,
+        SequenceResource, MINIMUM_SUPPORTED_CUBE_SIZE,
+    },
+    schedules::CubeScheduleSet,
+    utils::args,
+};
+
+const SCRIPT_ARGUMENT: &str = "--script";
+
+/// Runs the script at `path`, if it is set
+pub struct ScriptPlugin {
+    pub path: Option<String>,
+}
+
+impl Plugin for ScriptPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            run_script
+                .run_if(resource_exists::<ScriptResource>)
+                .in_set(CubeScheduleSet::Timers),
+        );
+
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        match load_script(Path::new(path)) {
+            Ok(steps) => {
+                info!("running script {path}");
+                app.insert_resource(ScriptResource::new(steps));
+            }
+            Err(err) => {
+                error!("failed to load script {path}: {err}");
+                app.add_systems(Startup, |mut exit: EventWriter<AppExit>| {
+                    exit.send(AppExit::error());
+                });
+            }
+        }
+    }
+}
+
+/// Returns the path given with `--script`, if any
+pub fn path_from_args(args: impl Iterator<Item = String>) -> Option<String> {
+    args::value_of(args, SCRIPT_ARGUMENT)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum ScriptStep {
+    Size(usize),
+    /// None for instant moves
+    Speed(Option<f32>),
+    Scramble {
+        seed: Option<u64>,
+    },
+    Apply(String),
+    Wait(Duration),
+    Solve,
+    Assert(Assertion),
+    Exit,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Assertion {
+    Solved,
+    NotSolved,
+    State(String),
+}
+
+/// A step together with the number of the line it is on, starting at 1
+#[derive(Clone, Debug, PartialEq)]
+struct ScriptLine {
+    line_number: usize,
+    step: ScriptStep,
+}
+
+fn load_script(path: &Path) -> Result<Vec<ScriptLine>, String> {
+    let contents = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    parse_script(&contents)
+}
+
+fn parse_script(contents: &str) -> Result<Vec<ScriptLine>, String> {
+    contents
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line_number, line)| {
+            parse_step(line)
+                .map(|step| ScriptLine { line_number, step })
+                .map_err(|err| format!("line {line_number}: {err}"))
+        })
+        .collect()
+}
+
+fn parse_step(line: &str) -> Result<ScriptStep, String> {
+    let (name, argument) = match line.split_once(char::is_whitespace) {
+        Some((name, argument)) => (name, argument.trim()),
+        None => (line, ""),
+    };
+
+    let step = match (name, argument) {
+        ("size", size) => {
+            let size = size
+                .parse()
+                .map_err(|_| format!("invalid size \"{size}\""))?;
+            if size < MINIMUM_SUPPORTED_CUBE_SIZE {
+                return Err(format!("invalid size \"{size}\""));
+            }
+            if size > MAX_CUBE_SIZE {
+                return Err(format!(
+                    "invalid size \"{size}\", it can be at most {MAX_CUBE_SIZE}"
+                ));
+            }
+            ScriptStep::Size(size)
+        }
+        ("speed", "instant") => ScriptStep::Speed(None),
+        ("speed", multiplier) => match multiplier.parse::<f32>() {
+            Ok(multiplier) if multiplier > 0.0 => ScriptStep::Speed(Some(multiplier)),
+            _ => return Err(format!("invalid speed \"{multiplier}\"")),
+        },
+        ("scramble", "") => ScriptStep::Scramble { seed: None },
+        ("scramble", seed) => match seed.strip_prefix("seed=").map(str::parse) {
+            Some(Ok(seed)) => ScriptStep::Scramble { seed: Some(seed) },
+            _ => {
+                return Err(format!(
+                    "invalid scramble \"{seed}\", expected seed=<number>"
+                ))
+            }
+        },
+        ("apply", "") => return Err("apply needs moves, like apply R U R' U'".to_string()),
+        ("apply", moves) => ScriptStep::Apply(moves.to_string()),
+        ("wait", duration) => ScriptStep::Wait(parse_duration(duration)?),
+        ("solve", "") => ScriptStep::Solve,
+        ("assert", "solved") => ScriptStep::Assert(Assertion::Solved),
+        ("assert", "not solved") => ScriptStep::Assert(Assertion::NotSolved),
+        ("assert", assertion) => match assertion.strip_prefix("state ") {
+            Some(facelets) => {
+                let facelets = facelets.trim();
+                CubeState::parse_facelets(facelets)
+                    .map_err(|err| format!("invalid facelets: {err}"))?;
+                ScriptStep::Assert(Assertion::State(facelets.to_string()))
+            }
+            None => return Err(format!("unknown assertion \"{assertion}\"")),
+        },
+        ("exit", "") => ScriptStep::Exit,
+        _ => return Err(format!("unknown step \"{line}\"")),
+    };
+
+    Ok(step)
+}
+
+/// Parses durations like `2s`, `0.5s` and `500ms`
+fn parse_duration(duration: &str) -> Result<Duration, String> {
+    let seconds = if let Some(milliseconds) = duration.strip_suffix("ms") {
+        milliseconds
+            .parse::<f32>()
+            .map(|milliseconds| milliseconds / 1000.0)
+    } else if let Some(seconds) = duration.strip_suffix('s') {
+        seconds.parse::<f32>()
+    } else {
+        return Err(format!(
+            "invalid duration \"{duration}\", expected for example 2s or 500ms"
+        ));
+    };
+
+    seconds
+        .ok()
+        .and_then(|seconds| Duration::try_from_secs_f32(seconds).ok())
+        .ok_or_else(|| format!("invalid duration \"{duration}\""))
+}
+
+#[derive(Resource)]
+pub struct ScriptResource {
+    steps: Vec<ScriptLine>,
+    next_step: usize,
+    /// The speed multiplier for moves, None for instant moves
+    speed: Option<f32>,
+    wait_timer: Option<Timer>,
+}
+
+impl ScriptResource {
+    fn new(steps: Vec<ScriptLine>) -> Self {
+        Self {
+            steps,
+            next_step: 0,
+            speed: Some(1.0),
+            wait_timer: None,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.next_step >= self.steps.len()
+    }
+}
+
+/// Runs the next step once the previous one is done: the moves have been animated, the cube has been respawned
+/// after a size change, or the wait is over.
+fn run_script(
+    mut commands: Commands,
+    mut script: ResMut<ScriptResource>,
+    mut sequence_resource: ResMut<SequenceResource>,
+    mut cube_size_resource: ResMut<CurrentCubeSizeResource>,
+    cube_commands: Res<CubeCommandsResource>,
+    animation_profile: Res<AnimationProfileResource>,
+    cube_query: Query<(&Cube, &CubeState)>,
+    mut exit: EventWriter<AppExit>,
+    time: Res<Time>,
+) {
+    if let Some(wait_timer) = &mut script.wait_timer {
+        if !wait_timer.tick(time.delta()).finished() {
+            return;
+        }
+        script.wait_timer = None;
+    }
+
+    if script.is_done() || !sequence_resource.is_done() {
+        return;
+    }
+
+    let Ok((cube, cube_state)) = cube_query.get_single() else {
+        // the cube might be respawning
+        return;
+    };
+
+    if cube.is_animating_rotation {
+        return;
+    }
+
+    let ScriptLine { line_number, step } = script.steps[script.next_step].clone();
+    script.next_step += 1;
+
+    let cube_size = cube.size();
+    let speed = script.speed;
+
+    match step {
+        ScriptStep::Size(size) => {
+            sequence_resource.set(vec![]);
+            cube_size_resource.0 = size;
+            commands.run_system(cube_commands.despawn);
+            commands.run_system(cube_commands.spawn);
+        }
+        ScriptStep::Speed(multiplier) => script.speed = multiplier,
+        ScriptStep::Scramble { seed } => {
+            let length = scramble_length(cube_size);
+            let scramble = match seed {
+                Some(seed) => create_seeded_scramble_sequence(cube_size, length, seed),
+                None => create_random_scramble_sequence(cube_size, length),
+            };
+            sequence_resource.set_animated(scramble, &animation_profile.0.scramble, speed);
+        }
+        ScriptStep::Apply(moves) => match algorithm_from_notation(&moves, cube_size) {
+            Ok(moves) => sequence_resource.set_animated(moves, &animation_profile.0.solve, speed),
+            Err(err) => fail(line_number, &err, &mut script, &mut exit),
+        },
+        ScriptStep::Wait(duration) => {
+            script.wait_timer = Some(Timer::new(duration, TimerMode::Once));
+        }
+        ScriptStep::Solve => {
+            let solution = solver::get_solve_sequence(SolveStrategy::Kociemba, cube_state);
+            if solution.is_empty() && !cube_state.is_solved() {
+                warn!("script line {line_number}: no solution found");
+            }
+            sequence_resource.set_animated(solution, &animation_profile.0.solve, speed);
+        }
+        ScriptStep::Assert(assertion) => {
+            if let Err(err) = check_assertion(&assertion, cube_state) {
+                fail(line_number, &err, &mut script, &mut exit);
+            }
+        }
+        ScriptStep::Exit => {
+            exit.send(AppExit::Success);
+        }
+    }
+
+    if script.is_done() {
+        info!("script finished");
+    }
+}
+
+fn check_assertion(assertion: &Assertion, cube_state: &CubeState) -> Result<(), String> {
+    match assertion {
+        Assertion::Solved if !cube_state.is_solved() => Err("the cube is not solved".to_string()),
+        Assertion::NotSolved if cube_state.is_solved() => Err("the cube is solved".to_string()),
+        Assertion::State(facelets) if cube_state.to_facelets() != *facelets => Err(format!(
+            "expected state {facelets}, but the state is {}",
+            cube_state.to_facelets()
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Stops the script and the app with an error exit code
+fn fail(
+    line_number: usize,
+    err: &str,
+    script: &mut ScriptResource,
+    exit: &mut EventWriter<AppExit>,
+) {
+    error!("script line {line_number}: {err}");
+    script.next_step = script.steps.len();
+    exit.send(AppExit::error());
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::cube::{test_utils::headless_app, CubeState};
+
+    use super::{parse_script, Assertion, ScriptLine, ScriptPlugin, ScriptResource, ScriptStep};
+
+    #[test]
+    fn test_parse_script() {
+        let script = "
+            # a demo
+            size 5
+            speed instant
+            scramble seed=7
+
+            apply R U2 R'
+            wait 500ms
+            solve
+            assert not solved
+            exit
+        ";
+
+        let steps: Vec<ScriptStep> = parse_script(script)
+            .unwrap()
+            .into_iter()
+            .map(|script_line| script_line.step)
+            .collect();
+
+        assert_eq!(
+            vec![
+                ScriptStep::Size(5),
+                ScriptStep::Speed(None),
+                ScriptStep::Scramble { seed: Some(7) },
+                ScriptStep::Apply("R U2 R'".to_string()),
+                ScriptStep::Wait(Duration::from_millis(500)),
+                ScriptStep::Solve,
+                ScriptStep::Assert(Assertion::NotSolved),
+                ScriptStep::Exit,
+            ],
+            steps
+        );
+
+        assert_eq!(
+            ScriptLine {
+                line_number: 2,
+                step: ScriptStep::Wait(Duration::from_secs(2)),
+            },
+            parse_script("\nwait 2s").unwrap()[0]
+        );
+    }
+
+    #[test]
+    fn test_parse_script_errors() {
+        assert_eq!(
+            Err("line 2: unknown step \"jump\"".to_string()),
+            parse_script("size 3\njump")
+        );
+        assert!(parse_script("size 0").is_err());
+        assert_eq!(
+            Err("line 1: invalid size \"101\", it can be at most 100".to_string()),
+            parse_script("size 101")
+        );
+        assert!(parse_script("size 100").is_ok());
+        assert!(parse_script("scramble 7").is_err());
+        assert!(parse_script("wait 2").is_err());
+        assert!(parse_script("wait -1s").is_err());
+        assert!(parse_script("wait infs").is_err());
+        assert!(parse_script("wait 1e20s").is_err());
+        assert!(parse_script("speed -1").is_err());
+        assert!(parse_script("apply").is_err());
+        assert!(parse_script("assert happy").is_err());
+        assert!(parse_script("assert state UUU").is_err());
+    }
+
+    /// Runs the script on a headless app until it exits, and returns whether it succeeded
+    fn run_script(script: &str) -> bool {
+        let mut app = headless_app(3);
+        app.add_plugins(ScriptPlugin { path: None })
+            .insert_resource(ScriptResource::new(parse_script(script).unwrap()));
+
+        for _ in 0..2000 {
+            app.update();
+
+            if let Some(exit) = app.should_exit() {
+                return exit.is_success();
+            }
+        }
+
+        panic!("the script did not exit");
+    }
+
+    #[test]
+    fn test_run_script() {
+        let solved_2x2 = CubeState::new(2).to_facelets();
+
+        assert!(run_script(&format!(
+            "
+            size 2
+            assert state {solved_2x2}
+            apply R U
+            assert not solved
+            speed instant
+            apply U' R'
+            wait 1s
+            assert solved
+            scramble seed=3
+            assert not solved
+            exit
+            "
+        )));
+
+        assert!(!run_script("apply R\nassert solved\nexit"));
+        assert!(!run_script("size 4\napply Q\nexit"));
+    }
+}
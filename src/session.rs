@@ -0,0 +1,378 @@
+// Keeps track of timed solves, grouped in sessions, and calculates statistics like the ones used at official (WCA)
+// competitions. Sessions are stored in `sessions.txt` in the storage directory so that they survive restarts.
+
+use bevy::prelude::*;
+
+use crate::{
+    schedules::CubeScheduleSet,
+    timer::{Penalty, SolveFinishedEvent, SolveResult},
+    utils::storage::{self, format_key_values, parse_key_values},
+};
+
+const SESSIONS_FILE: &str = "sessions.txt";
+const SESSIONS_FILE_HEADER: &str = "# Timed solves, grouped per session.
+# Each solve is written as `solve = <seconds> <moves>`, followed by `+2` or `DNF` if it got a penalty.
+";
+
+pub struct SessionPlugin;
+
+impl Plugin for SessionPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(load_sessions()).add_systems(
+            Update,
+            (record_solves, save_sessions)
+                .chain()
+                .in_set(CubeScheduleSet::HandleEvents),
+        );
+    }
+}
+
+/// A time in seconds, or None for a DNF
+pub type FinalTime = Option<f32>;
+
+#[derive(Default)]
+pub struct Session {
+    pub solves: Vec<SolveResult>,
+}
+
+/// All sessions, of which the last one is the current session
+#[derive(Resource)]
+pub struct SessionResource {
+    sessions: Vec<Session>,
+}
+
+impl Default for SessionResource {
+    fn default() -> Self {
+        Self {
+            sessions: vec![Session::default()],
+        }
+    }
+}
+
+impl SessionResource {
+    pub fn current(&self) -> &Session {
+        self.sessions.last().unwrap()
+    }
+
+    /// The number of the current session, starting at 1
+    pub fn current_number(&self) -> usize {
+        self.sessions.len()
+    }
+
+    /// Start a new session, unless the current one is still empty
+    pub fn new_session(&mut self) {
+        if !self.current().solves.is_empty() {
+            self.sessions.push(Session::default());
+        }
+    }
+
+    fn add_solve(&mut self, solve: SolveResult) {
+        self.sessions.last_mut().unwrap().solves.push(solve);
+    }
+
+    fn to_file_contents(&self) -> String {
+        let mut key_values = vec![];
+
+        for (i, session) in self.sessions.iter().enumerate() {
+            key_values.push(("session".to_string(), (i + 1).to_string()));
+
+            for solve in &session.solves {
+                let mut value = format!("{:.3} {}", solve.seconds, solve.moves);
+                match solve.penalty {
+                    Some(Penalty::PlusTwo) => value.push_str(" +2"),
+                    Some(Penalty::Dnf) => value.push_str(" DNF"),
+                    None => (),
+                }
+
+                key_values.push(("solve".to_string(), value));
+            }
+        }
+
+        format!("{SESSIONS_FILE_HEADER}{}", format_key_values(&key_values))
+    }
+
+    fn from_file_contents(contents: &str) -> Self {
+        let mut sessions: Vec<Session> = vec![];
+
+        for (key, value) in parse_key_values(contents) {
+            match key.as_str() {
+                "session" => sessions.push(Session::default()),
+                "solve" => {
+                    let Some(solve) = parse_solve(&value) else {
+                        warn!("sessions: invalid solve \"{value}\"");
+                        continue;
+                    };
+
+                    match sessions.last_mut() {
+                        Some(session) => session.solves.push(solve),
+                        None => sessions.push(Session {
+                            solves: vec![solve],
+                        }),
+                    }
+                }
+                _ => warn!("sessions: unknown key \"{key}\""),
+            }
+        }
+
+        if sessions.is_empty() {
+            return Self::default();
+        }
+
+        Self { sessions }
+    }
+}
+
+fn parse_solve(value: &str) -> Option<SolveResult> {
+    let mut parts = value.split_whitespace();
+
+    let seconds = parts.next()?.parse().ok()?;
+    let moves = parts.next()?.parse().ok()?;
+    let penalty = match parts.next() {
+        None => None,
+        Some("+2") => Some(Penalty::PlusTwo),
+        Some("DNF") => Some(Penalty::Dnf),
+        Some(_) => return None,
+    };
+
+    Some(SolveResult {
+        seconds,
+        penalty,
+        moves,
+    })
+}
+
+pub struct SessionStatistics {
+    pub solves: usize,
+    pub plus_twos: usize,
+    pub dnfs: usize,
+    pub best: Option<f32>,
+    /// Averages of the last 5, 12 and 100 solves. None if there are not enough solves.
+    pub ao5: Option<FinalTime>,
+    pub ao12: Option<FinalTime>,
+    pub ao100: Option<FinalTime>,
+    /// The mean of all solves that are not a DNF
+    pub mean: Option<f32>,
+    pub standard_deviation: Option<f32>,
+    pub mean_moves: Option<f32>,
+    /// Turns per second over all solves that are not a DNF
+    pub tps: Option<f32>,
+}
+
+impl Session {
+    pub fn statistics(&self) -> SessionStatistics {
+        let finished_solves: Vec<&SolveResult> = self
+            .solves
+            .iter()
+            .filter(|solve| solve.penalty != Some(Penalty::Dnf))
+            .collect();
+        let times: Vec<f32> = finished_solves
+            .iter()
+            .filter_map(|solve| solve.final_seconds())
+            .collect();
+
+        let mean = mean(&times);
+        let standard_deviation = mean.filter(|_| times.len() >= 2).map(|mean| {
+            let variance =
+                times.iter().map(|time| (time - mean).powi(2)).sum::<f32>() / times.len() as f32;
+            variance.sqrt()
+        });
+
+        let total_moves: usize = finished_solves.iter().map(|solve| solve.moves).sum();
+        let total_seconds: f32 = finished_solves.iter().map(|solve| solve.seconds).sum();
+
+        SessionStatistics {
+            solves: self.solves.len(),
+            plus_twos: self.count_penalties(Penalty::PlusTwo),
+            dnfs: self.count_penalties(Penalty::Dnf),
+            best: times.iter().copied().reduce(f32::min),
+            ao5: average_of(&self.solves, 5),
+            ao12: average_of(&self.solves, 12),
+            ao100: average_of(&self.solves, 100),
+            mean,
+            standard_deviation,
+            mean_moves: (!finished_solves.is_empty())
+                .then(|| total_moves as f32 / finished_solves.len() as f32),
+            tps: (total_seconds > 0.0).then(|| total_moves as f32 / total_seconds),
+        }
+    }
+
+    fn count_penalties(&self, penalty: Penalty) -> usize {
+        self.solves
+            .iter()
+            .filter(|solve| solve.penalty == Some(penalty))
+            .count()
+    }
+}
+
+fn mean(values: &[f32]) -> Option<f32> {
+    if values.is_empty() {
+        return None;
+    }
+
+    Some(values.iter().sum::<f32>() / values.len() as f32)
+}
+
+/// The average of the last `count` solves, the way it is calculated at official competitions: the best and worst
+/// 5% of the solves (at least 1 each) are left out, and the rest is averaged. DNF's count as the worst solves, so the
+/// average is a DNF if not all of them are left out.
+///
+/// Returns None if there are less than `count` solves.
+pub fn average_of(solves: &[SolveResult], count: usize) -> Option<FinalTime> {
+    if count == 0 || solves.len() < count {
+        return None;
+    }
+
+    let mut times: Vec<f32> = solves[solves.len() - count..]
+        .iter()
+        .map(|solve| solve.final_seconds().unwrap_or(f32::INFINITY))
+        .collect();
+    times.sort_by(f32::total_cmp);
+
+    let trimmed = (count as f32 * 0.05).ceil() as usize;
+    if count <= trimmed * 2 {
+        return None;
+    }
+
+    let counted_times = &times[trimmed..count - trimmed];
+    if counted_times.iter().any(|time| time.is_infinite()) {
+        return Some(None);
+    }
+
+    Some(mean(counted_times))
+}
+
+/// Loads the sessions from disk. A new session gets started for every run of the app.
+fn load_sessions() -> SessionResource {
+    let mut sessions = match storage::read(SESSIONS_FILE) {
+        Ok(contents) => SessionResource::from_file_contents(&contents),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => SessionResource::default(),
+        Err(err) => {
+            warn!("failed to load sessions: {err}");
+            SessionResource::default()
+        }
+    };
+
+    sessions.new_session();
+    sessions
+}
+
+fn record_solves(
+    mut solve_finished_event_reader: EventReader<SolveFinishedEvent>,
+    mut sessions: ResMut<SessionResource>,
+) {
+    for SolveFinishedEvent(result) in solve_finished_event_reader.read() {
+        info!("solve finished: {result}");
+        sessions.add_solve(result.clone());
+    }
+}
+
+fn save_sessions(sessions: Res<SessionResource>) {
+    if !sessions.is_changed() || sessions.is_added() {
+        return;
+    }
+
+    if let Err(err) = storage::write(SESSIONS_FILE, &sessions.to_file_contents()) {
+        error!("failed to save sessions: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::timer::{Penalty, SolveResult};
+
+    use super::{average_of, Session, SessionResource};
+
+    fn solve(seconds: f32, penalty: Option<Penalty>) -> SolveResult {
+        SolveResult {
+            seconds,
+            penalty,
+            moves: 50,
+        }
+    }
+
+    fn solves(times: &[f32]) -> Vec<SolveResult> {
+        times.iter().map(|seconds| solve(*seconds, None)).collect()
+    }
+
+    #[test]
+    fn test_average_of_5() {
+        assert_eq!(None, average_of(&solves(&[10.0, 11.0, 12.0, 13.0]), 5));
+
+        // the best and worst solve are left out
+        assert_eq!(
+            Some(Some(12.0)),
+            average_of(&solves(&[20.0, 11.0, 12.0, 13.0, 1.0]), 5)
+        );
+
+        // only the last 5 solves count
+        assert_eq!(
+            Some(Some(12.0)),
+            average_of(&solves(&[100.0, 20.0, 11.0, 12.0, 13.0, 1.0]), 5)
+        );
+    }
+
+    #[test]
+    fn test_average_of_5_with_penalties() {
+        let mut session = solves(&[10.0, 11.0, 12.0, 13.0]);
+        session.push(solve(0.0, Some(Penalty::Dnf)));
+        // a single DNF is the worst solve, which is left out
+        assert_eq!(Some(Some(12.0)), average_of(&session, 5));
+
+        session[0] = solve(9.0, Some(Penalty::PlusTwo));
+        assert_eq!(Some(Some(12.0)), average_of(&session, 5));
+
+        session[1] = solve(0.0, Some(Penalty::Dnf));
+        assert_eq!(Some(None), average_of(&session, 5));
+    }
+
+    #[test]
+    fn test_average_of_100_trims_5_percent() {
+        let mut times: Vec<f32> = (0..100).map(|i| 10.0 + i as f32).collect();
+        times[0] = 0.0;
+        times[99] = 1000.0;
+
+        // 15.0 up to and including 104.0 are left after leaving out the 5 best and 5 worst solves
+        assert_eq!(Some(Some(59.5)), average_of(&solves(&times), 100));
+    }
+
+    #[test]
+    fn test_session_statistics() {
+        let session = Session {
+            solves: vec![
+                solve(10.0, None),
+                solve(12.0, Some(Penalty::PlusTwo)),
+                solve(0.0, Some(Penalty::Dnf)),
+                solve(18.0, None),
+            ],
+        };
+
+        let statistics = session.statistics();
+        assert_eq!(4, statistics.solves);
+        assert_eq!(1, statistics.plus_twos);
+        assert_eq!(1, statistics.dnfs);
+        assert_eq!(Some(10.0), statistics.best);
+        assert_eq!(Some(14.0), statistics.mean);
+        assert!((statistics.standard_deviation.unwrap() - 3.266).abs() < 0.001);
+        assert_eq!(None, statistics.ao5);
+        assert_eq!(Some(50.0), statistics.mean_moves);
+        assert_eq!(Some(150.0 / 40.0), statistics.tps);
+    }
+
+    #[test]
+    fn test_sessions_file_round_trip() {
+        let mut sessions = SessionResource::default();
+        sessions.add_solve(solve(12.345, None));
+        sessions.new_session();
+        sessions.add_solve(solve(9.5, Some(Penalty::PlusTwo)));
+        sessions.add_solve(solve(0.0, Some(Penalty::Dnf)));
+
+        let loaded_sessions = SessionResource::from_file_contents(&sessions.to_file_contents());
+
+        assert_eq!(2, loaded_sessions.current_number());
+        assert_eq!(
+            sessions.sessions[0].solves,
+            loaded_sessions.sessions[0].solves
+        );
+        assert_eq!(sessions.current().solves, loaded_sessions.current().solves);
+    }
+}
@@ -0,0 +1,401 @@
+// Times solves the way it is done at official (WCA) competitions: after scrambling there are 15 seconds of
+// inspection, after which the solve has to be started. Starting within 17 seconds gives a +2 penalty, starting even
+// later results in a DNF (did not finish).
+
+use bevy::prelude::*;
+
+use crate::{
+    cube::{Cube, CubeRotationEvent, CubeState, Rotation, SequenceResource, SetCubeStateEvent},
+    schedules::CubeScheduleSet,
+};
+
+pub struct TimerPlugin;
+
+impl Plugin for TimerPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SolveTimerResource::default())
+            .add_event::<SolveFinishedEvent>()
+            .add_systems(Update, tick_solve_timer.in_set(CubeScheduleSet::Timers))
+            .add_systems(
+                Update,
+                handle_solve_timer_rotation_events.in_set(CubeScheduleSet::UpdateAnimations),
+            );
+    }
+}
+
+pub const INSPECTION_SECONDS: f32 = 15.0;
+/// Starting the solve after the inspection time but within this time results in a +2 penalty
+const INSPECTION_PENALTY_SECONDS: f32 = 17.0;
+const PLUS_TWO_PENALTY_SECONDS: f32 = 2.0;
+/// The moments during inspection at which the solver gets warned, in seconds
+const INSPECTION_WARNINGS: [f32; 2] = [8.0, 12.0];
+
+/// Sent when a timed solve is done, also when it results in a DNF
+#[derive(Event)]
+pub struct SolveFinishedEvent(pub SolveResult);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Penalty {
+    PlusTwo,
+    Dnf,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SolveResult {
+    /// The time it took to solve the cube, without the penalty
+    pub seconds: f32,
+    pub penalty: Option<Penalty>,
+    /// The number of turns, not counting rotations of the whole cube
+    pub moves: usize,
+}
+
+impl SolveResult {
+    /// The time including the penalty. Returns None for a DNF.
+    pub fn final_seconds(&self) -> Option<f32> {
+        match self.penalty {
+            None => Some(self.seconds),
+            Some(Penalty::PlusTwo) => Some(self.seconds + PLUS_TWO_PENALTY_SECONDS),
+            Some(Penalty::Dnf) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for SolveResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.final_seconds(), self.penalty) {
+            (None, _) => write!(f, "DNF"),
+            (Some(seconds), Some(Penalty::PlusTwo)) => write!(f, "{}+", format_time(seconds)),
+            (Some(seconds), _) => write!(f, "{}", format_time(seconds)),
+        }
+    }
+}
+
+/// Format seconds like cube timers do, for example `9.87` or `1:02.35`
+pub fn format_time(seconds: f32) -> String {
+    let hundredths = (seconds * 100.0).round() as u32;
+    let minutes = hundredths / 6000;
+    let seconds = hundredths % 6000;
+
+    if minutes > 0 {
+        format!("{minutes}:{:02}.{:02}", seconds / 100, seconds % 100)
+    } else {
+        format!("{}.{:02}", seconds / 100, seconds % 100)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TimerState {
+    Idle,
+    /// Inspection starts once the cube is done scrambling
+    WaitingForScramble,
+    Inspecting {
+        elapsed: f32,
+    },
+    Solving {
+        elapsed: f32,
+        penalty: Option<Penalty>,
+        moves: usize,
+    },
+    Finished(SolveResult),
+}
+
+#[derive(Resource)]
+pub struct SolveTimerResource {
+    /// Whether scrambles should start a timed solve
+    pub enabled: bool,
+    state: TimerState,
+}
+
+impl Default for SolveTimerResource {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            state: TimerState::Idle,
+        }
+    }
+}
+
+impl SolveTimerResource {
+    pub fn state(&self) -> &TimerState {
+        &self.state
+    }
+
+    /// Whether an inspection or solve is in progress
+    pub fn is_timing(&self) -> bool {
+        matches!(
+            self.state,
+            TimerState::Inspecting { .. } | TimerState::Solving { .. }
+        )
+    }
+
+    /// Start inspection once the scramble that is about to start is done
+    pub fn wait_for_scramble(&mut self) {
+        self.state = TimerState::WaitingForScramble;
+    }
+
+    pub fn cancel(&mut self) {
+        self.state = TimerState::Idle;
+    }
+
+    fn start_inspection(&mut self) {
+        self.state = TimerState::Inspecting { elapsed: 0.0 };
+    }
+
+    /// Returns the result if the solve got finished because inspection took too long
+    fn tick(&mut self, delta_seconds: f32) -> Option<SolveResult> {
+        match &mut self.state {
+            TimerState::Inspecting { elapsed } => {
+                *elapsed += delta_seconds;
+
+                if *elapsed > INSPECTION_PENALTY_SECONDS {
+                    return Some(self.finish(0.0, Some(Penalty::Dnf), 0));
+                }
+
+                None
+            }
+            TimerState::Solving { elapsed, .. } => {
+                *elapsed += delta_seconds;
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// The solve starts with the first turn after inspection. Turning the whole cube is allowed during inspection.
+    fn on_turn(&mut self, rotation: &Rotation) {
+        if let Rotation::Cube(_) = rotation {
+            return;
+        }
+
+        match &mut self.state {
+            TimerState::Inspecting { elapsed } => {
+                let penalty = if *elapsed <= INSPECTION_SECONDS {
+                    None
+                } else {
+                    Some(Penalty::PlusTwo)
+                };
+
+                self.state = TimerState::Solving {
+                    elapsed: 0.0,
+                    penalty,
+                    moves: 1,
+                };
+            }
+            TimerState::Solving { moves, .. } => *moves += 1,
+            _ => (),
+        }
+    }
+
+    /// Returns the result if this finished the solve
+    fn on_solved(&mut self) -> Option<SolveResult> {
+        let TimerState::Solving {
+            elapsed,
+            penalty,
+            moves,
+        } = self.state
+        else {
+            return None;
+        };
+
+        Some(self.finish(elapsed, penalty, moves))
+    }
+
+    fn finish(&mut self, seconds: f32, penalty: Option<Penalty>, moves: usize) -> SolveResult {
+        let result = SolveResult {
+            seconds,
+            penalty,
+            moves,
+        };
+        self.state = TimerState::Finished(result.clone());
+        result
+    }
+
+    /// The last inspection warning that has been passed, in seconds since the start of the inspection
+    pub fn inspection_warning(&self) -> Option<f32> {
+        let TimerState::Inspecting { elapsed } = self.state else {
+            return None;
+        };
+
+        INSPECTION_WARNINGS
+            .iter()
+            .rev()
+            .find(|warning| elapsed >= **warning)
+            .copied()
+    }
+}
+
+fn tick_solve_timer(
+    mut solve_timer: ResMut<SolveTimerResource>,
+    mut solve_finished_event_writer: EventWriter<SolveFinishedEvent>,
+    cube_query: Query<Ref<Cube>>,
+    sequence_resource: Res<SequenceResource>,
+    mut set_cube_state_event_reader: EventReader<SetCubeStateEvent>,
+    time: Res<Time>,
+) {
+    let did_set_cube_state = set_cube_state_event_reader.read().count() > 0;
+
+    if !solve_timer.enabled {
+        if solve_timer.state != TimerState::Idle {
+            solve_timer.cancel();
+        }
+        return;
+    }
+
+    let Ok(cube) = cube_query.get_single() else {
+        // the cube might be respawning
+        return;
+    };
+
+    if cube.is_added() && solve_timer.is_timing() {
+        // the cube got replaced by a solved one
+        solve_timer.cancel();
+        return;
+    }
+
+    if did_set_cube_state
+        && (solve_timer.is_timing() || solve_timer.state == TimerState::WaitingForScramble)
+    {
+        // the cube is not in the scrambled state anymore
+        solve_timer.cancel();
+        return;
+    }
+
+    if solve_timer.state == TimerState::WaitingForScramble
+        && sequence_resource.is_done()
+        && !cube.is_animating_rotation
+    {
+        solve_timer.start_inspection();
+        return;
+    }
+
+    if solve_timer.is_timing() && !sequence_resource.is_done() {
+        // a sequence, like a solve, got started during the timed solve
+        solve_timer.cancel();
+        return;
+    }
+
+    if let Some(result) = solve_timer.tick(time.delta_secs()) {
+        solve_finished_event_writer.send(SolveFinishedEvent(result));
+    }
+}
+
+fn handle_solve_timer_rotation_events(
+    mut solve_timer: ResMut<SolveTimerResource>,
+    mut rotation_event_reader: EventReader<CubeRotationEvent>,
+    mut solve_finished_event_writer: EventWriter<SolveFinishedEvent>,
+    cube_state_query: Query<&CubeState>,
+) {
+    if !solve_timer.is_timing() {
+        rotation_event_reader.clear();
+        return;
+    }
+
+    for rotation_event in rotation_event_reader.read() {
+        solve_timer.on_turn(&rotation_event.rotation);
+    }
+
+    if !matches!(solve_timer.state(), TimerState::Solving { .. }) {
+        return;
+    }
+
+    let Ok(cube_state) = cube_state_query.get_single() else {
+        error!("expected exactly 1 CubeState component");
+        return;
+    };
+
+    // The cube state gets updated when a rotation starts, so this stops the timer at the start of the last turn.
+    if cube_state.is_solved() {
+        if let Some(result) = solve_timer.on_solved() {
+            solve_finished_event_writer.send(SolveFinishedEvent(result));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cube::Rotation;
+
+    use super::{format_time, Penalty, SolveResult, SolveTimerResource, TimerState};
+
+    fn inspecting_timer() -> SolveTimerResource {
+        let mut solve_timer = SolveTimerResource {
+            enabled: true,
+            ..Default::default()
+        };
+        solve_timer.wait_for_scramble();
+        solve_timer.start_inspection();
+        solve_timer
+    }
+
+    #[test]
+    fn test_timed_solve() {
+        let mut solve_timer = inspecting_timer();
+        solve_timer.tick(5.0);
+
+        // turning the whole cube does not start the solve
+        solve_timer.on_turn(&Rotation::cube_x());
+        assert_eq!(
+            TimerState::Inspecting { elapsed: 5.0 },
+            *solve_timer.state()
+        );
+
+        solve_timer.on_turn(&Rotation::face_x(1));
+        solve_timer.tick(10.5);
+        solve_timer.on_turn(&Rotation::face_y(1));
+
+        assert_eq!(
+            Some(SolveResult {
+                seconds: 10.5,
+                penalty: None,
+                moves: 2,
+            }),
+            solve_timer.on_solved()
+        );
+        assert!(!solve_timer.is_timing());
+        assert_eq!(None, solve_timer.on_solved());
+    }
+
+    #[test]
+    fn test_inspection_penalties() {
+        let mut solve_timer = inspecting_timer();
+        solve_timer.tick(16.0);
+        solve_timer.on_turn(&Rotation::face_x(1));
+        solve_timer.tick(10.0);
+        let result = solve_timer.on_solved().unwrap();
+        assert_eq!(Some(Penalty::PlusTwo), result.penalty);
+        assert_eq!(Some(12.0), result.final_seconds());
+
+        let mut solve_timer = inspecting_timer();
+        assert_eq!(None, solve_timer.tick(17.0));
+        let result = solve_timer.tick(0.1).unwrap();
+        assert_eq!(Some(Penalty::Dnf), result.penalty);
+        assert_eq!(None, result.final_seconds());
+        assert_eq!(TimerState::Finished(result), *solve_timer.state());
+    }
+
+    #[test]
+    fn test_inspection_warnings() {
+        let mut solve_timer = inspecting_timer();
+        assert_eq!(None, solve_timer.inspection_warning());
+
+        solve_timer.tick(8.0);
+        assert_eq!(Some(8.0), solve_timer.inspection_warning());
+
+        solve_timer.tick(4.5);
+        assert_eq!(Some(12.0), solve_timer.inspection_warning());
+    }
+
+    #[test]
+    fn test_format_time() {
+        assert_eq!("0.00", format_time(0.0));
+        assert_eq!("9.87", format_time(9.87));
+        assert_eq!("1:02.35", format_time(62.35));
+
+        let result = SolveResult {
+            seconds: 9.5,
+            penalty: Some(Penalty::PlusTwo),
+            moves: 40,
+        };
+        assert_eq!("11.50+", result.to_string());
+    }
+}
@@ -0,0 +1,6 @@
+// Command line arguments of the game, like `--control-port 7070`
+
+/// The value that follows the argument with the given name, if any
+pub fn value_of(args: impl Iterator<Item = String>, name: &str) -> Option<String> {
+    args.skip_while(|arg| arg != name).nth(1)
+}
@@ -1,2 +1,2 @@
-#[allow(dead_code)]
-pub mod console;
+pub mod args;
+pub mod storage;
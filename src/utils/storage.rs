@@ -0,0 +1,108 @@
+// Stores user data, like settings, in files in the `.rubiks-cube` directory of the home directory of the user.
+// Settings are stored as `key = value` lines, so that they are easy to edit by hand.
+
+use std::{fs, io, path::PathBuf};
+
+const SETTINGS_FILE: &str = "settings.txt";
+
+fn storage_dir() -> PathBuf {
+    match std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")) {
+        Some(home_dir) => PathBuf::from(home_dir).join(".rubiks-cube"),
+        None => PathBuf::from(".rubiks-cube"),
+    }
+}
+
+/// The full path of a file in the storage directory
+pub fn path(file_name: &str) -> PathBuf {
+    storage_dir().join(file_name)
+}
+
+pub fn read(file_name: &str) -> io::Result<String> {
+    fs::read_to_string(storage_dir().join(file_name))
+}
+
+pub fn write(file_name: &str, contents: &str) -> io::Result<()> {
+    let dir = storage_dir();
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join(file_name), contents)
+}
+
+/// Returns None if the setting was never saved
+pub fn load_setting(key: &str) -> Option<String> {
+    let contents = read(SETTINGS_FILE).ok()?;
+
+    parse_key_values(&contents)
+        .into_iter()
+        .find(|(setting_key, _)| setting_key == key)
+        .map(|(_, value)| value)
+}
+
+/// Saves the setting, keeping all other settings as they are
+pub fn save_setting(key: &str, value: &str) -> io::Result<()> {
+    let mut settings = match read(SETTINGS_FILE) {
+        Ok(contents) => parse_key_values(&contents),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => vec![],
+        Err(err) => return Err(err),
+    };
+
+    match settings
+        .iter_mut()
+        .find(|(setting_key, _)| setting_key == key)
+    {
+        Some((_, setting_value)) => *setting_value = value.to_string(),
+        None => settings.push((key.to_string(), value.to_string())),
+    }
+
+    write(SETTINGS_FILE, &format_key_values(&settings))
+}
+
+/// Parses lines in the format `key = value`. Empty lines and lines starting with `#` are ignored.
+pub fn parse_key_values(contents: &str) -> Vec<(String, String)> {
+    contents
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            Some((key.trim().to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+pub fn format_key_values(key_values: &[(String, String)]) -> String {
+    key_values
+        .iter()
+        .map(|(key, value)| format!("{key} = {value}\n"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_key_values, parse_key_values};
+
+    #[test]
+    fn test_key_values_round_trip() {
+        let key_values = vec![
+            ("animation_profile".to_string(), "smooth".to_string()),
+            ("empty".to_string(), "".to_string()),
+        ];
+
+        assert_eq!(
+            key_values,
+            parse_key_values(&format_key_values(&key_values))
+        );
+    }
+
+    #[test]
+    fn test_parse_key_values() {
+        let contents = "# a comment\n\n  a =1 \nno separator\nb = x = y\n";
+
+        assert_eq!(
+            vec![
+                ("a".to_string(), "1".to_string()),
+                ("b".to_string(), "x = y".to_string()),
+            ],
+            parse_key_values(contents)
+        );
+    }
+}